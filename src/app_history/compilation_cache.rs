@@ -0,0 +1,157 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+use crate::app_history::compiler_queues::CompilationRequest;
+use crate::db::config::consumed_capacity::{register_cache_hit, with_compilation_reads};
+
+/// Chave do cache: dispositivo, dia e hash da configuração do compilador (parâmetros da requisição).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompilationCacheKey {
+    pub dev_id: String,
+    pub day: String,
+    pub config_hash: u64,
+}
+
+impl CompilationCacheKey {
+    /// São cacheados os compiladores diários de dispositivo: DACs e DUTs (eficiência energética, disponibilidade
+    /// e totalização L1), DRIs (disponibilidade e scripts de chiller), DMTs, DALs e DAMs.
+    /// Ficam de fora os medidores de energia, cuja consulta cobre intervalos de vários dias na verificação de
+    /// histórico salvo e tem um único consumidor, e os DMAs, compilados hora a hora.
+    pub fn from_request(request: &CompilationRequest) -> Option<Self> {
+        let (dev_id, day, config) = match request {
+            CompilationRequest::CompDac(params) => (&params.dev_id, params.ts_ini.get(0..10)?.to_owned(), serde_json::to_string(params).ok()?),
+            CompilationRequest::CompDut(params) => (&params.dev_id, params.ts_ini.get(0..10)?.to_owned(), serde_json::to_string(params).ok()?),
+            CompilationRequest::CompDri(params) => (&params.dev_id, params.day.format("%Y-%m-%d").to_string(), serde_json::to_string(params).ok()?),
+            CompilationRequest::CompDmt(params) => (&params.dev_id, params.ts_ini.get(0..10)?.to_owned(), serde_json::to_string(params).ok()?),
+            CompilationRequest::CompDal(params) => (&params.dev_id, params.ts_ini.get(0..10)?.to_owned(), serde_json::to_string(params).ok()?),
+            CompilationRequest::CompDam(params) => (&params.dev_id, params.ts_ini.get(0..10)?.to_owned(), serde_json::to_string(params).ok()?),
+            CompilationRequest::CompDma(_) | CompilationRequest::EnergyQuery(_) => return None,
+        };
+
+        let mut hasher = DefaultHasher::new();
+        config.hash(&mut hasher);
+
+        Some(Self {
+            dev_id: dev_id.to_owned(),
+            day,
+            config_hash: hasher.finish(),
+        })
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}_{:016x}.json", self.dev_id, self.config_hash)
+    }
+}
+
+enum CachedCompilation {
+    Memory(String),
+    Disk(PathBuf),
+}
+
+struct CacheEntry {
+    compilation: CachedCompilation,
+    // RCUs por tabela lidas para compilar, registradas a cada reaproveitamento
+    reads: HashMap<String, f64>,
+}
+
+/// Cache dos resultados de compilação de telemetrias durante o processamento de um dia.
+/// Cada entrada é preenchida uma única vez, mesmo com vários consumidores concorrentes pedindo o mesmo
+/// dispositivo. Se `spill_dir` estiver configurado, os resultados ficam em disco e só o caminho fica em memória.
+/// Execuções do mesmo dia podem se sobrepor (noturna e reprocessamento via HTTP), por isso cada dia conta as
+/// execuções ativas e só é descartado quando a última termina.
+pub struct CompilationCache {
+    entries: Mutex<HashMap<CompilationCacheKey, Arc<OnceCell<CacheEntry>>>>,
+    active_days: Mutex<HashMap<String, usize>>,
+    spill_dir: Option<PathBuf>,
+}
+
+impl CompilationCache {
+    pub fn new(spill_dir: Option<String>) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            active_days: Mutex::new(HashMap::new()),
+            spill_dir: spill_dir.filter(|dir| !dir.is_empty()).map(PathBuf::from),
+        }
+    }
+
+    pub async fn get_or_compile<F, Fut>(&self, key: CompilationCacheKey, compile: F) -> Result<String, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String, String>>,
+    {
+        let cell = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.entry(key.clone()).or_insert_with(|| Arc::new(OnceCell::new())).clone()
+        };
+
+        // A compilação é atribuída ao pipeline que a disparou; os demais registram o reaproveitamento
+        let compiled = AtomicBool::new(false);
+        let cached = cell.get_or_try_init(|| async {
+            compiled.store(true, Ordering::Relaxed);
+            let (result, reads) = with_compilation_reads(compile()).await;
+            Ok::<_, String>(CacheEntry { compilation: self.store(&key, result?)?, reads })
+        }).await?;
+
+        if !compiled.load(Ordering::Relaxed) {
+            register_cache_hit(&cached.reads);
+        }
+
+        match &cached.compilation {
+            CachedCompilation::Memory(result) => Ok(result.clone()),
+            CachedCompilation::Disk(path) => std::fs::read_to_string(path).map_err(|err| format!("[{:?}]: {}", path, err)),
+        }
+    }
+
+    fn store(&self, key: &CompilationCacheKey, result: String) -> Result<CachedCompilation, String> {
+        let Some(spill_dir) = &self.spill_dir else {
+            return Ok(CachedCompilation::Memory(result));
+        };
+
+        let day_dir = spill_dir.join(&key.day);
+        let path = day_dir.join(key.file_name());
+        let written = std::fs::create_dir_all(&day_dir).and_then(|_| std::fs::write(&path, &result));
+        match written {
+            Ok(()) => Ok(CachedCompilation::Disk(path)),
+            Err(err) => {
+                eprintln!("Erro ao gravar cache de compilação em disco [{:?}]: {}", path, err);
+                Ok(CachedCompilation::Memory(result))
+            }
+        }
+    }
+
+    /// Registra o início de uma execução do dia
+    pub fn begin_day(&self, day: &str) {
+        *self.active_days.lock().unwrap().entry(day.to_owned()).or_insert(0) += 1;
+    }
+
+    /// Registra o fim de uma execução do dia. As entradas do dia são descartadas quando não há outra execução
+    /// ativa; o lock é mantido durante a limpeza para que uma nova execução não comece no meio dela.
+    pub fn end_day(&self, day: &str) {
+        let mut active_days = self.active_days.lock().unwrap();
+        match active_days.get_mut(day) {
+            Some(active) if *active > 1 => {
+                *active -= 1;
+                return;
+            },
+            _ => {
+                active_days.remove(day);
+            },
+        }
+
+        self.entries.lock().unwrap().retain(|key, _| key.day != day);
+
+        if let Some(spill_dir) = &self.spill_dir {
+            let day_dir = spill_dir.join(day);
+            if day_dir.exists() {
+                if let Err(err) = std::fs::remove_dir_all(&day_dir) {
+                    eprintln!("Erro ao remover cache de compilação [{:?}]: {}", day_dir, err);
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app_history::{ dma_hist, energy_hist, dut_hist, dac_hist, dri_hist, dmt_hist, dal_hist, dam_hist };
+use crate::app_history::compilation_cache::CompilationCacheKey;
 use crate::GlobalVars;
 use std::error::Error;
 use std::sync::Arc;
@@ -16,7 +17,14 @@ pub enum CompilationRequest {
 }
 
 pub async fn task_queue_manager(request: CompilationRequest, globs: &Arc<GlobalVars>) -> Result<String, Box<dyn Error>>{
-    let response = match executar_requisicao(request, globs).await {
+    let response = match CompilationCacheKey::from_request(&request) {
+        Some(key) => globs.compilation_cache.get_or_compile(key, || async {
+            executar_requisicao(request, globs).await.map_err(|err| err.to_string())
+        }).await,
+        None => executar_requisicao(request, globs).await.map_err(|err| err.to_string()),
+    };
+
+    let response = match response {
         Ok(v) => v,
         Err(err) => return Err(format!("Erro ao executar requisição, {}", err).into()),
     };
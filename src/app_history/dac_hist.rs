@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::{error::Error, sync::Arc};
 
 use crate::{
-    compression::compiler_DAC::DACTelemetryCompiler, db::config::dynamo::QuerierDevIdTimestamp, models::external_models::device::DacDevice, telemetry_payloads::{dac_payload_json::get_raw_telemetry_pack_dac, dac_telemetry::{split_pack, HwInfoDAC, T_sensor_cfg, T_sensors}}, GlobalVars
+    compression::compiler_DAC::DACTelemetryCompiler, db::config::{table_resolution::DeviceType, telemetry_source::{TelemetryQuerier, TelemetrySource}}, models::external_models::device::DacDevice, telemetry_payloads::{dac_payload_json::get_raw_telemetry_pack_dac, dac_telemetry::{split_pack, HwInfoDAC, T_sensor_cfg, T_sensors}}, GlobalVars
 };
 
 pub fn parse_parameters_dac(
@@ -148,41 +148,16 @@ pub async fn process_comp_command_dac_v2(
     let mut page_ts_ini = accs.page_ts_ini;
     let mut tcomp = accs.tcomp;
 
-    let mut table_name = {
-        if (dev_id.len() == 12) && dev_id.to_uppercase().starts_with("DAC") {
-            format!("{}XXXX_RAW", &dev_id.to_uppercase()[0..8])
-        } else {
-            String::new()
+    let querier = match TelemetryQuerier::for_device(globs, DeviceType::Dac, &dev_id) {
+        Some(querier) => querier,
+        None => {
+            println!("Unknown DAC generation: {}", dev_id);
+            return Ok("{}".to_string());
         }
     };
-
-    for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DAC {
-        if dev_id.to_uppercase().starts_with(&custom.dev_prefix) {
-            table_name = custom.table_name.to_owned();
-            break;
-        }
-    }
-
-    if table_name.len() == 0 {
-        println!("Unknown DAC generation: {}", dev_id);
-        return Ok("{}".to_string());
-    }
     let mut dac_state =
         crate::telemetry_payloads::dac_l1::dac_l1_calculator::create_l1_calculator(&hw_cfg);
 
-    let querier = if table_name == "DAC20719XXXX_RAW" {
-        QuerierDevIdTimestamp::new_custom(
-            table_name,
-            "dac_id".to_owned(),
-            "timestamp".to_owned(),
-            dev_id.clone(),
-        )
-    } else {
-        QuerierDevIdTimestamp::new_diel_dev(
-            table_name,
-            dev_id.clone(),
-        )
-    };
     let mut found_invalid_payload = false;
     let result = querier
         .run(&ts_ini, &ts_end, &mut |items| {
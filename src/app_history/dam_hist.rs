@@ -4,7 +4,7 @@ use chrono::NaiveDateTime;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::{compression::compiler_DAM::DAMTelemetryCompiler, db::config::dynamo::QuerierDevIdTimestamp, telemetry_payloads::{dam_payload_json::get_raw_telemetry_pack_dam, dam_telemetry::split_pack}, GlobalVars};
+use crate::{compression::compiler_DAM::DAMTelemetryCompiler, db::config::{table_resolution::DeviceType, telemetry_source::{TelemetryQuerier, TelemetrySource}}, telemetry_payloads::{dam_payload_json::get_raw_telemetry_pack_dam, dam_telemetry::split_pack}, GlobalVars};
 
 pub fn parse_parameters_dam(day: &str, dev_id: &str, check_minutes_offline: Option<i32>) -> Result<ReqParameters, Box<dyn Error>> {
     if dev_id.len() < 9 {
@@ -68,30 +68,14 @@ pub async fn process_comp_command_dam(
     let page_ts_ini = accs.page_ts_ini;
     let mut tcomp = accs.tcomp;
 
-    let mut table_name = {
-        if (dev_id.len() == 12) && dev_id.to_uppercase().starts_with("DAM") {
-            format!("{}XXXX_RAW", &dev_id.to_uppercase()[0..8])
-        } else {
-            String::new()
+    let querier = match TelemetryQuerier::for_device(globs, DeviceType::Dam, &dev_id) {
+        Some(querier) => querier,
+        None => {
+            println!("Unknown DAM generation: {}", dev_id);
+            return Ok("{}".to_string());
         }
     };
 
-    for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DAM {
-        if dev_id.to_uppercase().starts_with(&custom.dev_prefix) {
-            table_name = custom.table_name.to_owned();
-            break;
-        }
-    }
-
-    if table_name.len() == 0 {
-        println!("Unknown DAM generation: {}", dev_id);
-        return Ok("{}".to_string());
-    }
-
-    let querier = QuerierDevIdTimestamp::new_diel_dev(
-        table_name,
-        dev_id.clone(),
-    );
     let mut found_invalid_payload = false;
     let result = querier
         .run(&ts_ini, &ts_end, &mut |items| {
@@ -4,7 +4,8 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use crate::compression::compiler_DMA::DMATelemetryCompiler;
 use crate::telemetry_payloads::dma_payload_json::get_raw_telemetry_pack_dma;
-use crate::db::config::dynamo::QuerierDevIdTimestamp;
+use crate::db::config::telemetry_source::{TelemetryQuerier, TelemetrySource};
+use crate::db::config::table_resolution::DeviceType;
 use crate::telemetry_payloads::dma_telemetry::{ split_pack };
 use crate::GlobalVars;
 use std::collections::HashMap;
@@ -46,27 +47,13 @@ pub async fn process_comp_command_dma (rpars: ReqParameters, globs: &Arc<GlobalV
     let page_ts_ini = accs.page_ts_ini;
     let mut tcomp = accs.tcomp;
   
-    let mut table_name = {
-      if (dev_id.len() == 12) && dev_id.to_uppercase().starts_with("DMA") {
-        format!("{}XXXX_RAW", &dev_id.to_uppercase()[0..8])
-      } else {
-        String::new()
-      }
-    };
-    
-    for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DMA {
-      if dev_id.to_uppercase().starts_with(&custom.dev_prefix) {
-        table_name = custom.table_name.to_owned();
-        break;
-      }
-    }
-  
-    if table_name.len() == 0 {
+    let querier = match TelemetryQuerier::for_device(globs, DeviceType::Dma, &dev_id) {
+      Some(querier) => querier,
+      None => {
         println!("Unknown DMA generation: {}", dev_id);
         return Ok("{}".to_string());
-    }
-  
-    let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, dev_id.clone());
+      }
+    };
   
     let mut found_invalid_payload = false;
     let mut is_first_of_the_day: bool = true;
@@ -4,7 +4,7 @@ use chrono::{ NaiveDate, NaiveDateTime};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::{compression::compiler_DRI::{DRICCNCompiledPeriod, DRICCNTelemetryCompiler, DRIVAVandFancoilCompiledPeriod, DRIVAVandFancoilTelemetryCompiler}, db::config::dynamo::QuerierDevIdTimestamp, models::external_models::device::DriDevice, telemetry_payloads::dri_telemetry::{split_pack_ccn, split_pack_vav_and_fancoil, DriCCNTelemetry, DriChillerCarrierHXTelemetry, DriChillerCarrierXAHvarTelemetry, DriChillerCarrierXATelemetry, DriVAVandFancoilTelemetry, TelemetryDri, TelemetryDriChillerCarrierHX, TelemetryDriChillerCarrierXA, TelemetryDriChillerCarrierXAHvar}, GlobalVars};
+use crate::{compression::compiler_DRI::{DRICCNCompiledPeriod, DRICCNTelemetryCompiler, DRIVAVandFancoilCompiledPeriod, DRIVAVandFancoilTelemetryCompiler}, db::config::{table_resolution::DeviceType, telemetry_source::{TelemetryQuerier, TelemetrySource}}, models::external_models::device::DriDevice, telemetry_payloads::dri_telemetry::{split_pack_ccn, split_pack_vav_and_fancoil, DriCCNTelemetry, DriChillerCarrierHXTelemetry, DriChillerCarrierXAHvarTelemetry, DriChillerCarrierXATelemetry, DriVAVandFancoilTelemetry, TelemetryDri, TelemetryDriChillerCarrierHX, TelemetryDriChillerCarrierXA, TelemetryDriChillerCarrierXAHvar}, GlobalVars};
 
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,25 +48,8 @@ impl DriHistParams {
     }
 
     async fn process_ccn_query(&self, globs: &Arc<GlobalVars>) -> Result<Option<String>, String> {
-        let dev_id_upper = self.dev_id.to_uppercase();
-        let mut table_name = {
-            if (self.dev_id.len() == 12) && dev_id_upper.starts_with("DRI") {
-                format!("{}XXXX_RAW", &dev_id_upper[0..8])
-            } else {
-                String::new()
-            }
-        };
-
-        for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DRI {
-            if dev_id_upper.starts_with(&custom.dev_prefix) {
-                table_name = custom.table_name.to_owned();
-                break;
-            }
-        }
-
-        if table_name.is_empty() {
-            return Err(format!("Unknown DRI generation: {}", self.dev_id));
-        }
+        let querier = TelemetryQuerier::for_device(globs, DeviceType::Dri, &self.dev_id)
+            .ok_or_else(|| format!("Unknown DRI generation: {}", self.dev_id))?;
 
         let interval_length_s = 24 * 60 * 60;
         let (ts_ini, ts_end) = {
@@ -80,7 +63,6 @@ impl DriHistParams {
 
         let mut tcomp = DRICCNTelemetryCompiler::new(self.dri_interval);
 
-        let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, self.dev_id.to_owned());
         let mut final_tels = Vec::new();
         querier.run(&ts_ini, &ts_end, &mut |items: Vec<TelemetryDri>| {
             let mut x = items.into_iter()
@@ -135,25 +117,8 @@ impl DriHistParams {
         Ok(result.unwrap())
     }
     async fn process_vav_and_fancoil_query(&self, globs: &Arc<GlobalVars>) -> Result<Option<String>, String> {
-        let dev_id_upper = self.dev_id.to_uppercase();
-        let mut table_name = {
-            if (self.dev_id.len() == 12) && dev_id_upper.starts_with("DRI") {
-                format!("{}XXXX_RAW", &dev_id_upper[0..8])
-            } else {
-                String::new()
-            }
-        };
-
-        for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DRI {
-            if dev_id_upper.starts_with(&custom.dev_prefix) {
-                table_name = custom.table_name.to_owned();
-                break;
-            }
-        }
-
-        if table_name.is_empty() {
-            return Err(format!("Unknown DRI generation: {}", self.dev_id));
-        }
+        let querier = TelemetryQuerier::for_device(globs, DeviceType::Dri, &self.dev_id)
+            .ok_or_else(|| format!("Unknown DRI generation: {}", self.dev_id))?;
 
         let interval_length_s = 24 * 60 * 60;
         let (ts_ini, ts_end) = {
@@ -166,7 +131,6 @@ impl DriHistParams {
 
         let mut tcomp = DRIVAVandFancoilTelemetryCompiler::new(self.dri_interval);
 
-        let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, self.dev_id.to_owned());
         let mut final_tels = Vec::new();
         querier.run(&ts_ini, &ts_end, &mut |items: Vec<TelemetryDri>| {
             let mut x = items.into_iter()
@@ -223,25 +187,8 @@ impl DriHistParams {
     }
 
     async fn process_chiller_carrier_query(&self, globs: &Arc<GlobalVars>) -> Result<Option<String>, String> {
-        let dev_id_upper = self.dev_id.to_uppercase();
-        let mut table_name = {
-            if (self.dev_id.len() == 12) && dev_id_upper.starts_with("DRI") {
-                format!("{}XXXX_RAW", &dev_id_upper[0..8])
-            } else {
-                String::new()
-            }
-        };
-
-        for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DRI {
-            if dev_id_upper.starts_with(&custom.dev_prefix) {
-                table_name = custom.table_name.to_owned();
-                break;
-            }
-        }
-
-        if table_name.is_empty() {
-            return Err(format!("Unknown DRI generation: {}", self.dev_id));
-        }
+        let querier = TelemetryQuerier::for_device(globs, DeviceType::Dri, &self.dev_id)
+            .ok_or_else(|| format!("Unknown DRI generation: {}", self.dev_id))?;
 
         let interval_length_s = 24 * 60 * 60;
         let (ts_ini, ts_end) = {
@@ -253,22 +200,21 @@ impl DriHistParams {
         };
 
         if &self.dri_type == "CHILLER_CARRIER_XA_HVAR" {
-            let final_tels = self.process_chiller_carrier_xa_hvar_query(globs, ts_ini, ts_end, table_name).await?;
+            let final_tels = self.process_chiller_carrier_xa_hvar_query(globs, ts_ini, ts_end, querier).await?;
             Ok(Some(serde_json::to_string(&final_tels).unwrap()))
         } else if &self.dri_type == "CHILLER_CARRIER_XA" {
-            let final_tels = self.process_chiller_carrier_xa_query(globs, ts_ini, ts_end, table_name).await?;
+            let final_tels = self.process_chiller_carrier_xa_query(globs, ts_ini, ts_end, querier).await?;
             Ok(Some(serde_json::to_string(&final_tels).unwrap()))
         }
         else {
-            let final_tels = self.process_chiller_carrier_hx_query(globs, ts_ini, ts_end, table_name).await?;
+            let final_tels = self.process_chiller_carrier_hx_query(globs, ts_ini, ts_end, querier).await?;
             Ok(Some(serde_json::to_string(&final_tels).unwrap()))
         } 
 
 
     }
 
-    async fn process_chiller_carrier_hx_query(&self, globs: &Arc<GlobalVars>, ts_ini: String, ts_end: String, table_name: String) -> Result<Vec<DriChillerCarrierHXTelemetry>, String> {
-        let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, self.dev_id.to_owned());
+    async fn process_chiller_carrier_hx_query(&self, globs: &Arc<GlobalVars>, ts_ini: String, ts_end: String, querier: TelemetryQuerier) -> Result<Vec<DriChillerCarrierHXTelemetry>, String> {
         let mut final_tels = Vec::new();
         querier.run(&ts_ini, &ts_end, &mut |items: Vec<TelemetryDriChillerCarrierHX>| {
             let mut x = items.into_iter()
@@ -284,8 +230,7 @@ impl DriHistParams {
         Ok(final_tels)
     }
 
-    async fn process_chiller_carrier_xa_query(&self, globs: &Arc<GlobalVars>, ts_ini: String, ts_end: String, table_name: String) -> Result<Vec<DriChillerCarrierXATelemetry>, String> {
-        let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, self.dev_id.to_owned());
+    async fn process_chiller_carrier_xa_query(&self, globs: &Arc<GlobalVars>, ts_ini: String, ts_end: String, querier: TelemetryQuerier) -> Result<Vec<DriChillerCarrierXATelemetry>, String> {
         let mut final_tels = Vec::new();
         querier.run(&ts_ini, &ts_end, &mut |items: Vec<TelemetryDriChillerCarrierXA>| {
             let mut x = items.into_iter()
@@ -301,8 +246,7 @@ impl DriHistParams {
         Ok(final_tels)
     }
 
-    async fn process_chiller_carrier_xa_hvar_query(&self, globs: &Arc<GlobalVars>, ts_ini: String, ts_end: String, table_name: String) -> Result<Vec<DriChillerCarrierXAHvarTelemetry>, String> {
-        let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, self.dev_id.to_owned());
+    async fn process_chiller_carrier_xa_hvar_query(&self, globs: &Arc<GlobalVars>, ts_ini: String, ts_end: String, querier: TelemetryQuerier) -> Result<Vec<DriChillerCarrierXAHvarTelemetry>, String> {
         let mut final_tels = Vec::new();
         querier.run(&ts_ini, &ts_end, &mut |items: Vec<TelemetryDriChillerCarrierXAHvar>| {
             let mut x = items.into_iter()
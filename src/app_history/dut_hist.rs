@@ -3,7 +3,7 @@ use chrono::{Duration, NaiveDateTime};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::{compression::compiler_DUT::DUTTelemetryCompiler, db::config::dynamo::QuerierDevIdTimestamp, telemetry_payloads::dut_payload_json::get_raw_telemetry_pack_dut, GlobalVars};
+use crate::{compression::compiler_DUT::DUTTelemetryCompiler, db::config::{table_resolution::DeviceType, telemetry_source::{TelemetryQuerier, TelemetrySource}}, telemetry_payloads::dut_payload_json::get_raw_telemetry_pack_dut, GlobalVars};
 use crate::telemetry_payloads::dut_telemetry::{ split_pack, HwInfoDUT };
 use crate::telemetry_payloads::dut_l1::l1_calc::create_l1_calculator;
 
@@ -47,29 +47,16 @@ pub async fn process_comp_command_dut (rpars: ReqParameters, globs: &Arc<GlobalV
     let page_ts_ini = accs.page_ts_ini;
     let mut tcomp = accs.tcomp;
   
-    let mut table_name = {
-      if (dev_id.len() == 12) && dev_id.to_uppercase().starts_with("DUT") {
-        format!("{}XXXX_RAW", &dev_id.to_uppercase()[0..8])
-      } else {
-        String::new()
+    let querier = match TelemetryQuerier::for_device(globs, DeviceType::Dut, &dev_id) {
+      Some(querier) => querier,
+      None => {
+        println!("Unknown DUT generation: {}", dev_id);
+        return Ok("{}".to_string());
       }
     };
   
-    for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DUT {
-      if dev_id.to_uppercase().starts_with(&custom.dev_prefix) {
-        table_name = custom.table_name.to_string();
-        break;
-      }
-    }
-  
-    if table_name.len() == 0 {
-      println!("Unknown DUT generation: {}", dev_id);
-      return Ok("{}".to_string());
-    }
-  
     let mut dut_l1_calc = create_l1_calculator(&dev);
   
-    let querier = QuerierDevIdTimestamp::new_diel_dev(table_name, dev_id.clone());
     let mut found_invalid_payload = false;
     let result = querier.run(&ts_ini, &ts_end, &mut |items| {
       for item in items {
@@ -1,12 +1,14 @@
-use std::{collections::HashMap, sync::Arc, error::Error};
+use std::{collections::{HashMap, HashSet}, sync::Arc, error::Error};
 
 use chrono::{Duration, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use crate::db::config::dynamo::QuerierDevIdTimestamp;
+use crate::db::config::telemetry_source::{TelemetryQuerier, TelemetrySource};
+use crate::db::config::table_resolution::DeviceType;
 
 use crate::models::external_models::device::EnergyDevice;
-use crate::telemetry_payloads::energy::dme::TelemetryDME;
+use crate::telemetry_payloads::energy::adapters::{adapter_for_manufacturer, EnergyManufacturerAdapter};
+use crate::telemetry_payloads::formulas::compile_device_formulas;
 use crate::telemetry_payloads::energy::padronized::{format_padronized_energy_temeletry, PadronizedEnergyTelemetry};
 use crate::GlobalVars;
 
@@ -24,11 +26,10 @@ pub struct EnergyHistParams {
 }
 
 impl EnergyHistParams {
-    pub async fn process_query(mut self, globs: &Arc<GlobalVars>) -> Result<String, Box<dyn Error>> {
-        let tels = match &self.manufacturer[..] {
-            "Diel Energia" => self.process_dme_query(globs).await,
-            _ => Err("Unknown manufacturer!".into()),
-        }?;
+    pub async fn process_query(self, globs: &Arc<GlobalVars>) -> Result<String, Box<dyn Error>> {
+        let adapter = adapter_for_manufacturer(&self.manufacturer, &globs.configfile)
+            .ok_or("Unknown manufacturer!")?;
+        let tels = self.process_manufacturer_query(adapter.as_ref(), globs).await?;
 
         let data = serde_json::json!({
             "energy_device_id": self.energy_device_id,
@@ -41,38 +42,20 @@ impl EnergyHistParams {
           Ok(data.to_string())
     }
 
-    async fn process_dme_query(&self, globs: &Arc<GlobalVars>) -> Result<Vec<PadronizedEnergyTelemetry>, Box<dyn Error>> {
-        let dev_id_upper = self.energy_device_id.to_uppercase();
-        let mut table_name = {
-            if (self.energy_device_id.len() == 12) && dev_id_upper.starts_with("DRI") {
-                format!("{}XXXX_RAW", &dev_id_upper[0..8])
-            } else {
-                String::new()
-            }
-        };
-
-        for custom in &globs.configfile.CUSTOM_TABLE_NAMES_DRI {
-            if dev_id_upper.starts_with(&custom.dev_prefix) {
-                table_name = custom.table_name.to_owned();
-                break;
-            }
-        }
+    async fn process_manufacturer_query(&self, adapter: &dyn EnergyManufacturerAdapter, globs: &Arc<GlobalVars>) -> Result<Vec<PadronizedEnergyTelemetry>, Box<dyn Error>> {
+        let querier = TelemetryQuerier::for_device(globs, DeviceType::Dri, &self.energy_device_id)
+            .ok_or_else(|| format!("Unknown energy meter generation: {}", self.energy_device_id))?;
 
-        if table_name.is_empty() {
-            return Err(format!("Unknown DRI generation: {}", self.energy_device_id).into());
-        }
+        // Compiladas uma vez por dispositivo, e não a cada telemetria
+        let formulas = compile_device_formulas(self.formulas.as_ref(), &self.energy_device_id);
 
         let ts_ini = self.start_time.format("%Y-%m-%dT%H:%M:%S").to_string();
         let ts_end = self.end_time.format("%Y-%m-%dT%H:%M:%S").to_string();
-        let querier = QuerierDevIdTimestamp::new_custom(table_name.to_owned(), "dev_id".to_owned(), "timestamp".to_owned(), self.energy_device_id.to_owned());
         let mut final_tels = Vec::new();
 
-        querier.run(&ts_ini, &ts_end, &mut |tels: Vec<TelemetryDME>| {
+        querier.run(&ts_ini, &ts_end, &mut |tels: Vec<Value>| {
             let mut x = tels.into_iter()
-                .filter_map(|mut tel| {
-                    tel.formulas = self.formulas.clone();
-                    tel.try_into().ok()
-                })
+                .filter_map(|tel| adapter.to_padronized(tel, &formulas).ok())
                 .collect::<Vec<PadronizedEnergyTelemetry>>();
             final_tels.append(&mut x);
             Ok(())
@@ -95,7 +78,7 @@ impl EnergyHistParams {
             start_time: NaiveDateTime::parse_from_str(&format!("{}T00:00:00", day), "%Y-%m-%dT%H:%M:%S").unwrap(),
             end_time,
             formulas: energy_device.formulas.clone(),
-            params: Some(["en_at_tri".to_string(), "demanda_med_at".to_string()].to_vec()),
+            params: Some(["en_at_tri".to_string(), "demanda_med_at".to_string(), "en_re_tri".to_string(), "fp".to_string()].to_vec()),
         }
     }
 }
@@ -133,6 +116,8 @@ pub struct EnergyDataStruct {
     pub day: String,
     pub total_measured: f64,
     pub hour_values: HashMap<String, Vec<f64>>,
+    /* Horas com alguma leitura de en_at_tri inválida */
+    pub invalid_hours: HashSet<String>,
     pub hours: Vec<String>,
 }
 
@@ -143,6 +128,7 @@ impl EnergyDataStruct {
             day: day_consumption.to_string(),
             total_measured: 0.0,
             hour_values: HashMap::new(),
+            invalid_hours: HashSet::new(),
             hours: Vec::new(),
         }
     }
@@ -162,7 +148,8 @@ pub struct HoursCompiledEnergyData {
     pub last_en_at_tri_hour: f64,
     pub first_en_at_tri_hour: f64,
     pub is_measured_consumption: bool,
-    pub is_valid_consumption: bool
+    pub is_valid_consumption: bool,
+    pub fill_strategy: String,
 }
 
 impl CompiledEnergyData {
@@ -1,6 +1,7 @@
 pub mod dma_hist;
 pub mod laager_hist;
 pub mod compiler_queues;
+pub mod compilation_cache;
 pub mod energy_hist;
 pub mod dut_hist;
 pub mod dac_hist;
@@ -0,0 +1,35 @@
+//! Servidor falso do API Server e da API da Laager, usado para rodar o `run_nightly_tasks` de ponta a ponta
+//! sem serviços externos. As respostas vêm de arquivos de fixture:
+//!
+//! ```text
+//! {fixtures}/apiserver/clients.json                      -> /clients/get-all-clients
+//! {fixtures}/apiserver/units/{CLIENT_ID}.json            -> /clients/get-all-units-by-client
+//! {fixtures}/apiserver/config_devices/{UNIT_ID}.json     -> /devices/get-config-devices
+//! {fixtures}/apiserver/laager_history/{LAAGER_CODE}.json -> /laager/get-history-list
+//! {fixtures}/laager/meters.json                          -> /api/v1/leitura/agua-fria/1
+//! {fixtures}/laager/meter_details/{RF_DEVICE_ID}.json    -> /api/v1/consumption/meter_details/{id}
+//! ```
+//!
+//! Para respostas que variam por dia, um arquivo `{nome}_{YYYY-MM-DD}.json` tem preferência sobre `{nome}.json`.
+//! Uso: `cargo run --bin mock_upstream -- [pasta_fixtures] [porta]`
+
+mod server;
+
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+use server::mock_upstream_server;
+
+const DEFAULT_FIXTURES_DIR: &str = "devops/mock_upstream/fixtures";
+const DEFAULT_PORT: u16 = 8443;
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let fixtures_dir = PathBuf::from(args.get(1).map(String::as_str).unwrap_or(DEFAULT_FIXTURES_DIR));
+    let port = args.get(2).and_then(|port| port.parse().ok()).unwrap_or(DEFAULT_PORT);
+
+    println!("Mock upstream servindo {} na porta {}", fixtures_dir.display(), port);
+
+    mock_upstream_server(fixtures_dir, TcpListener::bind(("0.0.0.0", port))?)?.await
+}
@@ -0,0 +1,163 @@
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+use actix_web::dev::Server;
+use actix_web::{get, middleware, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use chrono::Utc;
+use serde_json::Value;
+
+const MOCK_LAAGER_TOKEN: &str = "mock-laager-token";
+
+struct MockState {
+    fixtures_dir: PathBuf,
+}
+
+/// Monta o servidor falso sobre um listener já aberto, o que permite usar a porta 0 nos testes.
+pub fn mock_upstream_server(fixtures_dir: PathBuf, listener: TcpListener) -> std::io::Result<Server> {
+    let state = web::Data::new(MockState { fixtures_dir });
+
+    Ok(HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            // O cliente da Laager monta as URLs com barra dupla
+            .wrap(middleware::NormalizePath::trim())
+            .service(get_all_clients)
+            .service(get_all_units_by_client)
+            .service(get_config_devices)
+            .service(get_laager_history_list)
+            .service(laager_login)
+            .service(laager_meters)
+            .service(laager_meter_details)
+    }).listen(listener)?.run())
+}
+
+#[get("/clients/get-all-clients")]
+async fn get_all_clients(body: web::Bytes, state: web::Data<MockState>) -> impl Responder {
+    let params = parse_body(&body);
+    let mut response = match read_fixture(&state.fixtures_dir.join("apiserver"), "clients", None) {
+        Ok(response) => response,
+        Err(response) => return response,
+    };
+
+    filter_list(&mut response, "CLIENT_ID", &params["FILTER_BY_CLIENT_IDS"]);
+    HttpResponse::Ok().json(response)
+}
+
+#[get("/clients/get-all-units-by-client")]
+async fn get_all_units_by_client(body: web::Bytes, state: web::Data<MockState>) -> impl Responder {
+    let params = parse_body(&body);
+    let client_id = params["CLIENT_ID"].to_string();
+    let day = params["FILTER_BY_PRODUCTION_TIMESTAMP_DATE"].as_str();
+
+    let mut response = match read_fixture(&state.fixtures_dir.join("apiserver/units"), &client_id, day) {
+        Ok(response) => response,
+        // Cliente sem fixture de unidades é tratado como cliente sem unidades
+        Err(_) => serde_json::json!({ "list": [] }),
+    };
+
+    filter_list(&mut response, "UNIT_ID", &params["FILTER_BY_UNIT_IDS"]);
+    HttpResponse::Ok().json(response)
+}
+
+#[get("/devices/get-config-devices")]
+async fn get_config_devices(body: web::Bytes, state: web::Data<MockState>) -> impl Responder {
+    let params = parse_body(&body);
+    let unit_id = params["UNIT_ID"].to_string();
+
+    match read_fixture(&state.fixtures_dir.join("apiserver/config_devices"), &unit_id, params["DAY"].as_str()) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(response) => response,
+    }
+}
+
+#[get("/laager/get-history-list")]
+async fn get_laager_history_list(body: web::Bytes, state: web::Data<MockState>) -> impl Responder {
+    let params = parse_body(&body);
+    let laager_code = params["LAAGER_CODE"].as_str().unwrap_or_default();
+
+    match read_fixture(&state.fixtures_dir.join("apiserver/laager_history"), laager_code, params["FILTER_BY_HISTORY_DATE"].as_str()) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(response) => response,
+    }
+}
+
+#[post("/oauth/token")]
+async fn laager_login() -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({
+        "access_token": MOCK_LAAGER_TOKEN,
+        "token_type": "Bearer",
+        "expires_in": 7200,
+        "created_at": Utc::now().timestamp(),
+    }))
+}
+
+#[get("/api/v1/leitura/agua-fria/1")]
+async fn laager_meters(req: HttpRequest, state: web::Data<MockState>) -> impl Responder {
+    if !is_laager_authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match read_fixture(&state.fixtures_dir.join("laager"), "meters", None) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(response) => response,
+    }
+}
+
+#[get("/api/v1/consumption/meter_details/{rf_device_id}")]
+async fn laager_meter_details(req: HttpRequest, rf_device_id: web::Path<String>, state: web::Data<MockState>) -> impl Responder {
+    if !is_laager_authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match read_fixture(&state.fixtures_dir.join("laager/meter_details"), &rf_device_id, None) {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(response) => response,
+    }
+}
+
+fn is_laager_authorized(req: &HttpRequest) -> bool {
+    req.headers().get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value == format!("Bearer {}", MOCK_LAAGER_TOKEN))
+        .unwrap_or(false)
+}
+
+// O API Server recebe os parâmetros no corpo de requisições GET
+fn parse_body(body: &[u8]) -> Value {
+    serde_json::from_slice(body).unwrap_or(Value::Null)
+}
+
+fn read_fixture(dir: &Path, name: &str, day: Option<&str>) -> Result<Value, HttpResponse> {
+    let mut candidates = Vec::new();
+    if let Some(day) = day {
+        candidates.push(dir.join(format!("{}_{}.json", name, day)));
+    }
+    candidates.push(dir.join(format!("{}.json", name)));
+
+    let path = match candidates.into_iter().find(|path| path.exists()) {
+        Some(path) => path,
+        None => {
+            let msg_error = format!("Fixture não encontrada: {}/{}.json", dir.display(), name);
+            println!("{}", msg_error);
+            return Err(HttpResponse::NotFound().body(msg_error));
+        }
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|err| HttpResponse::InternalServerError().body(format!("Erro ao ler fixture {}, {}", path.display(), err)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|err| HttpResponse::InternalServerError().body(format!("Fixture inválida {}, {}", path.display(), err)))
+}
+
+// Aplica os filtros FILTER_BY_* do API Server: lista vazia ou ausente não filtra
+fn filter_list(response: &mut Value, id_field: &str, filter: &Value) {
+    let ids: Vec<i64> = match filter.as_array() {
+        Some(ids) if !ids.is_empty() => ids.iter().filter_map(Value::as_i64).collect(),
+        _ => return,
+    };
+
+    if let Some(list) = response.get_mut("list").and_then(Value::as_array_mut) {
+        list.retain(|item| item[id_field].as_i64().map(|id| ids.contains(&id)).unwrap_or(false));
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::models::database_models::calendar_holidays::CalendarHolidayRow;
+
+const BLACK_CONSCIOUSNESS_DAY_FIRST_YEAR: i32 = 2024;
+
+/// Feriados nacionais do ano: datas fixas e as móveis que dependem da Páscoa (carnaval, sexta-feira santa e Corpus Christi)
+pub fn national_holidays(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let fixed = [
+        (1, 1, "Confraternização Universal"),
+        (4, 21, "Tiradentes"),
+        (5, 1, "Dia do Trabalho"),
+        (9, 7, "Independência do Brasil"),
+        (10, 12, "Nossa Senhora Aparecida"),
+        (11, 2, "Finados"),
+        (11, 15, "Proclamação da República"),
+        (12, 25, "Natal"),
+    ];
+    let mut holidays: Vec<(NaiveDate, &'static str)> = fixed.iter()
+        .filter_map(|(month, day, name)| Some((NaiveDate::from_ymd_opt(year, *month, *day)?, *name)))
+        .collect();
+
+    // Feriado nacional a partir de 2024 (Lei 14.759/2023); antes disso, apenas onde houver lei estadual ou municipal
+    if year >= BLACK_CONSCIOUSNESS_DAY_FIRST_YEAR {
+        holidays.extend(NaiveDate::from_ymd_opt(year, 11, 20).map(|date| (date, "Dia Nacional de Zumbi e da Consciência Negra")));
+    }
+
+    if let Some(easter) = easter_sunday(year) {
+        holidays.push((easter - Duration::days(48), "Carnaval"));
+        holidays.push((easter - Duration::days(47), "Carnaval"));
+        holidays.push((easter - Duration::days(2), "Sexta-feira Santa"));
+        holidays.push((easter + Duration::days(60), "Corpus Christi"));
+    }
+
+    holidays
+}
+
+/// Feriados nacionais de todos os anos entre as duas datas
+pub fn holidays_between(start: NaiveDate, end: NaiveDate) -> HashSet<NaiveDate> {
+    (start.year()..=end.year()).flat_map(national_holidays).map(|(date, _)| date).collect()
+}
+
+/// Abrangência de um feriado ou dia sem expediente cadastrado em calendar_holidays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HolidayScope {
+    National,
+    State,
+    City,
+    Client,
+}
+
+impl HolidayScope {
+    pub fn parse(scope: &str) -> Option<Self> {
+        match scope.to_uppercase().as_str() {
+            "NATIONAL" => Some(HolidayScope::National),
+            "STATE" => Some(HolidayScope::State),
+            "CITY" => Some(HolidayScope::City),
+            "CLIENT" => Some(HolidayScope::Client),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HolidayScope::National => "NATIONAL",
+            HolidayScope::State => "STATE",
+            HolidayScope::City => "CITY",
+            HolidayScope::Client => "CLIENT",
+        }
+    }
+}
+
+/// Datas entre `start` e `end` cobertas por um feriado cadastrado. Feriados recorrentes se repetem
+/// todo ano no mesmo dia e mês, inclusive os períodos que atravessam a virada do ano.
+pub fn holiday_dates(holiday: &CalendarHolidayRow, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    let duration = holiday.end_date.map(|end_date| end_date - holiday.start_date).unwrap_or_default();
+
+    let first_days: Vec<NaiveDate> = if holiday.recurring {
+        ((start.year() - 1)..=end.year())
+            .filter_map(|year| holiday.start_date.with_year(year))
+            .collect()
+    } else {
+        vec![holiday.start_date]
+    };
+
+    first_days.into_iter()
+        .flat_map(|first_day| (0..=duration.num_days()).map(move |offset| first_day + Duration::days(offset)))
+        .filter(|date| *date >= start && *date <= end)
+        .collect()
+}
+
+// Algoritmo de Meeus/Jones/Butcher para o calendário gregoriano
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn holiday(start_date: NaiveDate, end_date: Option<NaiveDate>, recurring: bool) -> CalendarHolidayRow {
+        CalendarHolidayRow {
+            id: 1,
+            name: "Recesso".to_owned(),
+            scope: HolidayScope::Client.as_str().to_owned(),
+            start_date,
+            end_date,
+            recurring,
+            state_name: None,
+            city_name: None,
+            client_id: Some(1),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn easter_sunday_matches_known_dates() {
+        assert_eq!(easter_sunday(2000), Some(date(2000, 4, 23)));
+        assert_eq!(easter_sunday(2019), Some(date(2019, 4, 21)));
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+        // Datas extremas: mais tardia e mais cedo possíveis
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+        assert_eq!(easter_sunday(2285), Some(date(2285, 3, 22)));
+    }
+
+    #[test]
+    fn national_holidays_include_easter_based_dates() {
+        let holidays: HashSet<NaiveDate> = national_holidays(2025).into_iter().map(|(date, _)| date).collect();
+        for expected in [date(2025, 3, 3), date(2025, 3, 4), date(2025, 4, 18), date(2025, 6, 19), date(2025, 4, 21), date(2025, 12, 25)] {
+            assert!(holidays.contains(&expected), "{} não é feriado", expected);
+        }
+        assert_eq!(holidays.len(), 13);
+    }
+
+    #[test]
+    fn black_consciousness_day_is_national_only_from_2024() {
+        assert!(!national_holidays(2023).iter().any(|(date, _)| *date == self::date(2023, 11, 20)));
+        assert!(national_holidays(2024).iter().any(|(date, _)| *date == self::date(2024, 11, 20)));
+    }
+
+    #[test]
+    fn holidays_between_covers_every_year_in_the_range() {
+        let holidays = holidays_between(date(2023, 12, 1), date(2024, 1, 31));
+        assert!(holidays.contains(&date(2023, 12, 25)));
+        assert!(holidays.contains(&date(2024, 1, 1)));
+        assert!(holidays.contains(&date(2024, 3, 29)));
+    }
+
+    #[test]
+    fn holiday_dates_expands_periods_within_the_range() {
+        let recess = holiday(date(2024, 7, 15), Some(date(2024, 7, 19)), false);
+        assert_eq!(holiday_dates(&recess, date(2024, 7, 17), date(2024, 7, 31)), vec![date(2024, 7, 17), date(2024, 7, 18), date(2024, 7, 19)]);
+        assert!(holiday_dates(&recess, date(2025, 7, 1), date(2025, 7, 31)).is_empty());
+    }
+
+    #[test]
+    fn recurring_holiday_dates_repeat_every_year_across_new_year() {
+        let closure = holiday(date(2020, 12, 30), Some(date(2021, 1, 2)), true);
+        assert_eq!(
+            holiday_dates(&closure, date(2025, 1, 1), date(2025, 12, 31)),
+            vec![date(2025, 1, 1), date(2025, 1, 2), date(2025, 12, 30), date(2025, 12, 31)],
+        );
+
+        let anniversary = holiday(date(2010, 3, 19), None, true);
+        assert_eq!(holiday_dates(&anniversary, date(2024, 1, 1), date(2025, 12, 31)), vec![date(2024, 3, 19), date(2025, 3, 19)]);
+    }
+}
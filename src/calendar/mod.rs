@@ -0,0 +1,2 @@
+pub mod holidays;
+pub mod unit_calendar;
@@ -0,0 +1,57 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::calendar::holidays::{holiday_dates, holidays_between, national_holidays, HolidayScope};
+use crate::db::entities::calendar_holidays::get_unit_calendar_holidays;
+use crate::db::entities::electric_circuits::get_electric_circuit_unit_id;
+use crate::http::structs::calendar::NonWorkingDay;
+use crate::models::database_models::calendar_holidays::CalendarHolidayRow;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+/// Dias sem expediente da unidade no período: feriados nacionais calculados e os cadastrados em calendar_holidays.
+/// Quando mais de um cai no mesmo dia, fica o de menor abrangência (cliente, cidade, estado, nacional).
+pub fn build_unit_calendar(holidays: &[CalendarHolidayRow], start: NaiveDate, end: NaiveDate) -> BTreeMap<NaiveDate, NonWorkingDay> {
+    let mut calendar = BTreeMap::new();
+
+    for (date, name) in (start.year()..=end.year()).flat_map(national_holidays) {
+        if date >= start && date <= end {
+            calendar.insert(date, NonWorkingDay { date, name: name.to_owned(), scope: HolidayScope::National.as_str().to_owned() });
+        }
+    }
+
+    for scope in [HolidayScope::National, HolidayScope::State, HolidayScope::City, HolidayScope::Client] {
+        for holiday in holidays.iter().filter(|holiday| HolidayScope::parse(&holiday.scope) == Some(scope)) {
+            for date in holiday_dates(holiday, start, end) {
+                calendar.insert(date, NonWorkingDay { date, name: holiday.name.clone(), scope: scope.as_str().to_owned() });
+            }
+        }
+    }
+
+    calendar
+}
+
+/// Datas sem expediente da unidade, usadas pelas previsões e pela análise de programação.
+/// Se o calendário não puder ser obtido, considera apenas os feriados nacionais.
+pub fn unit_non_working_days(unit_id: i32, start: NaiveDate, end: NaiveDate, globs: &Arc<GlobalVars>) -> HashSet<NaiveDate> {
+    match get_unit_calendar_holidays(unit_id, start, end, globs) {
+        Ok(holidays) => build_unit_calendar(&holidays, start, end).into_keys().collect(),
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter calendário da unidade {}: {}", unit_id, err), 0, "ERROR");
+            holidays_between(start, end)
+        }
+    }
+}
+
+pub fn circuit_non_working_days(electric_circuit_id: i32, start: NaiveDate, end: NaiveDate, globs: &Arc<GlobalVars>) -> HashSet<NaiveDate> {
+    match get_electric_circuit_unit_id(electric_circuit_id, globs) {
+        Ok(Some(unit_id)) => unit_non_working_days(unit_id, start, end, globs),
+        Ok(None) => holidays_between(start, end),
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter unidade do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+            holidays_between(start, end)
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use json5;
 use serde::Deserialize;
 
+use crate::db::config::table_resolution::TableRule;
+use crate::schedules::power_quality::PowerQualityConfig;
+use crate::telemetry_payloads::energy::adapters::EnergyRegisterMap;
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct ConfigFile {
   /* Credenciais para o rusthist buscar no DynamoDB as telemetrias */
@@ -29,7 +33,35 @@ pub struct ConfigFile {
   pub CUSTOM_TABLE_NAMES_DAL: Vec<PrefixAndTable>,
   pub CUSTOM_TABLE_NAMES_DAM: Vec<PrefixAndTable>,
 
+  /* Regras de nome de tabela no DynamoDB (padrão do código do dispositivo -> tabela e chaves), avaliadas antes das listas acima */
+  pub DYNAMO_TABLE_RULES: Option<Vec<TableRule>>,
+
   pub API_PORT: u16,
+
+  /* Token exigido no header Authorization das rotas administrativas. Se vazio, as rotas administrativas ficam desabilitadas */
+  pub ADMIN_API_TOKEN: Option<String>,
+
+  /* Pasta com dumps JSONL de telemetrias ({dev_id}.jsonl). Se preenchido, substitui o DynamoDB como fonte das telemetrias */
+  pub LOCAL_TELEMETRY_DIR: Option<String>,
+
+  /* Pasta para gravar em disco o cache de compilação do dia. Se vazio, o cache fica em memória */
+  pub COMPILATION_CACHE_DIR: Option<String>,
+
+  /* Mapas de registradores para medidores de energia de outros fabricantes (campo do fabricante -> campo padronizado, com escala) */
+  pub ENERGY_REGISTER_MAPS: Option<Vec<EnergyRegisterMap>>,
+
+  /* Análise de qualidade de energia (tensão, desequilíbrio e frequência por 15 minutos, afundamentos e elevações). Se vazio, não é calculada.
+     A tensão nominal é o padrão para os circuitos sem tensão nominal cadastrada */
+  pub POWER_QUALITY: Option<PowerQualityConfig>,
+
+  /* Estratégia padrão para horas sem medição válida: EVEN_SPLIT, PROFILE_WEIGHTED ou LEAVE_EMPTY. Pode ser sobrescrita por circuito */
+  pub ENERGY_GAP_FILL_STRATEGY: Option<String>,
+
+  /* Modelo padrão de previsão de consumo: WEEKDAY_AVERAGE ou SEASONAL. Pode ser sobrescrito por cliente */
+  pub ENERGY_FORECAST_MODEL: Option<String>,
+
+  /* Rede cujos fatores de emissão (grid_emission_factors) são aplicados ao consumo, padrão SIN */
+  pub EMISSION_FACTOR_GRID: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -0,0 +1,100 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use chrono::{NaiveDate, Utc};
+
+// Valores usados quando a consulta não foi feita dentro de um pipeline/cliente identificado
+pub const UNKNOWN_PIPELINE: &str = "unknown";
+pub const UNKNOWN_CLIENT: i32 = 0;
+
+tokio::task_local! {
+    static PIPELINE: &'static str;
+    static CLIENT_REFERENCE_ID: i32;
+    // RCUs por tabela lidas durante uma compilação guardada no cache compartilhado
+    static COMPILATION_READS: RefCell<HashMap<String, f64>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConsumedCapacityKey {
+    pub table_name: String,
+    pub pipeline: String,
+    pub client_reference_id: i32,
+    pub record_date: NaiveDate,
+}
+
+/// Leituras feitas pelo pipeline e compilações que ele reaproveitou do cache, com as RCUs que elas custaram
+/// ao pipeline que as compilou.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConsumedCapacity {
+    pub consumed_rcu: f64,
+    pub cache_hits: i32,
+    pub cache_hit_rcu: f64,
+}
+
+impl ConsumedCapacity {
+    fn add(&mut self, other: &ConsumedCapacity) {
+        self.consumed_rcu += other.consumed_rcu;
+        self.cache_hits += other.cache_hits;
+        self.cache_hit_rcu += other.cache_hit_rcu;
+    }
+}
+
+// Totais acumulados desde o último flush para o Postgres
+static CONSUMED_CAPACITY: Mutex<Option<HashMap<ConsumedCapacityKey, ConsumedCapacity>>> = Mutex::new(None);
+
+/// Executa `fut` atribuindo as leituras do DynamoDB feitas dentro dele ao pipeline informado.
+pub async fn with_pipeline<F: Future>(pipeline: &'static str, fut: F) -> F::Output {
+    PIPELINE.scope(pipeline, fut).await
+}
+
+/// Executa `fut` atribuindo as leituras do DynamoDB feitas dentro dele ao cliente informado (id do API server).
+pub async fn with_client<F: Future>(client_reference_id: i32, fut: F) -> F::Output {
+    CLIENT_REFERENCE_ID.scope(client_reference_id, fut).await
+}
+
+/// Executa a compilação de `fut` e retorna também as RCUs lidas por tabela. As leituras continuam atribuídas
+/// ao pipeline que disparou a compilação; o retorno serve para registrar os reaproveitamentos do cache.
+pub async fn with_compilation_reads<F: Future>(fut: F) -> (F::Output, HashMap<String, f64>) {
+    COMPILATION_READS.scope(RefCell::new(HashMap::new()), async {
+        let output = fut.await;
+        (output, COMPILATION_READS.with(|reads| reads.take()))
+    }).await
+}
+
+/// Registra a capacidade consumida por uma página de consulta, no dia (UTC) em que a leitura foi feita.
+pub fn register_consumed_capacity(table_name: &str, capacity_units: f64) {
+    let _ = COMPILATION_READS.try_with(|reads| *reads.borrow_mut().entry(table_name.to_owned()).or_insert(0.0) += capacity_units);
+
+    add_consumed_capacity(table_name, ConsumedCapacity { consumed_rcu: capacity_units, ..ConsumedCapacity::default() });
+}
+
+/// Registra que o pipeline atual reaproveitou do cache uma compilação que leu `reads` (RCUs por tabela).
+pub fn register_cache_hit(reads: &HashMap<String, f64>) {
+    for (table_name, capacity_units) in reads {
+        add_consumed_capacity(table_name, ConsumedCapacity { cache_hits: 1, cache_hit_rcu: *capacity_units, ..ConsumedCapacity::default() });
+    }
+}
+
+fn add_consumed_capacity(table_name: &str, capacity: ConsumedCapacity) {
+    let key = ConsumedCapacityKey {
+        table_name: table_name.to_owned(),
+        pipeline: PIPELINE.try_with(|p| p.to_string()).unwrap_or_else(|_| UNKNOWN_PIPELINE.to_owned()),
+        client_reference_id: CLIENT_REFERENCE_ID.try_with(|c| *c).unwrap_or(UNKNOWN_CLIENT),
+        record_date: Utc::now().date_naive(),
+    };
+
+    let mut consumed = CONSUMED_CAPACITY.lock().unwrap();
+    consumed.get_or_insert_with(HashMap::new).entry(key).or_default().add(&capacity);
+}
+
+/// Retira os totais acumulados até agora, zerando o acumulador.
+pub fn take_consumed_capacity() -> HashMap<ConsumedCapacityKey, ConsumedCapacity> {
+    CONSUMED_CAPACITY.lock().unwrap().take().unwrap_or_default()
+}
+
+/// Devolve ao acumulador totais que não puderam ser gravados, para o próximo flush.
+pub fn restore_consumed_capacity(key: ConsumedCapacityKey, capacity: ConsumedCapacity) {
+    let mut consumed = CONSUMED_CAPACITY.lock().unwrap();
+    consumed.get_or_insert_with(HashMap::new).entry(key).or_default().add(&capacity);
+}
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use rusoto_dynamodb::{AttributeValue, DynamoDb, DynamoDbClient, QueryInput, QueryError};
 use rusoto_core::RusotoError;
 use std::env;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::consumed_capacity::register_consumed_capacity;
+use super::telemetry_source::TelemetrySource;
 
 
 
@@ -24,15 +27,6 @@ pub struct PrefixAndTable {
 }
 
 impl QuerierDevIdTimestamp {
-    pub fn new_diel_dev(table_name: String, dev_id: String) -> Self {
-        Self {
-            table_name,
-            key_var_name: "dev_id".to_owned(),
-            order_var_name: "timestamp".to_owned(),
-            part_key: dev_id,
-        }
-    }
-
     pub fn new_custom(table_name: String, key_var_name: String, order_var_name: String, part_key: String) -> Self {
         Self {
             table_name,
@@ -84,6 +78,7 @@ impl QuerierDevIdTimestamp {
         return QueryInput {
             table_name: table_name.to_owned(),
             consistent_read: Some(false),
+            return_consumed_capacity: Some("TOTAL".to_owned()),
             projection_expression: None, // Some(String::from("#ts,L1,#State,#Mode"))
             key_condition_expression: Some(format!("{key} = :{key} and #ts between :ts_begin and :ts_end", key = key_var_name)),
             // key_condition_expression: Some(format!("{key} = :{key} and begins_with(#ts, :day)", key = key_var_name)),
@@ -105,10 +100,12 @@ impl QuerierDevIdTimestamp {
             ..QueryInput::default()
         };
     }
+}
 
-    pub async fn run<'a, T, F>(&self, ts_ini: &str, ts_end: &str, proc_items: &mut F) -> Result<(), String>
+impl TelemetrySource for QuerierDevIdTimestamp {
+    async fn run<T, F>(&self, ts_ini: &str, ts_end: &str, proc_items: &mut F) -> Result<(), String>
     where
-        T: serde::Deserialize<'a>, // serde_json::Value
+        T: DeserializeOwned + Send, // serde_json::Value
         F: FnMut(Vec<T>) -> Result<(), String>,
         F: Send,
     {
@@ -119,6 +116,9 @@ impl QuerierDevIdTimestamp {
         loop {
             if ts_ini >= ts_end { break; }
             let result_page = Self::fetch_page(query_input.clone(), is_next_page).await?;
+            if let Some(capacity_units) = result_page.consumed_capacity.as_ref().and_then(|c| c.capacity_units) {
+                register_consumed_capacity(&self.table_name, capacity_units);
+            }
 
             let items = result_page.items.ok_or_else(|| "ERROR 120".to_owned())?;
             let items: Vec<T> = from_items(items).map_err(|err| err.to_string())?;
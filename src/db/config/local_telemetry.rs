@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::telemetry_source::TelemetrySource;
+
+// Quantidade de itens entregues por "página", para simular a paginação do DynamoDB
+const LOCAL_PAGE_SIZE: usize = 1000;
+
+/// Lê telemetrias brutas de dumps locais, um arquivo por dispositivo: `{dir}/{dev_id}.jsonl`
+/// (ou `.ndjson`), com um item do DynamoDB em JSON por linha.
+pub struct QuerierLocalFile {
+    dir: String,
+
+    key_var_name: String, // dev_id
+    part_key: String,
+
+    order_var_name: String,
+}
+
+impl QuerierLocalFile {
+    pub fn new(dir: String, key_var_name: String, order_var_name: String, part_key: String) -> Self {
+        Self {
+            dir,
+            key_var_name,
+            order_var_name,
+            part_key,
+        }
+    }
+
+    fn find_dump_file(&self) -> Option<PathBuf> {
+        ["jsonl", "ndjson"].iter()
+            .map(|ext| PathBuf::from(&self.dir).join(format!("{}.{}", self.part_key, ext)))
+            .find(|path| path.exists())
+    }
+
+    fn read_items(&self, ts_ini: &str, ts_end: &str) -> Result<Vec<Value>, String> {
+        let path = self.find_dump_file()
+            .ok_or_else(|| format!("ResourceNotFound: no dump file for {} in {}", self.part_key, self.dir))?;
+        let file_contents = std::fs::read_to_string(&path).map_err(|err| format!("[{:?}]: {}", path, err))?;
+
+        let mut items = Vec::new();
+        for (line_number, line) in file_contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let item: Value = serde_json::from_str(line).map_err(|err| format!("[{:?}:{}]: {}", path, line_number + 1, err))?;
+
+            // Mesma condição da key_condition_expression da consulta no DynamoDB: itens sem a chave ficam de fora
+            if item.get(&self.key_var_name).and_then(|v| v.as_str()) != Some(self.part_key.as_str()) {
+                continue;
+            }
+            let ts = match item.get(&self.order_var_name).and_then(|v| v.as_str()) {
+                Some(ts) => ts,
+                None => continue,
+            };
+            if ts < ts_ini || ts > ts_end {
+                continue;
+            }
+            items.push(item);
+        }
+
+        // O DynamoDB entrega os itens ordenados pela sort key
+        items.sort_by(|a, b| a[&self.order_var_name].as_str().cmp(&b[&self.order_var_name].as_str()));
+
+        Ok(items)
+    }
+}
+
+impl TelemetrySource for QuerierLocalFile {
+    async fn run<T, F>(&self, ts_ini: &str, ts_end: &str, proc_items: &mut F) -> Result<(), String>
+    where
+        T: DeserializeOwned + Send,
+        F: FnMut(Vec<T>) -> Result<(), String>,
+        F: Send,
+    {
+        if ts_ini >= ts_end { return Ok(()); }
+
+        let items = self.read_items(ts_ini, ts_end)?;
+        for page in items.chunks(LOCAL_PAGE_SIZE) {
+            let page = page.iter()
+                .map(|item| serde_json::from_value::<T>(item.clone()))
+                .collect::<Result<Vec<T>, _>>()
+                .map_err(|err| err.to_string())?;
+            proc_items(page)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,2 +1,6 @@
 pub mod postgres;
 pub mod dynamo;
+pub mod local_telemetry;
+pub mod telemetry_source;
+pub mod consumed_capacity;
+pub mod table_resolution;
@@ -0,0 +1,245 @@
+use std::sync::{Arc, RwLock};
+use serde::{Deserialize, Serialize};
+
+use crate::configs::ConfigFile;
+use crate::db::entities::dynamo_table_rules::get_dynamo_table_rules;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+/// Regra de resolução da tabela do DynamoDB de um dispositivo.
+/// `pattern` é comparado com o código do dispositivo em maiúsculas: `?` casa um caractere e `*` qualquer sequência.
+/// `table_template` aceita `{dev_id}` (código completo) e `{dev_id:N}` (N primeiros caracteres).
+/// `device_type` restringe a regra a um tipo de dispositivo; sem ele, a regra vale para todos os tipos.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TableRule {
+    #[serde(default)]
+    pub device_type: Option<String>,
+    pub pattern: String,
+    pub table_template: String,
+    #[serde(default = "default_partition_key")]
+    pub partition_key: String,
+    #[serde(default = "default_sort_key")]
+    pub sort_key: String,
+}
+
+fn default_partition_key() -> String {
+    "dev_id".to_owned()
+}
+
+fn default_sort_key() -> String {
+    "timestamp".to_owned()
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedTable {
+    pub table_name: String,
+    pub partition_key: String,
+    pub sort_key: String,
+    pub rule: TableRule,
+}
+
+/// Tipo do dispositivo cuja tabela está sendo resolvida. Os medidores de energia usam as tabelas dos DRIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Dac,
+    Dut,
+    Dri,
+    Dma,
+    Dmt,
+    Dal,
+    Dam,
+}
+
+impl DeviceType {
+    // Todos seguem o padrão {8 primeiros caracteres}XXXX_RAW
+    const ALL: [DeviceType; 7] = [DeviceType::Dac, DeviceType::Dut, DeviceType::Dri, DeviceType::Dma, DeviceType::Dmt, DeviceType::Dal, DeviceType::Dam];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|device_type| device_type.as_str().eq_ignore_ascii_case(value))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceType::Dac => "DAC",
+            DeviceType::Dut => "DUT",
+            DeviceType::Dri => "DRI",
+            DeviceType::Dma => "DMA",
+            DeviceType::Dmt => "DMT",
+            DeviceType::Dal => "DAL",
+            DeviceType::Dam => "DAM",
+        }
+    }
+}
+
+// A tabela da geração DAC20719 usa "dac_id" como partition key em vez de "dev_id"
+const DAC_ID_TABLE: &str = "DAC20719XXXX_RAW";
+
+impl TableRule {
+    fn new(device_type: DeviceType, pattern: String, table_template: String) -> Self {
+        let partition_key = if table_template == DAC_ID_TABLE { "dac_id".to_owned() } else { default_partition_key() };
+        Self {
+            device_type: Some(device_type.as_str().to_owned()),
+            pattern,
+            table_template,
+            partition_key,
+            sort_key: default_sort_key(),
+        }
+    }
+
+    pub fn matches(&self, device_type: DeviceType, dev_id: &str) -> bool {
+        let type_matches = self.device_type.as_deref().map(|rule_type| rule_type.eq_ignore_ascii_case(device_type.as_str())).unwrap_or(true);
+        type_matches && wildcard_match(self.pattern.to_uppercase().as_bytes(), dev_id.to_uppercase().as_bytes())
+    }
+
+    pub fn resolve(&self, dev_id: &str) -> ResolvedTable {
+        ResolvedTable {
+            table_name: render_template(&self.table_template, &dev_id.to_uppercase()),
+            partition_key: self.partition_key.clone(),
+            sort_key: self.sort_key.clone(),
+            rule: self.clone(),
+        }
+    }
+}
+
+/// Regras do configfile, na ordem em que são avaliadas: DYNAMO_TABLE_RULES, as listas CUSTOM_TABLE_NAMES_*
+/// (prefixo -> tabela) e por último o padrão de nome de cada tipo de dispositivo.
+/// Cada lista CUSTOM_TABLE_NAMES_* vale apenas para o seu tipo de dispositivo.
+pub fn rules_from_configfile(configfile: &ConfigFile) -> Vec<TableRule> {
+    let mut rules = configfile.DYNAMO_TABLE_RULES.clone().unwrap_or_default();
+
+    let custom_lists = [
+        (DeviceType::Dma, &configfile.CUSTOM_TABLE_NAMES_DMA),
+        (DeviceType::Dri, &configfile.CUSTOM_TABLE_NAMES_DRI),
+        (DeviceType::Dut, &configfile.CUSTOM_TABLE_NAMES_DUT),
+        (DeviceType::Dac, &configfile.CUSTOM_TABLE_NAMES_DAC),
+        (DeviceType::Dmt, &configfile.CUSTOM_TABLE_NAMES_DMT),
+        (DeviceType::Dal, &configfile.CUSTOM_TABLE_NAMES_DAL),
+        (DeviceType::Dam, &configfile.CUSTOM_TABLE_NAMES_DAM),
+    ];
+    for (device_type, custom_list) in custom_lists {
+        for custom in custom_list {
+            rules.push(TableRule::new(device_type, format!("{}*", custom.dev_prefix), custom.table_name.clone()));
+        }
+    }
+
+    rules.extend(default_rules());
+    rules
+}
+
+fn default_rules() -> Vec<TableRule> {
+    let mut rules = vec![TableRule::new(DeviceType::Dac, "DAC20719????".to_owned(), DAC_ID_TABLE.to_owned())];
+    rules.extend(DeviceType::ALL.iter()
+        .map(|device_type| TableRule::new(*device_type, format!("{}?????????", device_type.as_str()), "{dev_id:8}XXXX_RAW".to_owned())));
+    rules
+}
+
+/// Regras cadastradas no Postgres (tabela dynamo_table_rules) têm prioridade sobre as do configfile,
+/// que funcionam como padrão, e são avaliadas pela coluna priority (maior primeiro). Ficam em memória e são
+/// recarregadas a cada alteração pela API e periodicamente, para que as demais instâncias do serviço também vejam a alteração.
+pub struct TableResolver {
+    config_rules: Vec<TableRule>,
+    db_rules: RwLock<Vec<TableRule>>,
+}
+
+impl TableResolver {
+    pub fn new(configfile: &ConfigFile) -> Self {
+        Self {
+            config_rules: rules_from_configfile(configfile),
+            db_rules: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Primeira regra do tipo de dispositivo que casa com o código, ou None se a geração é desconhecida.
+    pub fn resolve(&self, device_type: DeviceType, dev_id: &str) -> Option<ResolvedTable> {
+        let db_rules = self.db_rules.read().unwrap();
+        db_rules.iter()
+            .chain(self.config_rules.iter())
+            .find(|rule| rule.matches(device_type, dev_id))
+            .map(|rule| rule.resolve(dev_id))
+    }
+
+    pub fn config_rules(&self) -> &[TableRule] {
+        &self.config_rules
+    }
+
+    /// Recarrega do Postgres as regras cadastradas pela API, retornando quantas foram carregadas.
+    pub fn reload(&self, globs: &Arc<GlobalVars>) -> Result<usize, String> {
+        let rows = get_dynamo_table_rules(globs).map_err(|err| err.to_string())?;
+        let rules = rows.into_iter()
+            .map(|row| TableRule {
+                device_type: row.device_type,
+                pattern: row.pattern,
+                table_template: row.table_template,
+                partition_key: row.partition_key,
+                sort_key: row.sort_key,
+            })
+            .collect::<Vec<TableRule>>();
+
+        let count = rules.len();
+        *self.db_rules.write().unwrap() = rules;
+        Ok(count)
+    }
+}
+
+// Intervalo da recarga periódica das regras do Postgres
+const RULES_RELOAD_INTERVAL_SECONDS: u64 = 60;
+
+pub async fn start_table_rules_reload(globs: &Arc<GlobalVars>) {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(RULES_RELOAD_INTERVAL_SECONDS)).await;
+        if let Err(err) = globs.table_resolver.reload(globs) {
+            let msg_error = format!("Erro ao recarregar regras de tabelas do DynamoDB: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            eprintln!("{}", msg_error);
+        }
+    }
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => wildcard_match(&pattern[1..], text) || (!text.is_empty() && wildcard_match(pattern, &text[1..])),
+        (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => wildcard_match(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+fn render_template(template: &str, dev_id: &str) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else { break };
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..start + len];
+        match placeholder.split_once(':') {
+            Some(("dev_id", n)) => {
+                // Os n primeiros caracteres (não bytes), para não cortar um caractere multibyte ao meio
+                let end = n.parse::<usize>().ok()
+                    .and_then(|n| dev_id.char_indices().nth(n))
+                    .map(|(index, _)| index)
+                    .unwrap_or(dev_id.len());
+                rendered.push_str(&dev_id[..end]);
+            },
+            None if placeholder == "dev_id" => rendered.push_str(dev_id),
+            _ => rendered.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template_cuts_the_device_code_by_characters() {
+        assert_eq!(render_template("{dev_id:3}XXXX_RAW", "DAC210"), "DACXXXX_RAW");
+        assert_eq!(render_template("{dev_id}_RAW", "DAC210"), "DAC210_RAW");
+        assert_eq!(render_template("{dev_id:10}_RAW", "DAC210"), "DAC210_RAW");
+        assert_eq!(render_template("{dev_id:2}_RAW", "ÇÃO1"), "ÇÃ_RAW");
+        assert_eq!(render_template("{other}_{dev_id:1}", "DAC210"), "{other}_D");
+    }
+}
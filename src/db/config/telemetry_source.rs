@@ -0,0 +1,54 @@
+use std::future::Future;
+use serde::de::DeserializeOwned;
+
+use crate::configs::ConfigFile;
+use crate::GlobalVars;
+use super::dynamo::QuerierDevIdTimestamp;
+use super::local_telemetry::QuerierLocalFile;
+use super::table_resolution::DeviceType;
+
+/// Origem das telemetrias brutas de um dispositivo. A consulta é sempre de um dispositivo
+/// (definido na criação do querier) em um intervalo de tempo, entregando os itens em páginas.
+pub trait TelemetrySource {
+    fn run<T, F>(&self, ts_ini: &str, ts_end: &str, proc_items: &mut F) -> impl Future<Output = Result<(), String>> + Send
+    where
+        T: DeserializeOwned + Send,
+        F: FnMut(Vec<T>) -> Result<(), String>,
+        F: Send;
+}
+
+/// Querier usado pelos compiladores, escolhe o backend de acordo com o configfile:
+/// se LOCAL_TELEMETRY_DIR estiver preenchido, lê os dumps JSONL locais em vez do DynamoDB.
+pub enum TelemetryQuerier {
+    Dynamo(QuerierDevIdTimestamp),
+    LocalFile(QuerierLocalFile),
+}
+
+impl TelemetryQuerier {
+    /// Resolve a tabela e as chaves do dispositivo pelas regras de nome de tabela. None se a geração é desconhecida.
+    pub fn for_device(globs: &GlobalVars, device_type: DeviceType, dev_id: &str) -> Option<Self> {
+        let table = globs.table_resolver.resolve(device_type, dev_id)?;
+        Some(Self::new_custom(&globs.configfile, table.table_name, table.partition_key, table.sort_key, dev_id.to_owned()))
+    }
+
+    pub fn new_custom(configfile: &ConfigFile, table_name: String, key_var_name: String, order_var_name: String, part_key: String) -> Self {
+        match &configfile.LOCAL_TELEMETRY_DIR {
+            Some(dir) if !dir.is_empty() => Self::LocalFile(QuerierLocalFile::new(dir.to_owned(), key_var_name, order_var_name, part_key)),
+            _ => Self::Dynamo(QuerierDevIdTimestamp::new_custom(table_name, key_var_name, order_var_name, part_key)),
+        }
+    }
+}
+
+impl TelemetrySource for TelemetryQuerier {
+    async fn run<T, F>(&self, ts_ini: &str, ts_end: &str, proc_items: &mut F) -> Result<(), String>
+    where
+        T: DeserializeOwned + Send,
+        F: FnMut(Vec<T>) -> Result<(), String>,
+        F: Send,
+    {
+        match self {
+            Self::Dynamo(querier) => querier.run(ts_ini, ts_end, proc_items).await,
+            Self::LocalFile(querier) => querier.run(ts_ini, ts_end, proc_items).await,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use crate::models::database_models::api_server_snapshots::ApiServerSnapshot;
+use crate::schema::api_server_snapshots;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn upsert_api_server_snapshot(data: &ApiServerSnapshot, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    diesel::insert_into(api_server_snapshots::table)
+        .values(data)
+        .on_conflict((api_server_snapshots::snapshot_type, api_server_snapshots::reference_id, api_server_snapshots::record_date))
+        .do_update()
+        .set((
+            api_server_snapshots::parent_reference_id.eq(excluded(api_server_snapshots::parent_reference_id)),
+            api_server_snapshots::payload.eq(excluded(api_server_snapshots::payload)),
+            api_server_snapshots::changed.eq(excluded(api_server_snapshots::changed)),
+            api_server_snapshots::fetched_at.eq(excluded(api_server_snapshots::fetched_at)),
+            api_server_snapshots::deleted.eq(excluded(api_server_snapshots::deleted)),
+            api_server_snapshots::other_timezone.eq(excluded(api_server_snapshots::other_timezone)),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(())
+}
+
+// Snapshot vigente em um dia: o mais recente gravado até aquele dia (pode ser a marca de remoção)
+pub fn get_api_server_snapshot(snapshot_type: &str, reference_id: i32, day: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Option<ApiServerSnapshot>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let snapshot = api_server_snapshots::table
+        .filter(api_server_snapshots::snapshot_type.eq(snapshot_type))
+        .filter(api_server_snapshots::reference_id.eq(reference_id))
+        .filter(api_server_snapshots::record_date.le(day))
+        .order(api_server_snapshots::record_date.desc())
+        .first::<ApiServerSnapshot>(&mut pool)
+        .optional()?;
+
+    Ok(snapshot)
+}
+
+// Snapshots vigentes no dia de cada referência do tipo, incluindo as marcas de remoção
+pub fn get_api_server_snapshots(snapshot_type: &str, parent_reference_id: Option<i32>, day: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<ApiServerSnapshot>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let mut snapshots = api_server_snapshots::table
+        .filter(api_server_snapshots::snapshot_type.eq(snapshot_type))
+        .filter(api_server_snapshots::record_date.le(day))
+        .distinct_on(api_server_snapshots::reference_id)
+        .order((api_server_snapshots::reference_id.asc(), api_server_snapshots::record_date.desc()))
+        .load::<ApiServerSnapshot>(&mut pool)?;
+
+    // O pai é filtrado só depois de escolher o snapshot vigente, para que uma referência que mudou de pai
+    // não seja encontrada também pelo snapshot antigo
+    if let Some(parent_reference_id) = parent_reference_id {
+        snapshots.retain(|snapshot| snapshot.parent_reference_id == Some(parent_reference_id));
+    }
+
+    Ok(snapshots)
+}
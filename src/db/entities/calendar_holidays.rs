@@ -0,0 +1,104 @@
+use chrono::{NaiveDate, Utc};
+use diesel::sql_types::{Date, Integer};
+use diesel::{prelude::*, sql_query};
+use crate::models::database_models::calendar_holidays::{CalendarHoliday, CalendarHolidayRow};
+use crate::schema::calendar_holidays;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn get_calendar_holidays(scope: Option<&str>, state_name: Option<&str>, city_name: Option<&str>, client_id: Option<i32>, globs: &Arc<GlobalVars>) -> Result<Vec<CalendarHolidayRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let mut query = calendar_holidays::table.into_boxed();
+    if let Some(scope) = scope {
+        query = query.filter(calendar_holidays::scope.eq(scope));
+    }
+    if let Some(state_name) = state_name {
+        query = query.filter(calendar_holidays::state_name.eq(state_name));
+    }
+    if let Some(city_name) = city_name {
+        query = query.filter(calendar_holidays::city_name.eq(city_name));
+    }
+    if let Some(client_id) = client_id {
+        query = query.filter(calendar_holidays::client_id.eq(client_id));
+    }
+
+    let holidays = query
+        .order(calendar_holidays::start_date.asc())
+        .load::<CalendarHolidayRow>(&mut pool)?;
+
+    Ok(holidays)
+}
+
+/// Feriados cadastrados que valem para a unidade no período: nacionais, do estado e da cidade da unidade
+/// (comparados sem diferenciar maiúsculas) e do cliente dono da unidade
+pub fn get_unit_calendar_holidays(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<CalendarHolidayRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            ch.*
+        FROM
+            calendar_holidays ch
+            INNER JOIN units u ON (u.id = $1)
+        WHERE
+            (
+                ch.scope = 'NATIONAL' OR
+                (ch.scope = 'STATE' AND UPPER(TRIM(ch.state_name)) = UPPER(TRIM(u.state_name))) OR
+                (ch.scope = 'CITY' AND UPPER(TRIM(ch.city_name)) = UPPER(TRIM(u.city_name)) AND
+                    (ch.state_name IS NULL OR UPPER(TRIM(ch.state_name)) = UPPER(TRIM(u.state_name)))) OR
+                (ch.scope = 'CLIENT' AND ch.client_id = u.client_id)
+            ) AND
+            (ch.recurring OR (ch.start_date <= $3 AND COALESCE(ch.end_date, ch.start_date) >= $2))
+        ORDER BY
+            ch.start_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Date, _>(start_date)
+        .bind::<Date, _>(end_date)
+        .load::<CalendarHolidayRow>(&mut pool)?;
+
+    Ok(response)
+}
+
+pub fn insert_calendar_holiday(data: &CalendarHoliday, globs: &Arc<GlobalVars>) -> Result<i32, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let id = diesel::insert_into(calendar_holidays::table)
+        .values(data)
+        .returning(calendar_holidays::id)
+        .get_result::<i32>(&mut pool)?;
+
+    Ok(id)
+}
+
+pub fn update_calendar_holiday(id: i32, data: &CalendarHoliday, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let updated = diesel::update(calendar_holidays::table.filter(calendar_holidays::id.eq(id)))
+        .set((
+            calendar_holidays::name.eq(&data.name),
+            calendar_holidays::scope.eq(&data.scope),
+            calendar_holidays::start_date.eq(data.start_date),
+            calendar_holidays::end_date.eq(data.end_date),
+            calendar_holidays::recurring.eq(data.recurring),
+            calendar_holidays::state_name.eq(&data.state_name),
+            calendar_holidays::city_name.eq(&data.city_name),
+            calendar_holidays::client_id.eq(data.client_id),
+            calendar_holidays::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(updated)
+}
+
+pub fn delete_calendar_holiday(id: i32, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(calendar_holidays::table.filter(calendar_holidays::id.eq(id)))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
@@ -0,0 +1,72 @@
+use chrono::Utc;
+use diesel::sql_types::{Integer, Nullable, Text};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::models::database_models::client_energy_forecast_models::ClientEnergyForecastModel;
+use crate::schema::client_energy_forecast_models;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+#[derive(QueryableByName)]
+struct ForecastModelName {
+    #[diesel(sql_type = Nullable<Text>)]
+    forecast_model: Option<String>,
+}
+
+pub fn set_client_forecast_model(data: ClientEnergyForecastModel, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    diesel::insert_into(client_energy_forecast_models::table)
+        .values(&data)
+        .on_conflict(client_energy_forecast_models::client_id)
+        .do_update()
+        .set((
+            client_energy_forecast_models::forecast_model.eq(excluded(client_energy_forecast_models::forecast_model)),
+            client_energy_forecast_models::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(())
+}
+
+/// Modelo de previsão escolhido para o cliente dono do circuito, se houver
+pub fn get_circuit_forecast_model(electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<String>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            cefm.forecast_model
+        FROM
+            electric_circuits ec
+            INNER JOIN units u ON u.id = ec.unit_id
+            LEFT JOIN client_energy_forecast_models cefm ON cefm.client_id = u.client_id
+        WHERE
+            ec.id = $1";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(electric_circuit_id)
+        .load::<ForecastModelName>(&mut pool)?;
+
+    Ok(response.into_iter().next().and_then(|row| row.forecast_model))
+}
+
+/// Modelo de previsão escolhido para o cliente dono da unidade, se houver
+pub fn get_unit_forecast_model(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<String>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            cefm.forecast_model
+        FROM
+            units u
+            LEFT JOIN client_energy_forecast_models cefm ON cefm.client_id = u.client_id
+        WHERE
+            u.id = $1";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .load::<ForecastModelName>(&mut pool)?;
+
+    Ok(response.into_iter().next().and_then(|row| row.forecast_model))
+}
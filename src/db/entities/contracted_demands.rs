@@ -0,0 +1,96 @@
+use chrono::NaiveDate;
+use diesel::sql_types::{Integer, Text};
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::contracted_demand::GetDemandOverrunEventResponse;
+use crate::models::database_models::contracted_demands::{ContractedDemand, ContractedDemandRow, DemandOverrunEvent};
+use crate::schema::{contracted_demands, demand_overrun_events};
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn get_contracted_demands(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<ContractedDemandRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let demands = contracted_demands::table
+        .filter(contracted_demands::unit_id.eq(unit_id))
+        .order(contracted_demands::start_date.asc())
+        .load::<ContractedDemandRow>(&mut pool)?;
+
+    Ok(demands)
+}
+
+pub fn insert_contracted_demand(data: &ContractedDemand, globs: &Arc<GlobalVars>) -> Result<i32, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let id = diesel::insert_into(contracted_demands::table)
+        .values(data)
+        .returning(contracted_demands::id)
+        .get_result::<i32>(&mut pool)?;
+
+    Ok(id)
+}
+
+pub fn delete_contracted_demand(id: i32, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(contracted_demands::table.filter(contracted_demands::id.eq(id)))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
+
+/// Substitui os eventos de ultrapassagem do dia, para que o reprocessamento não deixe eventos antigos
+pub fn replace_demand_overrun_events(contracted_demand_ids: &[i32], day: NaiveDate, events: &[DemandOverrunEvent], globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    pool.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(demand_overrun_events::table
+            .filter(demand_overrun_events::contracted_demand_id.eq_any(contracted_demand_ids))
+            .filter(demand_overrun_events::event_date.eq(day)))
+            .execute(conn)?;
+
+        diesel::insert_into(demand_overrun_events::table)
+            .values(events)
+            .execute(conn)?;
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+pub fn get_demand_overrun_events(unit_id: i32, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetDemandOverrunEventResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            demand_overrun_events.event_date,
+            demand_overrun_events.tariff_period,
+            demand_overrun_events.record_date,
+            electric_circuits.reference_id AS electric_circuit_reference_id,
+            demand_overrun_events.contracted_demand,
+            demand_overrun_events.tolerance_limit,
+            demand_overrun_events.measured_demand,
+            demand_overrun_events.overrun_demand,
+            demand_overrun_events.estimated_penalty
+        FROM
+            demand_overrun_events
+            INNER JOIN contracted_demands ON (contracted_demands.id = demand_overrun_events.contracted_demand_id)
+            INNER JOIN units ON (units.id = contracted_demands.unit_id)
+            LEFT JOIN electric_circuits ON (electric_circuits.id = contracted_demands.electric_circuit_id)
+        WHERE
+            units.reference_id = $1 AND
+            demand_overrun_events.event_date >= to_date($2, 'YYYY-MM-DD') AND
+            demand_overrun_events.event_date <= to_date($3, 'YYYY-MM-DD')
+        ORDER BY
+            demand_overrun_events.event_date,
+            demand_overrun_events.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<GetDemandOverrunEventResponse>(&mut pool)?;
+
+    Ok(response)
+}
@@ -0,0 +1,65 @@
+use diesel::sql_types::{Array, Integer, Text};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::dynamo_consumed_capacity::GetConsumedCapacityResponse;
+use crate::models::database_models::dynamo_consumed_capacity_hist::DynamoConsumedCapacityHist;
+use crate::schema::dynamo_consumed_capacity_hist;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn insert_data_dynamo_consumed_capacity(data: DynamoConsumedCapacityHist, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    // O mesmo dia pode ser gravado por várias execuções, então os valores são somados
+    diesel::insert_into(dynamo_consumed_capacity_hist::table)
+        .values(&data)
+        .on_conflict((dynamo_consumed_capacity_hist::table_name, dynamo_consumed_capacity_hist::pipeline, dynamo_consumed_capacity_hist::client_reference_id, dynamo_consumed_capacity_hist::record_date))
+        .do_update()
+        .set((
+            dynamo_consumed_capacity_hist::consumed_rcu.eq(dynamo_consumed_capacity_hist::consumed_rcu + excluded(dynamo_consumed_capacity_hist::consumed_rcu)),
+            dynamo_consumed_capacity_hist::cache_hits.eq(dynamo_consumed_capacity_hist::cache_hits + excluded(dynamo_consumed_capacity_hist::cache_hits)),
+            dynamo_consumed_capacity_hist::cache_hit_rcu.eq(dynamo_consumed_capacity_hist::cache_hit_rcu + excluded(dynamo_consumed_capacity_hist::cache_hit_rcu)),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(())
+}
+
+pub fn get_consumed_capacity(start_date: &str, end_date: &str, client_reference_ids: Option<Vec<i32>>, globs: &Arc<GlobalVars>) -> Result<Vec<GetConsumedCapacityResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let consumed_capacity = sql_query("
+    SELECT
+        dynamo_consumed_capacity_hist.record_date,
+        dynamo_consumed_capacity_hist.client_reference_id,
+        dynamo_consumed_capacity_hist.pipeline,
+        dynamo_consumed_capacity_hist.table_name,
+        SUM(dynamo_consumed_capacity_hist.consumed_rcu) AS consumed_rcu,
+        SUM(dynamo_consumed_capacity_hist.cache_hits) AS cache_hits,
+        SUM(dynamo_consumed_capacity_hist.cache_hit_rcu) AS cache_hit_rcu
+    FROM
+        dynamo_consumed_capacity_hist
+    WHERE
+        dynamo_consumed_capacity_hist.record_date >= to_date($1, 'YYYY-MM-DD') AND
+        dynamo_consumed_capacity_hist.record_date <= to_date($2, 'YYYY-MM-DD') AND
+        (cardinality($3::integer[]) = 0 OR dynamo_consumed_capacity_hist.client_reference_id = ANY($3::integer[]))
+    GROUP BY
+        dynamo_consumed_capacity_hist.record_date,
+        dynamo_consumed_capacity_hist.client_reference_id,
+        dynamo_consumed_capacity_hist.pipeline,
+        dynamo_consumed_capacity_hist.table_name
+    ORDER BY
+        dynamo_consumed_capacity_hist.record_date ASC,
+        consumed_rcu DESC
+    ");
+
+    let consumed_capacity = consumed_capacity
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .bind::<Array<Integer>, _>(client_reference_ids.unwrap_or_default());
+
+    let response = consumed_capacity.load::<GetConsumedCapacityResponse>(&mut pool)?;
+
+    Ok(response)
+}
@@ -0,0 +1,59 @@
+use diesel::sql_types::{Integer, Nullable, Text};
+use diesel::{prelude::*, sql_query};
+use crate::models::database_models::dynamo_table_rules::{DynamoTableRule, DynamoTableRuleRow};
+use crate::schema::dynamo_table_rules;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn get_dynamo_table_rules(globs: &Arc<GlobalVars>) -> Result<Vec<DynamoTableRuleRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let rules = dynamo_table_rules::table
+        .order((dynamo_table_rules::priority.desc(), dynamo_table_rules::id.asc()))
+        .load::<DynamoTableRuleRow>(&mut pool)?;
+
+    Ok(rules)
+}
+
+#[derive(QueryableByName)]
+struct UpsertedRule {
+    #[diesel(sql_type = Integer)]
+    id: i32,
+}
+
+/// Regras são únicas por tipo de dispositivo e padrão: a mesma regra sem tipo (NULL) vale para todos
+pub fn upsert_dynamo_table_rule(data: &DynamoTableRule, globs: &Arc<GlobalVars>) -> Result<i32, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        INSERT INTO dynamo_table_rules (device_type, pattern, table_template, partition_key, sort_key, priority)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT ((COALESCE(device_type, '')), pattern) DO UPDATE SET
+            table_template = EXCLUDED.table_template,
+            partition_key = EXCLUDED.partition_key,
+            sort_key = EXCLUDED.sort_key,
+            priority = EXCLUDED.priority,
+            updated_at = NOW()
+        RETURNING id";
+
+    let rule = sql_query(sql)
+        .bind::<Nullable<Text>, _>(data.device_type.as_deref())
+        .bind::<Text, _>(&data.pattern)
+        .bind::<Text, _>(&data.table_template)
+        .bind::<Text, _>(&data.partition_key)
+        .bind::<Text, _>(&data.sort_key)
+        .bind::<Integer, _>(data.priority)
+        .get_result::<UpsertedRule>(&mut pool)?;
+
+    Ok(rule.id)
+}
+
+pub fn delete_dynamo_table_rule(id: i32, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(dynamo_table_rules::table.filter(dynamo_table_rules::id.eq(id)))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
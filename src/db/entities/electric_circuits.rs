@@ -1,4 +1,7 @@
-use diesel::prelude::*;
+use diesel::sql_types::Integer;
+use diesel::{prelude::*, sql_query};
+use rust_decimal::Decimal;
+use crate::http::structs::electric_circuits::GetUnitElectricCircuitResponse;
 use crate::models::database_models::electric_circuits::ElectricCircuit;
 use crate::schedules::scheduler::write_to_log_file_thread;
 use crate::{schema, GlobalVars};
@@ -45,6 +48,20 @@ pub fn get_electric_circuit(reference_electric_circuit_id: i32, globs: &Arc<Glob
     }
 }
 
+/// Id do circuito pelo id de referência, apenas se ele pertencer à unidade
+pub fn get_unit_electric_circuit(unit_id: i32, reference_electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<i32>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let electric_circuit_id = schema::electric_circuits::table
+        .filter(schema::electric_circuits::unit_id.eq(unit_id))
+        .filter(schema::electric_circuits::reference_id.eq(reference_electric_circuit_id))
+        .select(schema::electric_circuits::id)
+        .first::<i32>(&mut pool)
+        .optional()?;
+
+    Ok(electric_circuit_id)
+}
+
 pub fn update_electric_circuit(electric_circuit_id: i32, name: &str, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
     let mut pool = globs.pool.get()?;
 
@@ -56,3 +73,198 @@ pub fn update_electric_circuit(electric_circuit_id: i32, name: &str, globs: &Arc
 
     Ok(())
 }
+
+/// Estratégia de preenchimento de horas sem medição, limite de consumo por hora e valor de virada do registrador configurados no circuito
+pub type ElectricCircuitEnergySettings = (Option<String>, Option<Decimal>, Option<Decimal>);
+
+pub fn get_electric_circuit_energy_settings(electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<ElectricCircuitEnergySettings>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let settings = schema::electric_circuits::table
+        .filter(schema::electric_circuits::id.eq(electric_circuit_id))
+        .select((schema::electric_circuits::gap_fill_strategy, schema::electric_circuits::max_hour_consumption, schema::electric_circuits::register_rollover_value))
+        .first(&mut pool)
+        .optional()?;
+
+    Ok(settings)
+}
+
+pub fn update_electric_circuit_energy_settings(reference_electric_circuit_id: i32, gap_fill_strategy: Option<String>, max_hour_consumption: Option<Decimal>, register_rollover_value: Option<Decimal>, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let updated = diesel::update(schema::electric_circuits::table.filter(schema::electric_circuits::reference_id.eq(reference_electric_circuit_id)))
+        .set((
+            schema::electric_circuits::gap_fill_strategy.eq(gap_fill_strategy),
+            schema::electric_circuits::max_hour_consumption.eq(max_hour_consumption),
+            schema::electric_circuits::register_rollover_value.eq(register_rollover_value),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(updated)
+}
+
+pub fn get_electric_circuit_nominal_voltage(electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<Decimal>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let nominal_voltage = schema::electric_circuits::table
+        .filter(schema::electric_circuits::id.eq(electric_circuit_id))
+        .select(schema::electric_circuits::nominal_voltage)
+        .first::<Option<Decimal>>(&mut pool)
+        .optional()?;
+
+    Ok(nominal_voltage.flatten())
+}
+
+pub fn update_electric_circuit_nominal_voltage(reference_electric_circuit_id: i32, nominal_voltage: Option<Decimal>, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let updated = diesel::update(schema::electric_circuits::table.filter(schema::electric_circuits::reference_id.eq(reference_electric_circuit_id)))
+        .set(schema::electric_circuits::nominal_voltage.eq(nominal_voltage))
+        .execute(&mut pool)?;
+
+    Ok(updated)
+}
+
+pub fn update_electric_circuit_parent(electric_circuit_id: i32, parent_id: Option<i32>, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    diesel::update(schema::electric_circuits::table.filter(schema::electric_circuits::id.eq(electric_circuit_id)))
+        .set(schema::electric_circuits::parent_id.eq(parent_id))
+        .execute(&mut pool)?;
+
+    Ok(())
+}
+
+/// Circuitos da unidade com o circuito pai de cada um (ids de referência)
+pub fn get_unit_electric_circuits(unit_reference_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<GetUnitElectricCircuitResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            electric_circuits.reference_id AS electric_circuit_reference_id,
+            electric_circuits.name,
+            parent.reference_id AS parent_electric_circuit_reference_id,
+            electric_circuits.virtual_formula
+        FROM
+            electric_circuits
+            INNER JOIN units ON (units.id = electric_circuits.unit_id)
+            LEFT JOIN electric_circuits parent ON (parent.id = electric_circuits.parent_id)
+        WHERE
+            units.reference_id = $1
+        ORDER BY
+            electric_circuits.reference_id";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_reference_id)
+        .load::<GetUnitElectricCircuitResponse>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Circuito virtual: (id, id de referência, fórmula)
+pub type VirtualCircuit = (i32, i32, String);
+
+pub fn get_unit_virtual_circuits(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<VirtualCircuit>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let circuits: Vec<(i32, i32, Option<String>)> = schema::electric_circuits::table
+        .filter(schema::electric_circuits::unit_id.eq(unit_id))
+        .filter(schema::electric_circuits::virtual_formula.is_not_null())
+        .select((schema::electric_circuits::id, schema::electric_circuits::reference_id, schema::electric_circuits::virtual_formula))
+        .load(&mut pool)?;
+
+    Ok(circuits.into_iter().filter_map(|(id, reference_id, formula)| Some((id, reference_id, formula?))).collect())
+}
+
+/// Circuitos medidos (não virtuais) da unidade: (id, id de referência)
+pub fn get_unit_measured_circuits(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<(i32, i32)>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let circuits = schema::electric_circuits::table
+        .filter(schema::electric_circuits::unit_id.eq(unit_id))
+        .filter(schema::electric_circuits::virtual_formula.is_null())
+        .select((schema::electric_circuits::id, schema::electric_circuits::reference_id))
+        .load(&mut pool)?;
+
+    Ok(circuits)
+}
+
+/// Circuito virtual pelo id de referência: (id, unit_id)
+pub fn get_virtual_electric_circuit(reference_electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<(i32, i32)>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let circuit = schema::electric_circuits::table
+        .filter(schema::electric_circuits::reference_id.eq(reference_electric_circuit_id))
+        .filter(schema::electric_circuits::virtual_formula.is_not_null())
+        .select((schema::electric_circuits::id, schema::electric_circuits::unit_id))
+        .first(&mut pool)
+        .optional()?;
+
+    Ok(circuit)
+}
+
+/// Cria um circuito virtual. Como não vem do API Server, recebe id de referência negativo (-id) para não colidir
+/// com os circuitos medidos. Retorna o id de referência gerado
+pub fn insert_virtual_electric_circuit(unit_id: i32, name: &str, formula: &str, globs: &Arc<GlobalVars>) -> Result<i32, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let reference_id = pool.transaction::<i32, diesel::result::Error, _>(|conn| {
+        let id: i32 = diesel::insert_into(schema::electric_circuits::table)
+            .values((
+                schema::electric_circuits::unit_id.eq(unit_id),
+                schema::electric_circuits::name.eq(name),
+                schema::electric_circuits::reference_id.eq(0),
+                schema::electric_circuits::virtual_formula.eq(formula),
+            ))
+            .returning(schema::electric_circuits::id)
+            .get_result(conn)?;
+
+        diesel::update(schema::electric_circuits::table.filter(schema::electric_circuits::id.eq(id)))
+            .set(schema::electric_circuits::reference_id.eq(-id))
+            .execute(conn)?;
+
+        Ok(-id)
+    })?;
+
+    Ok(reference_id)
+}
+
+pub fn update_virtual_electric_circuit(electric_circuit_id: i32, name: &str, formula: &str, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let updated = diesel::update(schema::electric_circuits::table.filter(schema::electric_circuits::id.eq(electric_circuit_id)))
+        .set((
+            schema::electric_circuits::name.eq(name),
+            schema::electric_circuits::virtual_formula.eq(formula),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(updated)
+}
+
+/// Remove o circuito virtual junto com o consumo, a demanda e a previsão calculados para ele
+pub fn delete_virtual_electric_circuit(electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = pool.transaction::<usize, diesel::result::Error, _>(|conn| {
+        diesel::delete(schema::energy_hist::table.filter(schema::energy_hist::electric_circuit_id.eq(electric_circuit_id))).execute(conn)?;
+        diesel::delete(schema::energy_demand_minutes_hist::table.filter(schema::energy_demand_minutes_hist::electric_circuit_id.eq(electric_circuit_id))).execute(conn)?;
+        diesel::delete(schema::energy_consumption_forecast::table.filter(schema::energy_consumption_forecast::electric_circuit_id.eq(electric_circuit_id))).execute(conn)?;
+        diesel::delete(schema::contracted_demands::table.filter(schema::contracted_demands::electric_circuit_id.eq(electric_circuit_id))).execute(conn)?;
+        diesel::delete(schema::electric_circuits::table.filter(schema::electric_circuits::id.eq(electric_circuit_id))).execute(conn)
+    })?;
+
+    Ok(deleted)
+}
+
+pub fn get_electric_circuit_unit_id(electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> Result<Option<i32>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let unit_id = schema::electric_circuits::table
+        .filter(schema::electric_circuits::id.eq(electric_circuit_id))
+        .select(schema::electric_circuits::unit_id)
+        .first::<i32>(&mut pool)
+        .optional()?;
+
+    Ok(unit_id)
+}
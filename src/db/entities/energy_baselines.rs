@@ -0,0 +1,130 @@
+use chrono::{NaiveDate, Utc};
+use diesel::sql_types::{Date, Integer};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::energy_baselines::UnitDayConsumption;
+use crate::models::database_models::energy_baselines::{EnergyBaseline, EnergyBaselineRow, UnitBaselineVariables, UnitBaselineVariablesRow};
+use crate::schema::{energy_baselines, unit_baseline_variables};
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn upsert_unit_baseline_variables(data: &[UnitBaselineVariables], globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let upserted = diesel::insert_into(unit_baseline_variables::table)
+        .values(data)
+        .on_conflict((unit_baseline_variables::unit_id, unit_baseline_variables::day))
+        .do_update()
+        .set((
+            unit_baseline_variables::average_temperature.eq(excluded(unit_baseline_variables::average_temperature)),
+            unit_baseline_variables::operating_hours.eq(excluded(unit_baseline_variables::operating_hours)),
+            unit_baseline_variables::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(upserted)
+}
+
+pub fn get_unit_baseline_variables(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<UnitBaselineVariablesRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let variables = unit_baseline_variables::table
+        .filter(unit_baseline_variables::unit_id.eq(unit_id))
+        .filter(unit_baseline_variables::day.between(start_date, end_date))
+        .order(unit_baseline_variables::day.asc())
+        .load::<UnitBaselineVariablesRow>(&mut pool)?;
+
+    Ok(variables)
+}
+
+pub fn insert_energy_baseline(data: &EnergyBaseline, globs: &Arc<GlobalVars>) -> Result<EnergyBaselineRow, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let baseline = diesel::insert_into(energy_baselines::table)
+        .values(data)
+        .get_result::<EnergyBaselineRow>(&mut pool)?;
+
+    Ok(baseline)
+}
+
+pub fn get_energy_baseline(id: i32, globs: &Arc<GlobalVars>) -> Result<Option<EnergyBaselineRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let baseline = energy_baselines::table
+        .filter(energy_baselines::id.eq(id))
+        .first::<EnergyBaselineRow>(&mut pool)
+        .optional()?;
+
+    Ok(baseline)
+}
+
+pub fn get_unit_energy_baselines(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<EnergyBaselineRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let baselines = energy_baselines::table
+        .filter(energy_baselines::unit_id.eq(unit_id))
+        .order(energy_baselines::created_at.desc())
+        .load::<EnergyBaselineRow>(&mut pool)?;
+
+    Ok(baselines)
+}
+
+pub fn delete_energy_baseline(id: i32, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(energy_baselines::table.filter(energy_baselines::id.eq(id)))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
+
+/// Consumo diário somado entre os circuitos raiz (não virtuais) da unidade, com a quantidade de horas
+/// gravadas por todos os circuitos raiz e se todas são válidas
+pub fn get_unit_daily_consumption(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<UnitDayConsumption>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        WITH root_circuits AS (
+            SELECT id
+            FROM electric_circuits
+            WHERE
+                unit_id = $1 AND
+                parent_id IS NULL AND
+                virtual_formula IS NULL
+        ),
+        unit_hours AS (
+            SELECT
+                energy_hist.record_date,
+                SUM(energy_hist.consumption) AS consumption,
+                COUNT(DISTINCT energy_hist.electric_circuit_id) AS circuits,
+                BOOL_AND(COALESCE(energy_hist.is_valid_consumption, true)) AS all_valid
+            FROM
+                energy_hist
+            WHERE
+                energy_hist.electric_circuit_id IN (SELECT id FROM root_circuits) AND
+                energy_hist.record_date >= $2 AND
+                energy_hist.record_date < $3 + INTERVAL '1 day'
+            GROUP BY
+                energy_hist.record_date
+        )
+        SELECT
+            record_date::date AS day,
+            SUM(consumption) AS consumption,
+            COUNT(*) FILTER (WHERE circuits = (SELECT COUNT(*) FROM root_circuits)) AS hours,
+            BOOL_AND(all_valid) AS all_valid
+        FROM
+            unit_hours
+        GROUP BY
+            record_date::date
+        ORDER BY
+            day";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Date, _>(start_date)
+        .bind::<Date, _>(end_date)
+        .load::<UnitDayConsumption>(&mut pool)?;
+
+    Ok(response)
+}
@@ -61,7 +61,8 @@ pub fn get_energy_consumption_target(params: GetEnergyTarget, globs: &Arc<Global
             INNER JOIN electric_circuits ec on ec.id = ecf.electric_circuit_id
         WHERE
             ecf.record_date between to_timestamp($1, 'YYYY-MM-DD HH24:MI:SS') and to_timestamp($2, 'YYYY-MM-DD HH24:MI:SS') + interval '1 month' - interval '1 day'
-            AND ec.reference_id = ANY($3::integer[])".to_string();
+            AND ec.reference_id = ANY($3::integer[])
+            AND ec.parent_id IS NULL".to_string();
 
     let sqlQuery = sql_query(sql)
         .bind::<Text, _>(params.startDate.clone())
@@ -145,13 +146,20 @@ pub fn energy_trends(params: &GetEnergyTrendsRequestBody, globs: &Arc<GlobalVars
 
     let mut sqlConsumptionTarget = format!("
         SELECT 
-            SUM(emct.consumption_target) as target
-        FROM
-            units u 
-            JOIN energy_monthly_consumption_target emct on emct.unit_id = u.id
-        WHERE 
-            emct.date_forecast = to_timestamp($2, 'YYYY-MM-DD')
-            {}", unitsFilterSQL);
+            SUM(unit_target.consumption_target) as target
+        FROM (
+            -- meta manual tem preferência sobre a automática do mesmo mês
+            SELECT DISTINCT ON (emct.unit_id)
+                emct.consumption_target
+            FROM
+                units u 
+                JOIN energy_monthly_consumption_target emct on emct.unit_id = u.id
+            WHERE 
+                emct.date_forecast = to_timestamp($2, 'YYYY-MM-DD')
+                {}
+            ORDER BY
+                emct.unit_id, emct.is_manual DESC
+        ) unit_target", unitsFilterSQL);
 
     let sqlQueryConsumptionTarget = sql_query(sqlConsumptionTarget)
         .bind::<Nullable<Array<Integer>>, _>(params.units.clone())
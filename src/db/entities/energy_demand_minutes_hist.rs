@@ -1,7 +1,9 @@
+use chrono::NaiveDateTime;
 use diesel::sql_types::{Array, Integer, Text};
 use diesel::upsert::excluded;
 use diesel::{prelude::*, sql_query};
 use crate::http::structs::energy_demand::{GetDemandInfoResponse, GetEnergyDemandResponse};
+use crate::http::structs::energy_tariffs::UnitDemand;
 use crate::models::database_models::energy_demand_minutes_hist::EnergyDemandMinutesHist;
 use crate::schedules::scheduler::write_to_log_file_thread;
 use crate::schema::energy_demand_minutes_hist;
@@ -204,3 +206,48 @@ pub fn get_demand_info_by_minutes(unit_id: i32, electric_circuit_ids: Vec<i32>,
     
     Ok(response)
 }
+
+pub fn get_circuit_demand_by_15_minutes(electric_circuit_id: i32, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<UnitDemand>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            record_date,
+            average_demand AS demand
+        FROM
+            energy_demand_minutes_hist
+        WHERE
+            electric_circuit_id = $1 AND
+            record_date >= to_timestamp($2, 'YYYY-MM-DD') AND
+            record_date < to_timestamp($3, 'YYYY-MM-DD') + INTERVAL '1 day'
+        ORDER BY
+            record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(electric_circuit_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<UnitDemand>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Demandas de 15 minutos gravadas para os circuitos no período
+pub fn get_circuits_demand_minutes_hist(electric_circuit_ids: &[i32], start_date: NaiveDateTime, end_date: NaiveDateTime, globs: &Arc<GlobalVars>) -> Result<Vec<EnergyDemandMinutesHist>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let response = energy_demand_minutes_hist::table
+        .filter(energy_demand_minutes_hist::electric_circuit_id.eq_any(electric_circuit_ids))
+        .filter(energy_demand_minutes_hist::record_date.ge(start_date))
+        .filter(energy_demand_minutes_hist::record_date.lt(end_date))
+        .select((
+            energy_demand_minutes_hist::average_demand,
+            energy_demand_minutes_hist::electric_circuit_id,
+            energy_demand_minutes_hist::min_demand,
+            energy_demand_minutes_hist::max_demand,
+            energy_demand_minutes_hist::record_date,
+        ))
+        .load::<EnergyDemandMinutesHist>(&mut pool)?;
+
+    Ok(response)
+}
@@ -5,24 +5,24 @@ use rust_decimal::{self, Decimal};
 use rust_decimal::prelude::ToPrimitive;
 use diesel::upsert::excluded;
 use diesel::{prelude::*, sql_query};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use crate::http::structs::energy::{GetDayEnergyConsumptionResponse, GetEnergyAnalysisHistFilterRequestBody, GetEnergyAnalysisHistFilterResponse, GetEnergyAnalysisHistRequestBody, GetEnergyAnalysisHistResponse, GetEnergyAnalysisListRequestBody, GetEnergyAnalysisListResponse, GetEnergyAnalysisListResponseSQL, GetEnergyConsumptionResponse, GetGeneralUnitsStats, GetHourEnergyConsumptionResponse, GetLastValidConsumption, GetProcelInsightsRequestBody, GetProcelInsigthsResponse, GetTotalDaysConsumptionUnit, GetTotalUnitsWithConsumption, GetUnitConsumptionByArea, GetUnitEnergyStats, GetUnitListProcelRequestBody, GetUnitListRequestBody, GetUnitListResponse, OrderByTypeEnum, ParamsGetTotalDaysConsumptionUnit, ProcelType};
 use crate::http::structs::energy::{GetEnergyAnalysisHistResponseWithFlags, GetEnergyAnalysisListResponseWithFlags};
+use crate::http::structs::electric_circuits::GetUnmeasuredLoadResponse;
+use crate::db::entities::grid_emission_factors::{emission_factor_grid, get_units_total_emissions};
+use crate::http::structs::energy_forecast::CircuitHourConsumption;
 use crate::models::database_models::energy_hist::EnergyHist;
+use crate::schedules::energy_gap_fill::legacy_fill_strategy;
 use crate::schedules::scheduler::write_to_log_file_thread;
 use crate::schema::{self, electric_circuits, energy_hist};
 use crate::GlobalVars;
 use std::sync::Arc;
 use std::error::Error;
 
-#[derive(Deserialize, Clone)]
-pub struct GetEnergyAverage {
-    pub date: String,
-    pub electCircuitId: i32,
-}
-
 #[derive(QueryableByName, Serialize, Clone, Copy)]
-pub struct GetEnergyAverageResponse {
+pub struct GetHourProfileResponse {
+    #[diesel(sql_type = Integer)]
+    pub hour: i32,
     #[diesel(sql_type = Numeric)]
     pub consumption_average: Decimal,
 }
@@ -38,6 +38,7 @@ pub fn insert_data_energy(data: EnergyHist, globs: &Arc<GlobalVars>) -> Result<(
             energy_hist::consumption.eq(excluded(energy_hist::consumption)),
             energy_hist::is_measured_consumption.eq(excluded(energy_hist::is_measured_consumption)),
             energy_hist::is_valid_consumption.eq(excluded(energy_hist::is_valid_consumption)),
+            energy_hist::fill_strategy.eq(excluded(energy_hist::fill_strategy)),
         ))
         .execute(&mut pool);
 
@@ -849,6 +850,7 @@ pub fn procel_insigths(params: &GetProcelInsightsRequestBody, globs: &Arc<Global
             averageConsumptionPreviousMonthPercentage: Decimal::new(0, 0),
             totalConsumption: Decimal::new(0, 0),
             totalCharged: Decimal::new(0, 0),
+            total_emissions: Decimal::new(0, 0),
             containsAnalysisData: false,
             containsProcel: false,
 
@@ -883,6 +885,11 @@ pub fn procel_insigths(params: &GetProcelInsightsRequestBody, globs: &Arc<Global
         })
     };
 
+    let mut total_emissions = get_units_total_emissions(&params.units, &params.startDate, &params.endDate, &emission_factor_grid(globs), &mut pool).unwrap_or_else(|err| {
+        write_to_log_file_thread(&format!("Erro ao calcular emissões das unidades {:?}: {}", params.units, err), 0, "ERROR");
+        Decimal::ZERO
+    });
+
     let stats = get_energy_stats_by_units(&GetUnitListProcelRequestBody {
         startDate: params.startDate.clone(),
         endDate: params.endDate.clone(),
@@ -905,6 +912,7 @@ pub fn procel_insigths(params: &GetProcelInsightsRequestBody, globs: &Arc<Global
             averageConsumptionPreviousMonthPercentage: Decimal::new(0, 0),
             totalConsumption: generalStats.total_consumption.unwrap_or_default(),
             totalCharged: generalStats.total_charged.unwrap_or_default(),
+            total_emissions,
             containsAnalysisData: true,
             containsProcel: false,
             classA: ProcelType {
@@ -1004,6 +1012,11 @@ pub fn procel_insigths(params: &GetProcelInsightsRequestBody, globs: &Arc<Global
             units: params.procelUnitsFilter.clone().unwrap()
         }, &mut pool).unwrap()[0];
 
+        total_emissions = get_units_total_emissions(&params.procelUnitsFilter.clone().unwrap(), &params.startDate, &params.endDate, &emission_factor_grid(globs), &mut pool).unwrap_or_else(|err| {
+            write_to_log_file_thread(&format!("Erro ao calcular emissões das unidades {:?}: {}", params.procelUnitsFilter, err), 0, "ERROR");
+            Decimal::ZERO
+        });
+
         previousMonthUnit = get_energy_stats_by_units(&GetUnitListProcelRequestBody {
             startDate: params.previousStartDate.clone(),
             endDate: params.previousEndDate.clone(),
@@ -1024,6 +1037,7 @@ pub fn procel_insigths(params: &GetProcelInsightsRequestBody, globs: &Arc<Global
         },
         totalConsumption: generalStats.total_consumption.unwrap_or_default(),
         totalCharged: generalStats.total_charged.unwrap_or_default(),
+        total_emissions,
         containsAnalysisData: true,
         containsProcel: match units.is_empty() {
             true => false,
@@ -1063,43 +1077,6 @@ pub fn procel_insigths(params: &GetProcelInsightsRequestBody, globs: &Arc<Global
     Ok(response)
 }
 
-pub fn get_energy_consumption_average(params: GetEnergyAverage, globs: &Arc<GlobalVars>) -> Result<(GetEnergyAverageResponse), Box<dyn Error>> {
-    let mut pool = globs.pool.get()?;
-
-    let mut sql = "
-        WITH last_consumptions AS (
-            SELECT 
-                eh.consumption as consumption,
-                eh.record_date as date
-            FROM
-                energy_hist eh 
-            WHERE
-                eh.record_date between to_timestamp($1, 'YYYY-MM-DD HH24:MI:SS') - interval '3 month' and to_timestamp($1, 'YYYY-MM-DD HH24:MI:SS')
-                AND EXTRACT (hour from eh.record_date) = EXTRACT (hour from to_timestamp($1, 'YYYY-MM-DD HH24:MI:SS'))
-                AND EXTRACT (dow from eh.record_date) = EXTRACT (dow from to_timestamp($1, 'YYYY-MM-DD HH24:MI:SS'))
-                AND electric_circuit_id = $2
-                AND eh.is_valid_consumption = true 
-            ORDER BY
-                eh.record_date Desc
-            LIMIT 4
-            OFFSET 0
-        )
-        SELECT
-            ROUND(AVG(lc.consumption), 2) as consumption_average
-        FROM
-            last_consumptions lc".to_string();
-
-    let sqlQuery = sql_query(sql)
-        .bind::<Text, _>(params.date.clone())
-        .bind::<Integer, _>(params.electCircuitId.clone());
-
-    let response = sqlQuery.load::<GetEnergyAverageResponse>(&mut pool)?;
-
-    drop(pool);
-        
-    Ok(response[0])
-}
-
 pub fn get_total_days_unit_with_consumption(params: ParamsGetTotalDaysConsumptionUnit, globs: &Arc<GlobalVars>) -> Result<GetTotalDaysConsumptionUnit, Box<dyn Error>> {
     let mut conn = PgConnection::establish(&globs.configfile.POSTGRES_DATABASE_URL).expect("CONNECTION DB ERROR: ");
 
@@ -1124,3 +1101,162 @@ pub fn get_total_days_unit_with_consumption(params: ParamsGetTotalDaysConsumptio
         
     Ok(response)
 }
+
+/// Consumo médio de cada hora do dia nas últimas semanas, apenas com horas medidas (dia típico do circuito)
+pub fn get_circuit_hour_profile(electric_circuit_id: i32, day: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetHourProfileResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            EXTRACT(hour FROM eh.record_date)::integer AS hour,
+            ROUND(AVG(eh.consumption), 3) AS consumption_average
+        FROM
+            energy_hist eh
+        WHERE
+            eh.electric_circuit_id = $1
+            AND eh.record_date >= to_timestamp($2, 'YYYY-MM-DD') - interval '28 days'
+            AND eh.record_date < to_timestamp($2, 'YYYY-MM-DD')
+            AND eh.is_valid_consumption = true
+            AND (eh.fill_strategy = 'MEASURED' OR (eh.fill_strategy IS NULL AND eh.is_measured_consumption IS NOT TRUE))
+        GROUP BY
+            EXTRACT(hour FROM eh.record_date)";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(electric_circuit_id)
+        .bind::<Text, _>(day)
+        .load::<GetHourProfileResponse>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Consumos horários válidos do circuito no período, em ordem cronológica. Com `hour`, apenas essa hora do dia
+pub fn get_circuit_hour_consumption(electric_circuit_id: i32, start_date: &str, end_date: &str, hour: Option<i32>, globs: &Arc<GlobalVars>) -> Result<Vec<CircuitHourConsumption>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            eh.electric_circuit_id,
+            eh.record_date,
+            eh.consumption
+        FROM
+            energy_hist eh
+        WHERE
+            eh.electric_circuit_id = $1
+            AND eh.record_date BETWEEN to_timestamp($2, 'YYYY-MM-DD HH24:MI:SS') AND to_timestamp($3, 'YYYY-MM-DD HH24:MI:SS')
+            AND ($4 IS NULL OR EXTRACT(hour FROM eh.record_date) = $4)
+            AND eh.is_valid_consumption = true
+        ORDER BY
+            eh.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(electric_circuit_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .bind::<Nullable<Integer>, _>(hour)
+        .load::<CircuitHourConsumption>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Consumos horários válidos de todos os circuitos da unidade no período, ordenados por circuito e data
+pub fn get_unit_circuits_hour_consumption(unit_id: i32, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<CircuitHourConsumption>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            eh.electric_circuit_id,
+            eh.record_date,
+            eh.consumption
+        FROM
+            energy_hist eh
+            INNER JOIN electric_circuits ec ON ec.id = eh.electric_circuit_id
+        WHERE
+            ec.unit_id = $1
+            AND eh.record_date BETWEEN to_timestamp($2, 'YYYY-MM-DD HH24:MI:SS') AND to_timestamp($3, 'YYYY-MM-DD HH24:MI:SS')
+            AND eh.is_valid_consumption = true
+        ORDER BY
+            eh.electric_circuit_id, eh.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<CircuitHourConsumption>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Consumo horário não medido de cada circuito pai da unidade: consumo do pai menos a soma dos filhos diretos
+pub fn get_unmeasured_load_by_hour(unit_reference_id: i32, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetUnmeasuredLoadResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            parent.reference_id AS electric_circuit_reference_id,
+            parent_hist.record_date,
+            parent_hist.consumption AS parent_consumption,
+            COALESCE(SUM(child_hist.consumption), 0) AS children_consumption,
+            parent_hist.consumption - COALESCE(SUM(child_hist.consumption), 0) AS unmeasured_consumption,
+            (parent_hist.is_valid_consumption AND COUNT(child_hist.electric_circuit_id) FILTER (WHERE child_hist.is_valid_consumption) = COUNT(child.id)) AS is_complete
+        FROM
+            electric_circuits parent
+            INNER JOIN units ON (units.id = parent.unit_id)
+            INNER JOIN electric_circuits child ON (child.parent_id = parent.id)
+            INNER JOIN energy_hist parent_hist ON (parent_hist.electric_circuit_id = parent.id)
+            LEFT JOIN energy_hist child_hist ON (child_hist.electric_circuit_id = child.id AND child_hist.record_date = parent_hist.record_date)
+        WHERE
+            units.reference_id = $1 AND
+            parent_hist.record_date >= to_timestamp($2, 'YYYY-MM-DD HH24:MI:SS') AND
+            parent_hist.record_date <= to_timestamp($3, 'YYYY-MM-DD HH24:MI:SS')
+        GROUP BY
+            parent.reference_id, parent_hist.record_date, parent_hist.consumption, parent_hist.is_valid_consumption
+        ORDER BY
+            parent.reference_id, parent_hist.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_reference_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<GetUnmeasuredLoadResponse>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Circuito, consumo, data, medido, válido e estratégia de preenchimento de uma hora de energy_hist
+type EnergyHistRow = (i32, Decimal, NaiveDateTime, Option<bool>, Option<bool>, Option<String>);
+
+/// Consumos horários gravados para os circuitos no período
+pub fn get_circuits_energy_hist(electric_circuit_ids: &[i32], start_date: NaiveDateTime, end_date: NaiveDateTime, globs: &Arc<GlobalVars>) -> Result<Vec<EnergyHist>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let rows: Vec<EnergyHistRow> = energy_hist::table
+        .filter(energy_hist::electric_circuit_id.eq_any(electric_circuit_ids))
+        .filter(energy_hist::record_date.ge(start_date))
+        .filter(energy_hist::record_date.lt(end_date))
+        .select((
+            energy_hist::electric_circuit_id,
+            energy_hist::consumption,
+            energy_hist::record_date,
+            energy_hist::is_measured_consumption,
+            energy_hist::is_valid_consumption,
+            energy_hist::fill_strategy,
+        ))
+        .load(&mut pool)?;
+
+    let response = rows.into_iter()
+        .map(|(electric_circuit_id, consumption, record_date, is_measured_consumption, is_valid_consumption, fill_strategy)| {
+            let is_measured_consumption = is_measured_consumption.unwrap_or(false);
+            let is_valid_consumption = is_valid_consumption.unwrap_or(false);
+            EnergyHist {
+                electric_circuit_id,
+                consumption,
+                record_date,
+                is_measured_consumption,
+                is_valid_consumption,
+                fill_strategy: fill_strategy.unwrap_or_else(|| legacy_fill_strategy(is_measured_consumption, is_valid_consumption).to_owned()),
+            }
+        })
+        .collect();
+
+    Ok(response)
+}
@@ -1,6 +1,9 @@
-use diesel::sql_types::Integer;
+use chrono::NaiveDateTime;
+use diesel::sql_types::{Integer, Nullable, Timestamp};
 use diesel::{prelude::*, sql_query};
+use rust_decimal::Decimal;
 use crate::http::structs::energy::GetTotalMonthlyTarget;
+use crate::http::structs::energy_targets::GetMonthlyTargetResponse;
 use crate::models::database_models::energy_monthly_consumption_target::EnergyMonthlyConsumptionTarget;
 use crate::schedules::scheduler::write_to_log_file_thread;
 use crate::schema::energy_monthly_consumption_target;
@@ -13,7 +16,7 @@ pub fn insert_data_energy_monthly_consumption_target(data: EnergyMonthlyConsumpt
     
     let result = diesel::insert_into(energy_monthly_consumption_target::table)
         .values(&data)
-        .on_conflict((energy_monthly_consumption_target::unit_id, energy_monthly_consumption_target::date_forecast))
+        .on_conflict((energy_monthly_consumption_target::unit_id, energy_monthly_consumption_target::date_forecast, energy_monthly_consumption_target::is_manual))
         .do_nothing()
         .execute(&mut pool);
 
@@ -40,6 +43,7 @@ pub fn monthly_target_exists_for_unit(unit_id: i32, globs: &Arc<GlobalVars>) ->
             energy_monthly_consumption_target
         WHERE 
             unit_id = $1 
+            AND is_manual = false
         ";
     
     let sql_query_aux = sql_query(sql)
@@ -48,4 +52,74 @@ pub fn monthly_target_exists_for_unit(unit_id: i32, globs: &Arc<GlobalVars>) ->
     let response = sql_query_aux.get_result::<GetTotalMonthlyTarget>(&mut pool)?;
         
     Ok(response)
-}
\ No newline at end of file
+}
+
+/// Cria a meta manual do mês. Retorna 0 se a unidade já tiver meta manual no mês
+pub fn insert_manual_monthly_target(unit_id: i32, month: NaiveDateTime, consumption_target: Decimal, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let inserted = diesel::insert_into(energy_monthly_consumption_target::table)
+        .values(&EnergyMonthlyConsumptionTarget { unit_id, consumption_target, date_forecast: month, is_manual: true })
+        .on_conflict((energy_monthly_consumption_target::unit_id, energy_monthly_consumption_target::date_forecast, energy_monthly_consumption_target::is_manual))
+        .do_nothing()
+        .execute(&mut pool)?;
+
+    Ok(inserted)
+}
+
+/// Altera a meta manual do mês. Retorna 0 se não houver meta manual no mês
+pub fn update_manual_monthly_target(unit_id: i32, month: NaiveDateTime, consumption_target: Decimal, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let updated = diesel::update(energy_monthly_consumption_target::table)
+        .filter(energy_monthly_consumption_target::unit_id.eq(unit_id))
+        .filter(energy_monthly_consumption_target::date_forecast.eq(month))
+        .filter(energy_monthly_consumption_target::is_manual.eq(true))
+        .set(energy_monthly_consumption_target::consumption_target.eq(consumption_target))
+        .execute(&mut pool)?;
+
+    Ok(updated)
+}
+
+/// Remove a meta manual do mês; a automática, se existir, volta a valer
+pub fn delete_manual_monthly_target(unit_id: i32, month: NaiveDateTime, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(energy_monthly_consumption_target::table)
+        .filter(energy_monthly_consumption_target::unit_id.eq(unit_id))
+        .filter(energy_monthly_consumption_target::date_forecast.eq(month))
+        .filter(energy_monthly_consumption_target::is_manual.eq(true))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
+
+pub fn get_unit_monthly_targets(unit_id: i32, start_month: Option<NaiveDateTime>, end_month: Option<NaiveDateTime>, globs: &Arc<GlobalVars>) -> Result<Vec<GetMonthlyTargetResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            emct.date_forecast AS month,
+            emct.consumption_target,
+            emct.is_manual,
+            (emct.is_manual OR NOT EXISTS (
+                SELECT 1 FROM energy_monthly_consumption_target manual
+                WHERE manual.unit_id = emct.unit_id AND manual.date_forecast = emct.date_forecast AND manual.is_manual = true
+            )) AS is_effective
+        FROM
+            energy_monthly_consumption_target emct
+        WHERE
+            emct.unit_id = $1
+            AND ($2 IS NULL OR emct.date_forecast >= $2)
+            AND ($3 IS NULL OR emct.date_forecast <= $3)
+        ORDER BY
+            emct.date_forecast, emct.is_manual DESC";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Nullable<Timestamp>, _>(start_month)
+        .bind::<Nullable<Timestamp>, _>(end_month)
+        .load::<GetMonthlyTargetResponse>(&mut pool)?;
+
+    Ok(response)
+}
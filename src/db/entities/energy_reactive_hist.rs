@@ -0,0 +1,96 @@
+use diesel::sql_types::{Array, Integer, Text};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::energy_reactive::GetReactiveEnergyDayResponse;
+use crate::models::database_models::energy_reactive_hist::{EnergyReactiveDayHist, EnergyReactiveHourHist};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::schema::{energy_reactive_day_hist, energy_reactive_hour_hist};
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn insert_data_reactive_hour(data: Vec<EnergyReactiveHourHist>, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let result = diesel::insert_into(energy_reactive_hour_hist::table)
+        .values(&data)
+        .on_conflict((energy_reactive_hour_hist::electric_circuit_id, energy_reactive_hour_hist::record_date))
+        .do_update()
+        .set((
+            energy_reactive_hour_hist::active_consumption.eq(excluded(energy_reactive_hour_hist::active_consumption)),
+            energy_reactive_hour_hist::reactive_consumption.eq(excluded(energy_reactive_hour_hist::reactive_consumption)),
+            energy_reactive_hour_hist::average_power_factor.eq(excluded(energy_reactive_hour_hist::average_power_factor)),
+            energy_reactive_hour_hist::min_power_factor.eq(excluded(energy_reactive_hour_hist::min_power_factor)),
+            energy_reactive_hour_hist::ufer.eq(excluded(energy_reactive_hour_hist::ufer)),
+        ))
+        .execute(&mut pool);
+
+    if let Err(err) = result {
+        let error_msg = format!("Erro ao inserir dados de energia reativa por hora: {:?}, {}", data, err);
+        write_to_log_file_thread(&error_msg, 0, "ERROR");
+    }
+
+    Ok(())
+}
+
+pub fn insert_data_reactive_day(data: EnergyReactiveDayHist, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let result = diesel::insert_into(energy_reactive_day_hist::table)
+        .values(&data)
+        .on_conflict((energy_reactive_day_hist::electric_circuit_id, energy_reactive_day_hist::record_date))
+        .do_update()
+        .set((
+            energy_reactive_day_hist::active_consumption.eq(excluded(energy_reactive_day_hist::active_consumption)),
+            energy_reactive_day_hist::reactive_consumption.eq(excluded(energy_reactive_day_hist::reactive_consumption)),
+            energy_reactive_day_hist::average_power_factor.eq(excluded(energy_reactive_day_hist::average_power_factor)),
+            energy_reactive_day_hist::min_power_factor.eq(excluded(energy_reactive_day_hist::min_power_factor)),
+            energy_reactive_day_hist::ufer.eq(excluded(energy_reactive_day_hist::ufer)),
+        ))
+        .execute(&mut pool);
+
+    if let Err(err) = result {
+        let error_msg = format!("Erro ao inserir dados de energia reativa por dia: {:?}, {}", data, err);
+        write_to_log_file_thread(&error_msg, 0, "ERROR");
+    }
+
+    Ok(())
+}
+
+pub fn get_reactive_energy_by_day(unit_id: i32, electric_circuit_ids: Vec<i32>, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetReactiveEnergyDayResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let reactive_hist = sql_query("
+    SELECT
+        electric_circuits.reference_id AS electric_circuit_reference_id,
+        energy_reactive_day_hist.record_date,
+        energy_reactive_day_hist.active_consumption,
+        energy_reactive_day_hist.reactive_consumption,
+        energy_reactive_day_hist.average_power_factor,
+        energy_reactive_day_hist.min_power_factor,
+        energy_reactive_day_hist.ufer,
+        units.tarifa_kwh
+    FROM
+        energy_reactive_day_hist
+        INNER JOIN electric_circuits on (electric_circuits.id = energy_reactive_day_hist.electric_circuit_id)
+        INNER JOIN units on (units.id = electric_circuits.unit_id)
+    WHERE
+        units.reference_id = $1 AND
+        (cardinality($2::integer[]) = 0 OR electric_circuits.reference_id = ANY($2::integer[])) AND
+        energy_reactive_day_hist.record_date >= to_date($3, 'YYYY-MM-DD') AND
+        energy_reactive_day_hist.record_date <= to_date($4, 'YYYY-MM-DD')
+    ORDER BY
+        energy_reactive_day_hist.record_date ASC,
+        electric_circuits.reference_id ASC
+    ");
+
+    let reactive_hist = reactive_hist
+        .bind::<Integer, _>(unit_id)
+        .bind::<Array<Integer>, _>(electric_circuit_ids)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date);
+
+    let response = reactive_hist.load::<GetReactiveEnergyDayResponse>(&mut pool)?;
+
+    Ok(response)
+}
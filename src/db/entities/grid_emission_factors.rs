@@ -0,0 +1,169 @@
+use chrono::{NaiveDate, Utc};
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use diesel::sql_types::{Array, Date, Integer, Nullable, Numeric, Text};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use rust_decimal::Decimal;
+use crate::http::structs::carbon_emissions::{EmissionsGroupBy, EmissionsPeriod, EmissionsSummary, ReqParamsGetEmissions};
+use crate::models::database_models::grid_emission_factors::{GridEmissionFactor, GridEmissionFactorRow};
+use crate::schema::grid_emission_factors;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub const DEFAULT_EMISSION_FACTOR_GRID: &str = "SIN";
+
+pub fn emission_factor_grid(globs: &Arc<GlobalVars>) -> String {
+    globs.configfile.EMISSION_FACTOR_GRID.clone().unwrap_or_else(|| DEFAULT_EMISSION_FACTOR_GRID.to_owned())
+}
+
+// Fator vigente no dia do consumo: o de início mais recente que ainda não terminou
+const EMISSION_FACTOR_JOIN: &str = "
+    LEFT JOIN LATERAL (
+        SELECT gef.emission_factor
+        FROM grid_emission_factors gef
+        WHERE
+            gef.grid = $4 AND
+            gef.start_date <= ehv.compilation_record_date::date AND
+            (gef.end_date IS NULL OR gef.end_date >= ehv.compilation_record_date::date)
+        ORDER BY gef.start_date DESC
+        LIMIT 1
+    ) factor ON true";
+
+pub fn get_grid_emission_factors(grid: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GridEmissionFactorRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let factors = grid_emission_factors::table
+        .filter(grid_emission_factors::grid.eq(grid))
+        .order(grid_emission_factors::start_date.asc())
+        .load::<GridEmissionFactorRow>(&mut pool)?;
+
+    Ok(factors)
+}
+
+/// Grava o fator sem alterar emissões já calculadas: None se o período do novo fator, até `today`, já estiver
+/// coberto por um fator em vigor. Só fatores que ainda não começaram podem ser substituídos.
+pub fn insert_grid_emission_factor(data: &GridEmissionFactor, today: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Option<i32>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let id = pool.transaction::<_, diesel::result::Error, _>(|conn| {
+        if data.start_date <= today {
+            let covered_until = data.end_date.map(|end| end.min(today)).unwrap_or(today);
+            let started_overlap = grid_emission_factors::table
+                .filter(grid_emission_factors::grid.eq(&data.grid))
+                .filter(grid_emission_factors::start_date.le(covered_until))
+                .filter(grid_emission_factors::end_date.is_null().or(grid_emission_factors::end_date.ge(data.start_date)))
+                .select(grid_emission_factors::id)
+                .first::<i32>(conn)
+                .optional()?;
+            if started_overlap.is_some() {
+                return Ok(None);
+            }
+        }
+
+        diesel::insert_into(grid_emission_factors::table)
+            .values(data)
+            .on_conflict((grid_emission_factors::grid, grid_emission_factors::start_date))
+            .do_update()
+            .set((
+                grid_emission_factors::end_date.eq(excluded(grid_emission_factors::end_date)),
+                grid_emission_factors::emission_factor.eq(excluded(grid_emission_factors::emission_factor)),
+                grid_emission_factors::source.eq(excluded(grid_emission_factors::source)),
+                grid_emission_factors::updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .returning(grid_emission_factors::id)
+            .get_result::<i32>(conn)
+            .map(Some)
+    })?;
+
+    Ok(id)
+}
+
+/// Remove apenas fatores que ainda não entraram em vigor
+pub fn delete_grid_emission_factor(id: i32, today: NaiveDate, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(grid_emission_factors::table
+        .filter(grid_emission_factors::id.eq(id))
+        .filter(grid_emission_factors::start_date.gt(today)))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
+
+/// Consumo diário das unidades (energy_hist_view) convertido em tCO2e pelo fator vigente em cada dia,
+/// agrupado por dia ou mês e por unidade ou cliente
+pub fn get_emissions(params: &ReqParamsGetEmissions, grid: &str, globs: &Arc<GlobalVars>) -> Result<Vec<EmissionsSummary>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let time = match params.period {
+        EmissionsPeriod::Day => "ehv.compilation_record_date::date",
+        EmissionsPeriod::Month => "date_trunc('month', ehv.compilation_record_date)::date",
+    };
+    let unit = match params.group_by {
+        EmissionsGroupBy::Unit => "u.reference_id AS unit_id, u.unit_name::text AS unit_name",
+        EmissionsGroupBy::Client => "NULL::integer AS unit_id, NULL::text AS unit_name",
+    };
+
+    let sql = format!("
+        SELECT
+            {} AS time,
+            {},
+            c.client_name::text AS client_name,
+            SUM(ehv.consumption) AS consumption,
+            COALESCE(SUM(ehv.consumption * factor.emission_factor / 1000), 0) AS emissions,
+            SUM(CASE WHEN factor.emission_factor IS NULL THEN ehv.consumption ELSE 0 END) AS consumption_without_factor
+        FROM
+            energy_hist_view ehv
+            INNER JOIN units u ON (u.id = ehv.unit_id)
+            INNER JOIN clients c ON (c.id = u.client_id)
+            {}
+        WHERE
+            (cardinality($1::integer[]) = 0 OR u.reference_id = ANY($1::integer[])) AND
+            ehv.compilation_record_date >= $2 AND
+            ehv.compilation_record_date < $3 + INTERVAL '1 day' AND
+            ($5::text IS NULL OR c.client_name = $5)
+        GROUP BY
+            1, 2, 3, 4
+        ORDER BY
+            time, client_name, unit_name", time, unit, EMISSION_FACTOR_JOIN);
+
+    let response = sql_query(sql)
+        .bind::<Array<Integer>, _>(&params.units)
+        .bind::<Date, _>(params.start_date)
+        .bind::<Date, _>(params.end_date)
+        .bind::<Text, _>(grid)
+        .bind::<Nullable<Text>, _>(params.client_name.as_deref())
+        .load::<EmissionsSummary>(&mut pool)?;
+
+    Ok(response)
+}
+
+#[derive(QueryableByName)]
+struct TotalEmissions {
+    #[diesel(sql_type = Nullable<Numeric>)]
+    total_emissions: Option<Decimal>,
+}
+
+/// Total de tCO2e das unidades no período, no formato de datas e filtro de unidades de procel_insigths
+pub fn get_units_total_emissions(units: &[i32], start_date: &str, end_date: &str, grid: &str, conn: &mut PooledConnection<ConnectionManager<PgConnection>>) -> Result<Decimal, diesel::result::Error> {
+    let sql = format!("
+        SELECT
+            SUM(ehv.consumption * factor.emission_factor / 1000) AS total_emissions
+        FROM
+            energy_hist_view ehv
+            INNER JOIN units u ON (u.id = ehv.unit_id)
+            {}
+        WHERE
+            (cardinality($1::integer[]) = 0 OR u.reference_id = ANY($1::integer[])) AND
+            ehv.compilation_record_date BETWEEN to_timestamp($2, 'YYYY-MM-DD') AND to_timestamp($3, 'YYYY-MM-DD')", EMISSION_FACTOR_JOIN);
+
+    let response = sql_query(sql)
+        .bind::<Array<Integer>, _>(units)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .bind::<Text, _>(grid)
+        .get_result::<TotalEmissions>(conn)?;
+
+    Ok(response.total_emissions.unwrap_or_default().round_dp(3))
+}
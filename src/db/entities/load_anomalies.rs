@@ -0,0 +1,138 @@
+use chrono::NaiveDate;
+use diesel::sql_types::{Date, Integer};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::load_anomalies::UnitHourLoad;
+use crate::models::database_models::load_anomalies::{LoadAnomaly, LoadAnomalyRow, UnitDailyLoadProfile, UnitOperatingSchedule, UnitOperatingScheduleRow};
+use crate::schema::{load_anomalies, unit_daily_load_profiles, unit_operating_schedules};
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn get_unit_operating_schedule(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<UnitOperatingScheduleRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let schedule = unit_operating_schedules::table
+        .filter(unit_operating_schedules::unit_id.eq(unit_id))
+        .order(unit_operating_schedules::weekday.asc())
+        .load::<UnitOperatingScheduleRow>(&mut pool)?;
+
+    Ok(schedule)
+}
+
+/// Substitui o horário de funcionamento da unidade pelos dias informados
+pub fn replace_unit_operating_schedule(unit_id: i32, days: &[UnitOperatingSchedule], globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let inserted = pool.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(unit_operating_schedules::table.filter(unit_operating_schedules::unit_id.eq(unit_id)))
+            .execute(conn)?;
+
+        diesel::insert_into(unit_operating_schedules::table)
+            .values(days)
+            .execute(conn)
+    })?;
+
+    Ok(inserted)
+}
+
+/// Consumo de cada hora do dia somado entre os circuitos raiz (não virtuais) da unidade, se todas as leituras da hora
+/// são válidas e se todos os circuitos raiz gravaram a hora
+pub fn get_unit_hourly_load(unit_id: i32, day: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<UnitHourLoad>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        WITH root_circuits AS (
+            SELECT id
+            FROM electric_circuits
+            WHERE
+                unit_id = $1 AND
+                parent_id IS NULL AND
+                virtual_formula IS NULL
+        )
+        SELECT
+            energy_hist.record_date,
+            SUM(energy_hist.consumption) AS consumption,
+            BOOL_AND(COALESCE(energy_hist.is_valid_consumption, true)) AS all_valid,
+            COUNT(DISTINCT energy_hist.electric_circuit_id) = (SELECT COUNT(*) FROM root_circuits) AS all_circuits
+        FROM
+            energy_hist
+        WHERE
+            energy_hist.electric_circuit_id IN (SELECT id FROM root_circuits) AND
+            energy_hist.record_date >= $2 AND
+            energy_hist.record_date < $2 + INTERVAL '1 day'
+        GROUP BY
+            energy_hist.record_date
+        ORDER BY
+            energy_hist.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Date, _>(day)
+        .load::<UnitHourLoad>(&mut pool)?;
+
+    Ok(response)
+}
+
+pub fn upsert_unit_daily_load_profile(data: &UnitDailyLoadProfile, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let upserted = diesel::insert_into(unit_daily_load_profiles::table)
+        .values(data)
+        .on_conflict((unit_daily_load_profiles::unit_id, unit_daily_load_profiles::day))
+        .do_update()
+        .set((
+            unit_daily_load_profiles::base_load.eq(excluded(unit_daily_load_profiles::base_load)),
+            unit_daily_load_profiles::after_hours_consumption.eq(excluded(unit_daily_load_profiles::after_hours_consumption)),
+            unit_daily_load_profiles::after_hours_average_load.eq(excluded(unit_daily_load_profiles::after_hours_average_load)),
+            unit_daily_load_profiles::after_hours_count.eq(excluded(unit_daily_load_profiles::after_hours_count)),
+            unit_daily_load_profiles::operating_hours_consumption.eq(excluded(unit_daily_load_profiles::operating_hours_consumption)),
+        ))
+        .execute(&mut pool)?;
+
+    Ok(upserted)
+}
+
+pub fn get_unit_daily_load_profiles(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<UnitDailyLoadProfile>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let profiles = unit_daily_load_profiles::table
+        .filter(unit_daily_load_profiles::unit_id.eq(unit_id))
+        .filter(unit_daily_load_profiles::day.between(start_date, end_date))
+        .order(unit_daily_load_profiles::day.asc())
+        .load::<UnitDailyLoadProfile>(&mut pool)?;
+
+    Ok(profiles)
+}
+
+/// Substitui as anomalias do dia, para que o reprocessamento não deixe anomalias antigas
+pub fn replace_load_anomalies(unit_id: i32, day: NaiveDate, anomalies: &[LoadAnomaly], globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    pool.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(load_anomalies::table
+            .filter(load_anomalies::unit_id.eq(unit_id))
+            .filter(load_anomalies::anomaly_date.eq(day)))
+            .execute(conn)?;
+
+        diesel::insert_into(load_anomalies::table)
+            .values(anomalies)
+            .execute(conn)?;
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+pub fn get_load_anomalies(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<LoadAnomalyRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let anomalies = load_anomalies::table
+        .filter(load_anomalies::unit_id.eq(unit_id))
+        .filter(load_anomalies::anomaly_date.between(start_date, end_date))
+        .order((load_anomalies::anomaly_date.asc(), load_anomalies::anomaly_type.asc()))
+        .load::<LoadAnomalyRow>(&mut pool)?;
+
+    Ok(anomalies)
+}
@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use diesel::sql_types::{Array, Integer, Text};
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::electric_circuits::GetMeterDiscontinuityEventResponse;
+use crate::models::database_models::meter_discontinuity_events::MeterDiscontinuityEvent;
+use crate::schema::meter_discontinuity_events;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+/// Substitui os eventos de descontinuidade do medidor no período, para que o reprocessamento não deixe eventos antigos
+pub fn replace_meter_discontinuity_events(electric_circuit_id: i32, start_time: NaiveDateTime, end_time: NaiveDateTime, events: &[MeterDiscontinuityEvent], globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    pool.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(meter_discontinuity_events::table
+            .filter(meter_discontinuity_events::electric_circuit_id.eq(electric_circuit_id))
+            .filter(meter_discontinuity_events::record_date.ge(start_time))
+            .filter(meter_discontinuity_events::record_date.lt(end_time)))
+            .execute(conn)?;
+
+        diesel::insert_into(meter_discontinuity_events::table)
+            .values(events)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+pub fn get_meter_discontinuity_events(unit_id: i32, electric_circuit_ids: Vec<i32>, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetMeterDiscontinuityEventResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            electric_circuits.reference_id AS electric_circuit_reference_id,
+            meter_discontinuity_events.record_date,
+            meter_discontinuity_events.event_type,
+            meter_discontinuity_events.previous_value,
+            meter_discontinuity_events.new_value,
+            meter_discontinuity_events.applied_offset,
+            meter_discontinuity_events.estimated_consumption
+        FROM
+            meter_discontinuity_events
+            INNER JOIN electric_circuits ON (electric_circuits.id = meter_discontinuity_events.electric_circuit_id)
+            INNER JOIN units ON (units.id = electric_circuits.unit_id)
+        WHERE
+            units.reference_id = $1 AND
+            (cardinality($2::integer[]) = 0 OR electric_circuits.reference_id = ANY($2::integer[])) AND
+            meter_discontinuity_events.record_date >= to_timestamp($3, 'YYYY-MM-DD') AND
+            meter_discontinuity_events.record_date < to_timestamp($4, 'YYYY-MM-DD') + INTERVAL '1 day'
+        ORDER BY
+            meter_discontinuity_events.record_date,
+            electric_circuits.reference_id";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Array<Integer>, _>(electric_circuit_ids)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<GetMeterDiscontinuityEventResponse>(&mut pool)?;
+
+    Ok(response)
+}
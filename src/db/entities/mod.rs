@@ -15,3 +15,16 @@ pub mod energy_monthly_consumption_target;
 pub mod assets;
 pub mod devices_l1_totalization_hist;
 pub mod last_device_telemetry_time;
+pub mod dynamo_consumed_capacity_hist;
+pub mod dynamo_table_rules;
+pub mod api_server_snapshots;
+pub mod energy_reactive_hist;
+pub mod unit_tariffs;
+pub mod contracted_demands;
+pub mod power_quality;
+pub mod meter_discontinuity_events;
+pub mod client_energy_forecast_models;
+pub mod energy_baselines;
+pub mod grid_emission_factors;
+pub mod calendar_holidays;
+pub mod load_anomalies;
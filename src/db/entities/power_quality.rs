@@ -0,0 +1,141 @@
+use chrono::NaiveDateTime;
+use diesel::sql_types::{Array, Integer, Text};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::power_quality::{GetPowerQualityEventResponse, GetPowerQualityStatsResponse};
+use crate::models::database_models::power_quality::{PowerQualityEvent, PowerQualityMinutesHist};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::schema::{power_quality_events, power_quality_minutes_hist};
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn insert_data_power_quality(data: Vec<PowerQualityMinutesHist>, globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let result = diesel::insert_into(power_quality_minutes_hist::table)
+        .values(&data)
+        .on_conflict((power_quality_minutes_hist::electric_circuit_id, power_quality_minutes_hist::record_date))
+        .do_update()
+        .set((
+            power_quality_minutes_hist::min_voltage.eq(excluded(power_quality_minutes_hist::min_voltage)),
+            power_quality_minutes_hist::max_voltage.eq(excluded(power_quality_minutes_hist::max_voltage)),
+            power_quality_minutes_hist::average_voltage.eq(excluded(power_quality_minutes_hist::average_voltage)),
+            power_quality_minutes_hist::average_voltage_unbalance.eq(excluded(power_quality_minutes_hist::average_voltage_unbalance)),
+            power_quality_minutes_hist::max_voltage_unbalance.eq(excluded(power_quality_minutes_hist::max_voltage_unbalance)),
+            power_quality_minutes_hist::average_current_unbalance.eq(excluded(power_quality_minutes_hist::average_current_unbalance)),
+            power_quality_minutes_hist::max_current_unbalance.eq(excluded(power_quality_minutes_hist::max_current_unbalance)),
+            power_quality_minutes_hist::min_frequency.eq(excluded(power_quality_minutes_hist::min_frequency)),
+            power_quality_minutes_hist::max_frequency.eq(excluded(power_quality_minutes_hist::max_frequency)),
+            power_quality_minutes_hist::max_frequency_deviation.eq(excluded(power_quality_minutes_hist::max_frequency_deviation)),
+            power_quality_minutes_hist::readings_count.eq(excluded(power_quality_minutes_hist::readings_count)),
+            power_quality_minutes_hist::readings_out_of_limits.eq(excluded(power_quality_minutes_hist::readings_out_of_limits)),
+        ))
+        .execute(&mut pool);
+
+    if let Err(err) = result {
+        let error_msg = format!("Erro ao inserir dados de qualidade de energia: {}", err);
+        write_to_log_file_thread(&error_msg, 0, "ERROR");
+    }
+
+    Ok(())
+}
+
+/// Substitui os eventos de afundamento/elevação do circuito no período, para que o reprocessamento não duplique eventos
+pub fn replace_power_quality_events(electric_circuit_id: i32, start_time: NaiveDateTime, end_time: NaiveDateTime, events: &[PowerQualityEvent], globs: &Arc<GlobalVars>) -> Result<(), Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    pool.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(power_quality_events::table
+            .filter(power_quality_events::electric_circuit_id.eq(electric_circuit_id))
+            .filter(power_quality_events::record_date.ge(start_time))
+            .filter(power_quality_events::record_date.lt(end_time)))
+            .execute(conn)?;
+
+        diesel::insert_into(power_quality_events::table)
+            .values(events)
+            .execute(conn)?;
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+pub fn get_power_quality_stats(unit_id: i32, electric_circuit_ids: Vec<i32>, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetPowerQualityStatsResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            electric_circuits.reference_id AS electric_circuit_reference_id,
+            power_quality_minutes_hist.record_date,
+            power_quality_minutes_hist.min_voltage,
+            power_quality_minutes_hist.max_voltage,
+            power_quality_minutes_hist.average_voltage,
+            power_quality_minutes_hist.average_voltage_unbalance,
+            power_quality_minutes_hist.max_voltage_unbalance,
+            power_quality_minutes_hist.average_current_unbalance,
+            power_quality_minutes_hist.max_current_unbalance,
+            power_quality_minutes_hist.min_frequency,
+            power_quality_minutes_hist.max_frequency,
+            power_quality_minutes_hist.max_frequency_deviation,
+            power_quality_minutes_hist.readings_count,
+            power_quality_minutes_hist.readings_out_of_limits
+        FROM
+            power_quality_minutes_hist
+            INNER JOIN electric_circuits ON (electric_circuits.id = power_quality_minutes_hist.electric_circuit_id)
+            INNER JOIN units ON (units.id = electric_circuits.unit_id)
+        WHERE
+            units.reference_id = $1 AND
+            (cardinality($2::integer[]) = 0 OR electric_circuits.reference_id = ANY($2::integer[])) AND
+            power_quality_minutes_hist.record_date >= to_timestamp($3, 'YYYY-MM-DD HH24:MI:SS') AND
+            power_quality_minutes_hist.record_date <= to_timestamp($4, 'YYYY-MM-DD HH24:MI:SS')
+        ORDER BY
+            power_quality_minutes_hist.record_date,
+            electric_circuits.reference_id";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Array<Integer>, _>(electric_circuit_ids)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<GetPowerQualityStatsResponse>(&mut pool)?;
+
+    Ok(response)
+}
+
+pub fn get_power_quality_events(unit_id: i32, electric_circuit_ids: Vec<i32>, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<GetPowerQualityEventResponse>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            electric_circuits.reference_id AS electric_circuit_reference_id,
+            power_quality_events.record_date,
+            power_quality_events.end_date,
+            power_quality_events.event_type,
+            power_quality_events.phase,
+            power_quality_events.duration_seconds,
+            power_quality_events.extreme_voltage,
+            power_quality_events.extreme_voltage_pu
+        FROM
+            power_quality_events
+            INNER JOIN electric_circuits ON (electric_circuits.id = power_quality_events.electric_circuit_id)
+            INNER JOIN units ON (units.id = electric_circuits.unit_id)
+        WHERE
+            units.reference_id = $1 AND
+            (cardinality($2::integer[]) = 0 OR electric_circuits.reference_id = ANY($2::integer[])) AND
+            power_quality_events.record_date >= to_timestamp($3, 'YYYY-MM-DD HH24:MI:SS') AND
+            power_quality_events.record_date <= to_timestamp($4, 'YYYY-MM-DD HH24:MI:SS')
+        ORDER BY
+            power_quality_events.record_date,
+            electric_circuits.reference_id";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Array<Integer>, _>(electric_circuit_ids)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<GetPowerQualityEventResponse>(&mut pool)?;
+
+    Ok(response)
+}
@@ -0,0 +1,116 @@
+use chrono::Utc;
+use diesel::sql_types::{Integer, Text};
+use diesel::upsert::excluded;
+use diesel::{prelude::*, sql_query};
+use crate::http::structs::energy_tariffs::{UnitDemand, UnitHourConsumption};
+use crate::models::database_models::unit_tariffs::{UnitTariff, UnitTariffRow};
+use crate::schema::unit_tariffs;
+use crate::GlobalVars;
+use std::sync::Arc;
+use std::error::Error;
+
+pub fn get_unit_tariffs(unit_id: i32, globs: &Arc<GlobalVars>) -> Result<Vec<UnitTariffRow>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let tariffs = unit_tariffs::table
+        .filter(unit_tariffs::unit_id.eq(unit_id))
+        .order(unit_tariffs::start_date.asc())
+        .load::<UnitTariffRow>(&mut pool)?;
+
+    Ok(tariffs)
+}
+
+pub fn upsert_unit_tariff(data: &UnitTariff, globs: &Arc<GlobalVars>) -> Result<i32, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let id = diesel::insert_into(unit_tariffs::table)
+        .values(data)
+        .on_conflict((unit_tariffs::unit_id, unit_tariffs::start_date))
+        .do_update()
+        .set((
+            unit_tariffs::modality.eq(excluded(unit_tariffs::modality)),
+            unit_tariffs::end_date.eq(excluded(unit_tariffs::end_date)),
+            unit_tariffs::peak_start_time.eq(excluded(unit_tariffs::peak_start_time)),
+            unit_tariffs::peak_end_time.eq(excluded(unit_tariffs::peak_end_time)),
+            unit_tariffs::peak_weekdays_only.eq(excluded(unit_tariffs::peak_weekdays_only)),
+            unit_tariffs::peak_energy_price.eq(excluded(unit_tariffs::peak_energy_price)),
+            unit_tariffs::off_peak_energy_price.eq(excluded(unit_tariffs::off_peak_energy_price)),
+            unit_tariffs::peak_demand_price.eq(excluded(unit_tariffs::peak_demand_price)),
+            unit_tariffs::off_peak_demand_price.eq(excluded(unit_tariffs::off_peak_demand_price)),
+            unit_tariffs::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .returning(unit_tariffs::id)
+        .get_result::<i32>(&mut pool)?;
+
+    Ok(id)
+}
+
+pub fn delete_unit_tariff(id: i32, globs: &Arc<GlobalVars>) -> Result<usize, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let deleted = diesel::delete(unit_tariffs::table.filter(unit_tariffs::id.eq(id)))
+        .execute(&mut pool)?;
+
+    Ok(deleted)
+}
+
+pub fn get_unit_hourly_consumption(unit_id: i32, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<UnitHourConsumption>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            energy_hist.record_date,
+            SUM(energy_hist.consumption) AS consumption
+        FROM
+            energy_hist
+            INNER JOIN electric_circuits ON (electric_circuits.id = energy_hist.electric_circuit_id)
+        WHERE
+            electric_circuits.unit_id = $1 AND
+            electric_circuits.parent_id IS NULL AND
+            electric_circuits.virtual_formula IS NULL AND
+            energy_hist.record_date >= to_timestamp($2, 'YYYY-MM-DD') AND
+            energy_hist.record_date < to_timestamp($3, 'YYYY-MM-DD') + INTERVAL '1 day'
+        GROUP BY
+            energy_hist.record_date
+        ORDER BY
+            energy_hist.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<UnitHourConsumption>(&mut pool)?;
+
+    Ok(response)
+}
+
+/// Demanda média de cada intervalo de 15 minutos, somada entre os circuitos raiz (não virtuais) da unidade
+pub fn get_unit_demand_by_15_minutes(unit_id: i32, start_date: &str, end_date: &str, globs: &Arc<GlobalVars>) -> Result<Vec<UnitDemand>, Box<dyn Error>> {
+    let mut pool = globs.pool.get()?;
+
+    let sql = "
+        SELECT
+            energy_demand_minutes_hist.record_date,
+            SUM(energy_demand_minutes_hist.average_demand) AS demand
+        FROM
+            energy_demand_minutes_hist
+            INNER JOIN electric_circuits ON (electric_circuits.id = energy_demand_minutes_hist.electric_circuit_id)
+        WHERE
+            electric_circuits.unit_id = $1 AND
+            electric_circuits.parent_id IS NULL AND
+            electric_circuits.virtual_formula IS NULL AND
+            energy_demand_minutes_hist.record_date >= to_timestamp($2, 'YYYY-MM-DD') AND
+            energy_demand_minutes_hist.record_date < to_timestamp($3, 'YYYY-MM-DD') + INTERVAL '1 day'
+        GROUP BY
+            energy_demand_minutes_hist.record_date
+        ORDER BY
+            energy_demand_minutes_hist.record_date";
+
+    let response = sql_query(sql)
+        .bind::<Integer, _>(unit_id)
+        .bind::<Text, _>(start_date)
+        .bind::<Text, _>(end_date)
+        .load::<UnitDemand>(&mut pool)?;
+
+    Ok(response)
+}
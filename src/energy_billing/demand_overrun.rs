@@ -0,0 +1,184 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::energy_billing::tariffs::{is_peak_time, TariffModality};
+use crate::http::structs::energy_tariffs::UnitDemand;
+use crate::models::database_models::contracted_demands::{ContractedDemandRow, DemandOverrunEvent};
+use crate::models::database_models::unit_tariffs::UnitTariffRow;
+
+// A parcela ultrapassada é cobrada com o dobro da tarifa de demanda (REN ANEEL 1000/2021)
+const OVERRUN_PENALTY_MULTIPLIER: Decimal = Decimal::TWO;
+
+pub const TARIFF_PERIOD_PEAK: &str = "PONTA";
+pub const TARIFF_PERIOD_OFF_PEAK: &str = "FORA_PONTA";
+pub const TARIFF_PERIOD_SINGLE: &str = "UNICO";
+
+/// Demanda contratada vigente na data para o circuito (ou para a unidade, quando `electric_circuit_id` é None)
+pub fn contracted_demand_for_date(contracted_demands: &[ContractedDemandRow], electric_circuit_id: Option<i32>, date: NaiveDate) -> Option<&ContractedDemandRow> {
+    contracted_demands.iter()
+        .filter(|contracted| contracted.electric_circuit_id == electric_circuit_id)
+        .filter(|contracted| contracted.start_date <= date && contracted.end_date.map(|end| end >= date).unwrap_or(true))
+        .max_by_key(|contracted| contracted.start_date)
+}
+
+/// Compara a maior demanda de 15 minutos do dia com a demanda contratada de cada posto tarifário.
+/// Há ultrapassagem quando a medida supera a contratada acrescida da tolerância; a multa estimada
+/// considera a demanda excedente sobre a contratada.
+pub fn detect_demand_overruns(contracted: &ContractedDemandRow, tariff: Option<&UnitTariffRow>, demand: &[UnitDemand], day: NaiveDate) -> Vec<DemandOverrunEvent> {
+    let day_demand: Vec<&UnitDemand> = demand.iter().filter(|slot| slot.record_date.date() == day).collect();
+    let is_blue = tariff.and_then(|tariff| TariffModality::parse(&tariff.modality)) == Some(TariffModality::Blue);
+
+    let mut events = Vec::new();
+    match (is_blue, contracted.peak_demand, tariff) {
+        (true, Some(peak_demand), Some(tariff)) => {
+            let (peak, off_peak): (Vec<&UnitDemand>, Vec<&UnitDemand>) = day_demand.into_iter().partition(|slot| is_peak_time(tariff, slot.record_date));
+            events.extend(check_overrun(contracted, &peak, peak_demand, tariff.peak_demand_price, TARIFF_PERIOD_PEAK, day));
+            events.extend(check_overrun(contracted, &off_peak, contracted.off_peak_demand, tariff.off_peak_demand_price, TARIFF_PERIOD_OFF_PEAK, day));
+        },
+        _ => {
+            let price = tariff.and_then(|tariff| tariff.off_peak_demand_price);
+            events.extend(check_overrun(contracted, &day_demand, contracted.off_peak_demand, price, TARIFF_PERIOD_SINGLE, day));
+        }
+    }
+
+    events
+}
+
+fn check_overrun(contracted: &ContractedDemandRow, slots: &[&UnitDemand], contracted_demand: Decimal, demand_price: Option<Decimal>, tariff_period: &str, day: NaiveDate) -> Option<DemandOverrunEvent> {
+    let max_slot = slots.iter().max_by_key(|slot| slot.demand)?;
+    let tolerance_limit = contracted_demand * (Decimal::ONE + contracted.tolerance_percentage / Decimal::ONE_HUNDRED);
+
+    if max_slot.demand <= tolerance_limit {
+        return None;
+    }
+
+    let overrun_demand = max_slot.demand - contracted_demand;
+
+    Some(DemandOverrunEvent {
+        contracted_demand_id: contracted.id,
+        event_date: day,
+        tariff_period: tariff_period.to_owned(),
+        record_date: max_slot.record_date,
+        contracted_demand,
+        tolerance_limit: tolerance_limit.round_dp(3),
+        measured_demand: max_slot.demand.round_dp(3),
+        overrun_demand: overrun_demand.round_dp(3),
+        estimated_penalty: demand_price.map(|price| (overrun_demand * price * OVERRUN_PENALTY_MULTIPLIER).round_dp(2)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDateTime, NaiveTime};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, 2).unwrap()
+    }
+
+    fn at(day: NaiveDate, hour: u32, minute: u32) -> NaiveDateTime {
+        day.and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn contracted(peak_demand: Option<Decimal>, off_peak_demand: Decimal) -> ContractedDemandRow {
+        ContractedDemandRow {
+            id: 1,
+            unit_id: 1,
+            electric_circuit_id: None,
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            end_date: None,
+            peak_demand,
+            off_peak_demand,
+            tolerance_percentage: dec!(5),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn blue_tariff() -> UnitTariffRow {
+        UnitTariffRow {
+            id: 1,
+            unit_id: 1,
+            modality: "AZUL".to_owned(),
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            end_date: None,
+            peak_start_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            peak_end_time: NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+            peak_weekdays_only: true,
+            peak_energy_price: dec!(2.5),
+            off_peak_energy_price: dec!(0.5),
+            peak_demand_price: Some(dec!(30)),
+            off_peak_demand_price: Some(dec!(10)),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn demand(record_date: NaiveDateTime, demand: Decimal) -> UnitDemand {
+        UnitDemand { record_date, demand }
+    }
+
+    #[test]
+    fn demand_within_tolerance_is_not_an_overrun() {
+        let slots = [demand(at(day(), 10, 0), dec!(90)), demand(at(day(), 10, 15), dec!(105))];
+        assert!(detect_demand_overruns(&contracted(None, dec!(100)), None, &slots, day()).is_empty());
+    }
+
+    #[test]
+    fn overrun_is_measured_against_contracted_demand() {
+        let slots = [demand(at(day(), 10, 0), dec!(90)), demand(at(day(), 14, 30), dec!(110))];
+        let events = detect_demand_overruns(&contracted(None, dec!(100)), None, &slots, day());
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.tariff_period, TARIFF_PERIOD_SINGLE);
+        assert_eq!(event.record_date, at(day(), 14, 30));
+        assert_eq!(event.tolerance_limit, dec!(105));
+        assert_eq!(event.measured_demand, dec!(110));
+        assert_eq!(event.overrun_demand, dec!(10));
+        assert_eq!(event.estimated_penalty, None);
+    }
+
+    #[test]
+    fn blue_tariff_checks_each_period_with_its_own_contracted_demand_and_price() {
+        let slots = [
+            demand(at(day(), 10, 0), dec!(90)),
+            demand(at(day(), 19, 0), dec!(60)),
+            demand(at(day(), 22, 0), dec!(95)),
+        ];
+        let events = detect_demand_overruns(&contracted(Some(dec!(50)), dec!(100)), Some(&blue_tariff()), &slots, day());
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.tariff_period, TARIFF_PERIOD_PEAK);
+        assert_eq!(event.contracted_demand, dec!(50));
+        assert_eq!(event.overrun_demand, dec!(10));
+        // Excedente cobrado com o dobro da tarifa de demanda de ponta
+        assert_eq!(event.estimated_penalty, Some(dec!(600)));
+    }
+
+    #[test]
+    fn slots_from_other_days_are_ignored() {
+        let next_day = day().succ_opt().unwrap();
+        let slots = [demand(at(day(), 10, 0), dec!(90)), demand(at(next_day, 0, 0), dec!(200))];
+        assert!(detect_demand_overruns(&contracted(None, dec!(100)), None, &slots, day()).is_empty());
+    }
+
+    #[test]
+    fn contracted_demand_for_date_uses_the_latest_one_in_force() {
+        let mut old = contracted(None, dec!(100));
+        old.end_date = NaiveDate::from_ymd_opt(2024, 6, 30);
+        let mut current = contracted(None, dec!(120));
+        current.id = 2;
+        current.start_date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let mut circuit = contracted(None, dec!(30));
+        circuit.id = 3;
+        circuit.electric_circuit_id = Some(7);
+        let contracted_demands = [old, current, circuit];
+
+        assert_eq!(contracted_demand_for_date(&contracted_demands, None, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()).map(|row| row.id), Some(1));
+        assert_eq!(contracted_demand_for_date(&contracted_demands, None, day()).map(|row| row.id), Some(2));
+        assert_eq!(contracted_demand_for_date(&contracted_demands, Some(7), day()).map(|row| row.id), Some(3));
+        assert_eq!(contracted_demand_for_date(&contracted_demands, None, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()).map(|row| row.id), None);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod tariffs;
+pub mod demand_overrun;
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+use rust_decimal::Decimal;
+
+use crate::http::structs::energy_tariffs::{EnergyCostSummary, UnitDemand, UnitHourConsumption};
+use crate::models::database_models::unit_tariffs::UnitTariffRow;
+
+/// Modalidades tarifárias do grupo A. Na convencional a energia tem preço único (fora de ponta);
+/// na verde a demanda tem preço único; na azul a demanda é cobrada separadamente na ponta e fora de ponta.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TariffModality {
+    Conventional,
+    Green,
+    Blue,
+}
+
+impl TariffModality {
+    pub fn parse(modality: &str) -> Option<Self> {
+        match modality.to_uppercase().as_str() {
+            "CONVENCIONAL" => Some(TariffModality::Conventional),
+            "VERDE" => Some(TariffModality::Green),
+            "AZUL" => Some(TariffModality::Blue),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TariffModality::Conventional => "CONVENCIONAL",
+            TariffModality::Green => "VERDE",
+            TariffModality::Blue => "AZUL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CostPeriod {
+    Day,
+    Month,
+}
+
+impl CostPeriod {
+    fn key(&self, record_date: NaiveDateTime) -> NaiveDate {
+        match self {
+            CostPeriod::Day => record_date.date(),
+            CostPeriod::Month => record_date.date().with_day(1).unwrap(),
+        }
+    }
+}
+
+/// Tarifa vigente na data: a de início mais recente que ainda não terminou
+pub fn tariff_for_date(tariffs: &[UnitTariffRow], date: NaiveDate) -> Option<&UnitTariffRow> {
+    tariffs.iter()
+        .filter(|tariff| tariff.start_date <= date && tariff.end_date.map(|end| end >= date).unwrap_or(true))
+        .max_by_key(|tariff| tariff.start_date)
+}
+
+/// Indica se o horário está no posto de ponta da tarifa. Na modalidade convencional não há posto de ponta.
+pub fn is_peak_time(tariff: &UnitTariffRow, record_date: NaiveDateTime) -> bool {
+    if TariffModality::parse(&tariff.modality) == Some(TariffModality::Conventional) {
+        return false;
+    }
+
+    if tariff.peak_weekdays_only && matches!(record_date.weekday(), Weekday::Sat | Weekday::Sun) {
+        return false;
+    }
+
+    let time = record_date.time();
+    if tariff.peak_start_time <= tariff.peak_end_time {
+        time >= tariff.peak_start_time && time < tariff.peak_end_time
+    } else {
+        // Posto de ponta que atravessa a meia-noite
+        time >= tariff.peak_start_time || time < tariff.peak_end_time
+    }
+}
+
+#[derive(Default)]
+struct PeriodAccumulator {
+    peak_consumption: Decimal,
+    off_peak_consumption: Decimal,
+    peak_cost: Decimal,
+    off_peak_cost: Decimal,
+    hours_without_tariff: i32,
+    peak_demand: Option<(Decimal, Decimal)>,
+    off_peak_demand: Option<(Decimal, Decimal)>,
+}
+
+/// Calcula o custo da energia por dia ou por mês a partir do consumo horário da unidade.
+/// A cobrança de demanda só é calculada por mês, sobre a maior demanda de 15 minutos de cada posto.
+pub fn calculate_energy_cost(tariffs: &[UnitTariffRow], consumption: &[UnitHourConsumption], demand: &[UnitDemand], period: CostPeriod) -> Vec<EnergyCostSummary> {
+    let mut periods: BTreeMap<NaiveDate, PeriodAccumulator> = BTreeMap::new();
+
+    for hour in consumption {
+        let acc = periods.entry(period.key(hour.record_date)).or_default();
+        let tariff = match tariff_for_date(tariffs, hour.record_date.date()) {
+            Some(tariff) => tariff,
+            None => {
+                acc.hours_without_tariff += 1;
+                continue;
+            }
+        };
+
+        if is_peak_time(tariff, hour.record_date) {
+            acc.peak_consumption += hour.consumption;
+            acc.peak_cost += hour.consumption * tariff.peak_energy_price;
+        } else {
+            acc.off_peak_consumption += hour.consumption;
+            acc.off_peak_cost += hour.consumption * tariff.off_peak_energy_price;
+        }
+    }
+
+    if period == CostPeriod::Month {
+        for slot in demand {
+            let tariff = match tariff_for_date(tariffs, slot.record_date.date()) {
+                Some(tariff) => tariff,
+                None => continue,
+            };
+            let acc = periods.entry(period.key(slot.record_date)).or_default();
+
+            // Na modalidade azul a demanda de ponta tem preço próprio; nas demais vale a maior demanda do mês
+            let (max_demand, price) = match TariffModality::parse(&tariff.modality) {
+                Some(TariffModality::Blue) if is_peak_time(tariff, slot.record_date) => (&mut acc.peak_demand, tariff.peak_demand_price),
+                _ => (&mut acc.off_peak_demand, tariff.off_peak_demand_price),
+            };
+            let price = price.unwrap_or_default();
+            if max_demand.map(|(value, _)| slot.demand > value).unwrap_or(true) {
+                *max_demand = Some((slot.demand, price));
+            }
+        }
+    }
+
+    periods.into_iter().map(|(date, acc)| {
+        let demand_cost = match period {
+            CostPeriod::Day => None,
+            CostPeriod::Month => Some(
+                acc.peak_demand.map(|(value, price)| value * price).unwrap_or_default()
+                + acc.off_peak_demand.map(|(value, price)| value * price).unwrap_or_default()
+            ),
+        };
+        let energy_cost = acc.peak_cost + acc.off_peak_cost;
+
+        EnergyCostSummary {
+            time: date,
+            peak_consumption: acc.peak_consumption.round_dp(2),
+            off_peak_consumption: acc.off_peak_consumption.round_dp(2),
+            peak_cost: acc.peak_cost.round_dp(2),
+            off_peak_cost: acc.off_peak_cost.round_dp(2),
+            peak_demand: acc.peak_demand.map(|(value, _)| value.round_dp(2)),
+            off_peak_demand: acc.off_peak_demand.map(|(value, _)| value.round_dp(2)),
+            demand_cost: demand_cost.map(|cost| cost.round_dp(2)),
+            total_cost: (energy_cost + demand_cost.unwrap_or_default()).round_dp(2),
+            hours_without_tariff: acc.hours_without_tariff,
+        }
+    }).collect()
+}
@@ -1,51 +1,69 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
-use tokio::time;
+use chrono::Utc;
+use reqwest::StatusCode;
 
+use crate::external_api::http_client::{HttpClientError, HttpMethod, UpstreamRequest};
 use crate::{models::external_models::device::{ LaagerLoginRequestBody, LaagerLoginResponseData, VerifyLaagerData, WaterConsumption, WaterConsumptionHistory}, GlobalVars};
 use crate::{app_history::laager_hist::{LaagerConsumption, LaagerConsumptionHistoryPerHour}};
 
 pub struct LaagerApi;
 
+// Renova o token alguns minutos antes de expirar
+const LAAGER_TOKEN_REFRESH_MARGIN_S: i64 = 5 * 60;
+
+#[derive(Debug, Clone)]
+pub struct LaagerToken {
+    pub access_token: String,
+    /// Esquema do header Authorization devolvido no login (ex.: Bearer)
+    pub token_type: String,
+    /// Timestamp (epoch, segundos) de expiração do token
+    pub expires_at: i64,
+}
+
 impl LaagerApi {
     async fn send_request(route: &str, globs: &Arc<GlobalVars>) -> Result<String, String> {
-        let mut attempts = 0;
-        let login = Self::laager_login(&globs).await?;
-
-        loop {
-            let response = reqwest::Client::new()
-                .get(&format!("{}/{}", globs.configfile.APILAAGER_URL, route))
-                .header("Authorization", format!("Bearer {}", login.access_token))
-                .header("Accept", "application/json")
-                .send()
-                .await;
-    
-            match response {
-                Ok(response_body) => {
-                    if response_body.status().is_success() {
-                        let text = response_body.text().await.map_err(|err| format!("Erro ao ler resposta: {}", err))?;
-                        return Ok(text);
-                    } else {
-                        attempts += 1;
-                        if attempts >= 12 {
-                            return Err(format!("Erro na requisição: {}, Status: {}", route, response_body.status()));
-                        } else {
-                            eprintln!("Erro na requisição: {}, Tentativa {}/12, esperando 30 segundos para tentar novamente...", response_body.status(), attempts);
-                            time::sleep(Duration::from_secs(30)).await;
-                        }
-                    }
-                }
-                Err(err) => {
-                    attempts += 1;
-                    if attempts >= 12 {
-                        return Err(format!("Erro ao enviar requisição após {} tentativas: {}", attempts, err));
-                    } else {
-                        eprintln!("Erro ao enviar requisição: {}, Tentativa {}/12, esperando 30 segundos para tentar novamente...", err, attempts);
-                        time::sleep(Duration::from_secs(30)).await;
-                    }
-                }
+        let token = Self::get_access_token(globs, None).await?;
+
+        match Self::send_authenticated(route, &token, globs).await {
+            // Token revogado ou expirado antes do previsto: renova uma única vez e tenta de novo
+            Err(HttpClientError::Status(StatusCode::UNAUTHORIZED, _)) => {
+                let token = Self::get_access_token(globs, Some(&token.access_token)).await?;
+                Self::send_authenticated(route, &token, globs).await.map_err(|err| err.to_string())
+            },
+            result => result.map_err(|err| err.to_string()),
+        }
+    }
+
+    async fn send_authenticated(route: &str, token: &LaagerToken, globs: &Arc<GlobalVars>) -> Result<String, HttpClientError> {
+        let request = UpstreamRequest::new(HttpMethod::Get, format!("{}/{}", globs.configfile.APILAAGER_URL, route))
+            .header("Authorization", format!("{} {}", token.token_type, token.access_token))
+            .header("Accept", "application/json");
+
+        globs.http_client.send("laager", &request).await
+    }
+
+    /// Retorna o token em cache, fazendo login se ele não existe, está perto de expirar ou é o `rejected_token`
+    /// (recusado pela API). O lock fica retido durante o login para que requisições concorrentes façam um único login.
+    async fn get_access_token(globs: &Arc<GlobalVars>, rejected_token: Option<&str>) -> Result<LaagerToken, String> {
+        let mut cached = globs.laager_token.lock().await;
+
+        if let Some(token) = cached.as_ref() {
+            let rejected = rejected_token == Some(token.access_token.as_str());
+            if !rejected && Utc::now().timestamp() < token.expires_at - LAAGER_TOKEN_REFRESH_MARGIN_S {
+                return Ok(token.clone());
             }
         }
+
+        let login = Self::laager_login(globs).await?;
+        let token = LaagerToken {
+            access_token: login.access_token,
+            token_type: login.token_type,
+            expires_at: i64::from(login.created_at) + i64::from(login.expires_in),
+        };
+        *cached = Some(token.clone());
+
+        Ok(token)
     }
 
     fn create_login_body(globs: &Arc<GlobalVars>) -> LaagerLoginRequestBody {
@@ -59,43 +77,17 @@ impl LaagerApi {
     }
     
     async fn laager_login(globs: &Arc<GlobalVars>) -> Result<LaagerLoginResponseData, String> {
-        let mut attempts = 0;
         let body = Self::create_login_body(globs);
+        let body = serde_json::to_value(&body).map_err(|err| format!("Erro ao serializar JSON, {}", err))?;
 
-        loop {
-            let response = reqwest::Client::new()
-                .post(&format!("{}/{}", globs.configfile.APILAAGER_URL, "/oauth/token"))
-                .json(&body)
-                .send()
-                .await;
-    
-            match response {
-                Ok(response_body) => {
-                    if response_body.status().is_success() {
-                        let result = response_body.text().await.map_err(|err| format!("Erro ao ler resposta: {}", err))?;
-                        let result_data: LaagerLoginResponseData = serde_json::from_str(&result).map_err(|err| format!("Erro ao desserealizar JSON, {}", err))?;
-                        return Ok(result_data);
-                    } else {
-                        attempts += 1;
-                        if attempts >= 12 {
-                            return Err(format!("Erro ao fazer login na API da Laager, Status: {}", response_body.status()));
-                        } else {
-                            eprintln!("Erro ao fazer login na API da Laager: {}, Tentativa {}/12, esperando 30 segundos para tentar novamente...", response_body.status(), attempts);
-                            time::sleep(Duration::from_secs(30)).await;
-                        }
-                    }
-                }
-                Err(err) => {
-                    attempts += 1;
-                    if attempts >= 12 {
-                        return Err(format!("Erro ao enviar requisição após {} tentativas: {}", attempts, err));
-                    } else {
-                        eprintln!("Erro ao enviar requisição: {}, Tentativa {}/12, esperando 30 segundos para tentar novamente...", err, attempts);
-                        time::sleep(Duration::from_secs(30)).await;
-                    }
-                }
-            }
-        }
+        let request = UpstreamRequest::new(HttpMethod::Post, format!("{}/{}", globs.configfile.APILAAGER_URL, "/oauth/token"))
+            .json(body);
+
+        let result = globs.http_client.send("laager", &request).await
+            .map_err(|err| format!("Erro ao fazer login na API da Laager, {}", err))?;
+        let result_data: LaagerLoginResponseData = serde_json::from_str(&result).map_err(|err| format!("Erro ao desserealizar JSON, {}", err))?;
+
+        Ok(result_data)
     }
 
     pub async fn verify_laager_meter(laager_id: &str, globs: &Arc<GlobalVars>) -> Result<String, String> {
@@ -0,0 +1,229 @@
+use chrono::{NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::str::FromStr;
+use std::sync::Arc;
+use crate::db::entities::api_server_snapshots::{get_api_server_snapshot, get_api_server_snapshots, upsert_api_server_snapshot};
+use crate::external_api::api_server::ApiServer;
+use crate::models::database_models::api_server_snapshots::ApiServerSnapshot;
+use crate::models::external_models::{client::ClientInfo, device::ConfigDevices, unit::UnitInfo};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+const SNAPSHOT_CLIENT: &str = "client";
+const SNAPSHOT_UNIT: &str = "unit";
+const SNAPSHOT_CONFIG_DEVICES: &str = "config_devices";
+
+/// Origem das configurações de clientes, unidades e dispositivos usadas no processamento.
+/// Com `StoredSnapshots` o reprocessamento usa apenas o que foi gravado no banco, sem acessar o API Server
+/// (nem a API da Laager, cujos medidores são ignorados nesse modo).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    ApiServer,
+    StoredSnapshots,
+}
+
+impl ConfigSource {
+    pub fn from_stored_snapshots_flag(use_stored_snapshots: Option<bool>) -> Self {
+        if use_stored_snapshots.unwrap_or(false) { ConfigSource::StoredSnapshots } else { ConfigSource::ApiServer }
+    }
+
+    pub async fn get_clients(&self, client_ids: Option<Vec<i32>>, day: &str, globs: &Arc<GlobalVars>) -> Result<Vec<ClientInfo>, String> {
+        match self {
+            ConfigSource::ApiServer => ApiServer::get_clients(client_ids, day, globs).await,
+            ConfigSource::StoredSnapshots => {
+                let snapshots = get_api_server_snapshots(SNAPSHOT_CLIENT, None, parse_day(day)?, globs)
+                    .map_err(|err| format!("Erro ao buscar snapshots de clientes, {}", err))?;
+                let clients: Vec<ClientInfo> = parse_payloads(snapshots.iter().filter(|snapshot| !snapshot.deleted))?;
+                let client_ids = client_ids.unwrap_or_default();
+
+                Ok(clients.into_iter().filter(|client| client_ids.is_empty() || client_ids.contains(&client.client_id)).collect())
+            }
+        }
+    }
+
+    pub async fn get_all_units_by_client(&self, client_id: &i32, units_with_others_timezones: Option<bool>, unit_ids: Option<Vec<i32>>, day: &str, globs: &Arc<GlobalVars>) -> Result<Vec<UnitInfo>, String> {
+        match self {
+            ConfigSource::ApiServer => ApiServer::get_all_units_by_client(client_id, units_with_others_timezones, unit_ids, day, globs).await,
+            ConfigSource::StoredSnapshots => {
+                let snapshots = get_api_server_snapshots(SNAPSHOT_UNIT, Some(*client_id), parse_day(day)?, globs)
+                    .map_err(|err| format!("Erro ao buscar snapshots de unidades do cliente {}, {}", client_id, err))?;
+                let units: Vec<UnitInfo> = parse_payloads(snapshots.iter()
+                    .filter(|snapshot| !snapshot.deleted)
+                    .filter(|snapshot| in_timezone_group(snapshot.other_timezone, units_with_others_timezones)))?;
+                let unit_ids = unit_ids.unwrap_or_default();
+
+                Ok(units.into_iter().filter(|unit| unit_ids.is_empty() || unit_ids.contains(&unit.unit_id)).collect())
+            }
+        }
+    }
+
+    pub async fn get_config_devices(&self, unit_id: &i32, day: &str, globs: &Arc<GlobalVars>) -> Result<ConfigDevices, String> {
+        match self {
+            ConfigSource::ApiServer => ApiServer::get_config_devices(unit_id, day, globs).await,
+            ConfigSource::StoredSnapshots => {
+                let snapshot = get_api_server_snapshot(SNAPSHOT_CONFIG_DEVICES, *unit_id, parse_day(day)?, globs)
+                    .map_err(|err| format!("Erro ao buscar snapshot de dispositivos da unidade {}, {}", unit_id, err))?
+                    .filter(|snapshot| !snapshot.deleted)
+                    .ok_or_else(|| format!("Nenhum snapshot de dispositivos da unidade {} até o dia {}", unit_id, day))?;
+
+                serde_json::from_str(&snapshot.payload).map_err(|err| format!("Erro ao desserealizar snapshot, {}", err))
+            }
+        }
+    }
+}
+
+/// Grava os clientes retornados pelo API Server. Se a lista é completa (sem filtro de clientes),
+/// os clientes que deixaram de vir recebem uma marca de remoção.
+pub fn save_clients_snapshot(response: &str, complete_list: bool, day: &str, globs: &Arc<GlobalVars>) {
+    let mut present = Vec::new();
+    for item in list_items(response) {
+        if let Some(client_id) = item.get("CLIENT_ID").and_then(Value::as_i64) {
+            save_snapshot(SNAPSHOT_CLIENT, client_id as i32, None, None, &item, day, globs);
+            present.push(client_id as i32);
+        }
+    }
+
+    if complete_list {
+        save_deletions(SNAPSHOT_CLIENT, None, &present, None, day, globs);
+    }
+}
+
+/// Grava as unidades do cliente retornadas pelo API Server, com o grupo de fuso horário da consulta.
+/// Se a lista é completa (sem filtro de unidades), as unidades do mesmo grupo que deixaram de vir recebem uma marca de remoção.
+pub fn save_units_snapshot(response: &str, client_id: i32, units_with_others_timezones: Option<bool>, complete_list: bool, day: &str, globs: &Arc<GlobalVars>) {
+    let mut present = Vec::new();
+    for item in list_items(response) {
+        if let Some(unit_id) = item.get("UNIT_ID").and_then(Value::as_i64) {
+            save_snapshot(SNAPSHOT_UNIT, unit_id as i32, Some(client_id), units_with_others_timezones, &item, day, globs);
+            present.push(unit_id as i32);
+        }
+    }
+
+    if complete_list {
+        save_deletions(SNAPSHOT_UNIT, Some(client_id), &present, units_with_others_timezones, day, globs);
+    }
+}
+
+pub fn save_config_devices_snapshot(response: &str, unit_id: i32, day: &str, globs: &Arc<GlobalVars>) {
+    match serde_json::from_str::<Value>(response) {
+        Ok(payload) => save_snapshot(SNAPSHOT_CONFIG_DEVICES, unit_id, None, None, &payload, day, globs),
+        Err(err) => write_to_log_file_thread(&format!("Erro ao ler snapshot de dispositivos da unidade {}, {}", unit_id, err), 0, "ERROR"),
+    }
+}
+
+// Só grava quando a configuração difere da vigente no dia; sem mudança, o snapshot anterior continua valendo
+fn save_snapshot(snapshot_type: &str, reference_id: i32, parent_reference_id: Option<i32>, other_timezone: Option<bool>, payload: &Value, day: &str, globs: &Arc<GlobalVars>) {
+    let record_date = match parse_day(day) {
+        Ok(record_date) => record_date,
+        Err(err) => {
+            write_to_log_file_thread(&err, 0, "ERROR");
+            return;
+        }
+    };
+
+    let current = match get_api_server_snapshot(snapshot_type, reference_id, record_date, globs) {
+        Ok(current) => current,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao buscar snapshot vigente {} {}, {}", snapshot_type, reference_id, err), 0, "ERROR");
+            None
+        }
+    };
+
+    // A comparação é feita sobre o JSON interpretado, assim a ordem das chaves não gera falsas mudanças
+    let unchanged = current.as_ref().map(|current| {
+        !current.deleted
+            && current.parent_reference_id == parent_reference_id
+            && (other_timezone.is_none() || current.other_timezone == other_timezone)
+            && serde_json::from_str::<Value>(&current.payload).map(|previous| &previous == payload).unwrap_or(false)
+    }).unwrap_or(false);
+    if unchanged {
+        return;
+    }
+
+    write_to_log_file_thread(&format!("Configuração {} {} alterada no dia {}", snapshot_type, reference_id, day), 0, "INFO");
+
+    let snapshot = ApiServerSnapshot {
+        snapshot_type: snapshot_type.to_string(),
+        reference_id,
+        record_date,
+        parent_reference_id,
+        payload: payload.to_string(),
+        changed: true,
+        fetched_at: Utc::now().naive_utc(),
+        deleted: false,
+        other_timezone: other_timezone.or_else(|| current.and_then(|current| current.other_timezone)),
+    };
+
+    store_snapshot(&snapshot, globs);
+}
+
+// Marca como removidas as referências vigentes no dia que não vieram na lista completa do API Server
+fn save_deletions(snapshot_type: &str, parent_reference_id: Option<i32>, present: &[i32], other_timezone: Option<bool>, day: &str, globs: &Arc<GlobalVars>) {
+    let record_date = match parse_day(day) {
+        Ok(record_date) => record_date,
+        Err(err) => {
+            write_to_log_file_thread(&err, 0, "ERROR");
+            return;
+        }
+    };
+
+    let current = match get_api_server_snapshots(snapshot_type, parent_reference_id, record_date, globs) {
+        Ok(current) => current,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao buscar snapshots vigentes {}, {}", snapshot_type, err), 0, "ERROR");
+            return;
+        }
+    };
+
+    for snapshot in current {
+        if snapshot.deleted || present.contains(&snapshot.reference_id) || !in_timezone_group(snapshot.other_timezone, other_timezone) {
+            continue;
+        }
+
+        write_to_log_file_thread(&format!("Configuração {} {} removida no dia {}", snapshot_type, snapshot.reference_id, day), 0, "INFO");
+        store_snapshot(&ApiServerSnapshot {
+            record_date,
+            changed: true,
+            fetched_at: Utc::now().naive_utc(),
+            deleted: true,
+            ..snapshot
+        }, globs);
+    }
+}
+
+fn store_snapshot(snapshot: &ApiServerSnapshot, globs: &Arc<GlobalVars>) {
+    if let Err(err) = upsert_api_server_snapshot(snapshot, globs) {
+        let msg_error = format!("Erro ao gravar snapshot {} {} do dia {}, {}", snapshot.snapshot_type, snapshot.reference_id, snapshot.record_date, err);
+        write_to_log_file_thread(&msg_error, 0, "ERROR");
+        eprintln!("{}", msg_error);
+    }
+}
+
+// Unidades buscadas sem o filtro de fuso horário são consideradas do fuso padrão
+fn in_timezone_group(unit_other_timezone: Option<bool>, units_with_others_timezones: Option<bool>) -> bool {
+    match units_with_others_timezones {
+        Some(others) => unit_other_timezone.unwrap_or(false) == others,
+        None => true,
+    }
+}
+
+fn list_items(response: &str) -> Vec<Value> {
+    match serde_json::from_str::<Value>(response) {
+        Ok(Value::Object(mut data)) => match data.remove("list") {
+            Some(Value::Array(items)) => items,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn parse_payloads<'a, T: DeserializeOwned>(snapshots: impl Iterator<Item = &'a ApiServerSnapshot>) -> Result<Vec<T>, String> {
+    snapshots
+        .map(|snapshot| serde_json::from_str(&snapshot.payload).map_err(|err| format!("Erro ao desserealizar snapshot, {}", err)))
+        .collect()
+}
+
+fn parse_day(day: &str) -> Result<NaiveDate, String> {
+    NaiveDate::from_str(day).map_err(|err| format!("Data inválida {}, {}", day, err))
+}
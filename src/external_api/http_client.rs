@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+// Tempo máximo de cada requisição (conexão + resposta)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_ATTEMPTS: u32 = 5;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+// Falhas consecutivas que abrem o circuito de um upstream, e por quanto tempo ele fica aberto
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+const CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+pub struct UpstreamRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub json_body: Option<Value>,
+}
+
+impl UpstreamRequest {
+    pub fn new(method: HttpMethod, url: String) -> Self {
+        Self {
+            method,
+            url,
+            headers: Vec::new(),
+            json_body: None,
+        }
+    }
+
+    pub fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn json(mut self, body: Value) -> Self {
+        self.json_body = Some(body);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum HttpClientError {
+    /// O upstream respondeu com um status de erro (não tratado ou após esgotar as tentativas)
+    Status(StatusCode, String),
+    /// Falha de conexão, timeout ou leitura da resposta
+    Transport(String),
+    /// O circuito do upstream está aberto, a requisição nem foi enviada
+    CircuitOpen(String),
+}
+
+impl fmt::Display for HttpClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpClientError::Status(status, url) => write!(f, "Erro na requisição: {}, Status: {}", url, status),
+            HttpClientError::Transport(err) => write!(f, "Erro ao enviar requisição: {}", err),
+            HttpClientError::CircuitOpen(upstream) => write!(f, "Circuito aberto para {}, requisição não enviada", upstream),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Cliente HTTP compartilhado pelas integrações externas. Reaproveita conexões, aplica timeout por requisição,
+/// tenta novamente com backoff exponencial apenas em erros transitórios e mantém um circuit breaker por upstream.
+pub struct HttpClient {
+    client: reqwest::Client,
+    circuits: Mutex<HashMap<String, CircuitState>>,
+}
+
+impl HttpClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Erro ao criar cliente HTTP");
+
+        Self {
+            client,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Envia a requisição e retorna o corpo da resposta. `upstream` identifica o circuito (ex.: "apiserver", "laager").
+    pub async fn send(&self, upstream: &str, request: &UpstreamRequest) -> Result<String, HttpClientError> {
+        let mut attempts = 0;
+        loop {
+            self.check_circuit(upstream)?;
+            attempts += 1;
+
+            let error = match self.send_once(request).await {
+                Ok(response) if response.status().is_success() => {
+                    let text = response.text().await.map_err(|err| HttpClientError::Transport(format!("Erro ao ler resposta: {}", err)))?;
+                    self.record_success(upstream);
+                    return Ok(text);
+                },
+                Ok(response) => HttpClientError::Status(response.status(), request.url.clone()),
+                Err(err) => HttpClientError::Transport(err.to_string()),
+            };
+
+            let retryable = match &error {
+                HttpClientError::Status(status, _) => is_retryable_status(*status),
+                _ => true,
+            };
+            if retryable {
+                self.record_failure(upstream);
+            }
+            if !retryable || attempts >= MAX_ATTEMPTS {
+                return Err(error);
+            }
+
+            let delay = backoff_delay(attempts);
+            eprintln!("{}, Tentativa {}/{}, esperando {} segundos para tentar novamente...", error, attempts, MAX_ATTEMPTS, delay.as_secs());
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn send_once(&self, request: &UpstreamRequest) -> Result<reqwest::Response, reqwest::Error> {
+        let mut builder = match request.method {
+            HttpMethod::Get => self.client.get(&request.url),
+            HttpMethod::Post => self.client.post(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(*name, value);
+        }
+        if let Some(body) = &request.json_body {
+            builder = builder.json(body);
+        }
+        builder.send().await
+    }
+
+    fn check_circuit(&self, upstream: &str) -> Result<(), HttpClientError> {
+        let circuits = self.circuits.lock().unwrap();
+        match circuits.get(upstream).and_then(|state| state.open_until) {
+            Some(open_until) if Instant::now() < open_until => Err(HttpClientError::CircuitOpen(upstream.to_owned())),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_success(&self, upstream: &str) {
+        self.circuits.lock().unwrap().remove(upstream);
+    }
+
+    fn record_failure(&self, upstream: &str) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let state = circuits.entry(upstream.to_owned()).or_default();
+        state.consecutive_failures += 1;
+        // Depois do tempo aberto, uma nova falha (meio-aberto) reabre o circuito imediatamente
+        if state.consecutive_failures >= CIRCUIT_FAILURE_THRESHOLD {
+            eprintln!("Abrindo circuito para {} por {} segundos", upstream, CIRCUIT_OPEN_DURATION.as_secs());
+            state.open_until = Some(Instant::now() + CIRCUIT_OPEN_DURATION);
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn backoff_delay(attempt: u32) -> Duration {
+    BACKOFF_BASE.saturating_mul(2u32.saturating_pow(attempt - 1)).min(BACKOFF_MAX)
+}
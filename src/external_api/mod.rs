@@ -1,2 +1,4 @@
 pub mod api_server;
 pub mod api_laager;
+pub mod http_client;
+pub mod config_snapshots;
@@ -0,0 +1,330 @@
+use chrono::{Duration, NaiveDate};
+
+// Mínimo de dias completos no período de referência para ajustar a linha de base
+pub const MIN_BASELINE_DAYS: usize = 30;
+pub const SAVINGS_CONFIDENCE_LEVEL: i32 = 90;
+// Limite de ρ para que n' não chegue a zero
+const MAX_RESIDUAL_AUTOCORRELATION: f64 = 0.99;
+
+/// Um dia do período de referência ou de relatório. `consumption` é None quando o dia não tem as 24 horas válidas.
+#[derive(Debug, Clone)]
+pub struct BaselineObservation {
+    pub day: NaiveDate,
+    pub consumption: Option<f64>,
+    pub average_temperature: Option<f64>,
+    pub operating_hours: Option<f64>,
+}
+
+/// Variáveis independentes do modelo. O número de dias entra como intercepto (consumo base por dia).
+#[derive(Debug, Clone, Copy)]
+pub struct BaselineSpec {
+    pub cooling_base_temperature: Option<f64>,
+    pub uses_operating_hours: bool,
+}
+
+/// Regressão linear diária (IPMVP opção C): consumo = intercepto + a * graus-dia de resfriamento + b * horas de funcionamento
+#[derive(Debug, Clone)]
+pub struct BaselineModel {
+    pub spec: BaselineSpec,
+    pub intercept: f64,
+    pub cooling_degree_days_coefficient: Option<f64>,
+    pub operating_hours_coefficient: Option<f64>,
+    pub observations: usize,
+    pub r_squared: f64,
+    pub cv_rmse: f64,
+    pub rmse: f64,
+    /// Autocorrelação de ordem 1 dos resíduos entre dias consecutivos, usada no número efetivo de observações
+    pub residual_autocorrelation: f64,
+}
+
+impl BaselineSpec {
+    fn parameters(&self) -> usize {
+        1 + self.cooling_base_temperature.is_some() as usize + self.uses_operating_hours as usize
+    }
+
+    /// Linha da matriz de regressão do dia, None se faltar alguma variável do modelo
+    fn features(&self, observation: &BaselineObservation) -> Option<Vec<f64>> {
+        let mut features = vec![1.0];
+        if let Some(base) = self.cooling_base_temperature {
+            features.push((observation.average_temperature? - base).max(0.0));
+        }
+        if self.uses_operating_hours {
+            features.push(observation.operating_hours?);
+        }
+        Some(features)
+    }
+}
+
+impl BaselineModel {
+    /// Ajusta o modelo por mínimos quadrados com os dias completos do período de referência
+    pub fn fit(spec: BaselineSpec, observations: &[BaselineObservation]) -> Result<Self, String> {
+        let samples: Vec<(NaiveDate, Vec<f64>, f64)> = observations.iter()
+            .filter_map(|observation| Some((observation.day, spec.features(observation)?, observation.consumption?)))
+            .collect();
+
+        let n = samples.len();
+        let p = spec.parameters();
+        if n < MIN_BASELINE_DAYS.max(p + 1) {
+            return Err(format!("Período de referência com {} dias completos, mínimo de {}", n, MIN_BASELINE_DAYS));
+        }
+
+        // Equações normais: (X'X) b = X'y
+        let mut xtx = vec![vec![0.0; p]; p];
+        let mut xty = vec![0.0; p];
+        for (_, x, y) in &samples {
+            for i in 0..p {
+                xty[i] += x[i] * y;
+                for j in 0..p {
+                    xtx[i][j] += x[i] * x[j];
+                }
+            }
+        }
+        let coefficients = solve_linear_system(xtx, xty)
+            .ok_or_else(|| "Variáveis independentes sem variação no período de referência".to_owned())?;
+
+        let mean = samples.iter().map(|(_, _, y)| y).sum::<f64>() / n as f64;
+        let mut squared_error = 0.0;
+        let mut squared_total = 0.0;
+        let mut residuals = Vec::with_capacity(n);
+        for (day, x, y) in &samples {
+            let predicted: f64 = x.iter().zip(&coefficients).map(|(value, coefficient)| value * coefficient).sum();
+            squared_error += (y - predicted).powi(2);
+            squared_total += (y - mean).powi(2);
+            residuals.push((*day, y - predicted));
+        }
+
+        let rmse = (squared_error / (n - p) as f64).sqrt();
+        let mut coefficients = coefficients.into_iter();
+        let intercept = coefficients.next().unwrap_or_default();
+
+        Ok(BaselineModel {
+            spec,
+            intercept,
+            cooling_degree_days_coefficient: spec.cooling_base_temperature.and_then(|_| coefficients.next()),
+            operating_hours_coefficient: if spec.uses_operating_hours { coefficients.next() } else { None },
+            observations: n,
+            r_squared: if squared_total > 0.0 { 1.0 - squared_error / squared_total } else { 0.0 },
+            cv_rmse: if mean > 0.0 { rmse / mean * 100.0 } else { 0.0 },
+            rmse,
+            residual_autocorrelation: lag1_autocorrelation(&residuals, squared_error),
+        })
+    }
+
+    /// Consumo previsto pela linha de base nas condições do dia (linha de base ajustada)
+    pub fn predict(&self, observation: &BaselineObservation) -> Option<f64> {
+        let features = self.spec.features(observation)?;
+        let coefficients = [Some(self.intercept), self.cooling_degree_days_coefficient, self.operating_hours_coefficient];
+        let predicted: f64 = features.iter().zip(coefficients.iter().flatten()).map(|(value, coefficient)| value * coefficient).sum();
+        Some(predicted.max(0.0))
+    }
+
+    /// Incerteza absoluta da economia somada em `reporting_days` dias, no nível SAVINGS_CONFIDENCE_LEVEL
+    /// (ASHRAE Guideline 14: t * RMSE * sqrt(m * (n/n') * (1 + 2/n')), com n' = n * (1 - ρ) / (1 + ρ)).
+    /// None quando n' não deixa graus de liberdade, ou seja, a incerteza é indeterminada.
+    pub fn savings_uncertainty(&self, reporting_days: usize) -> Option<f64> {
+        if reporting_days == 0 || self.observations == 0 {
+            return Some(0.0);
+        }
+        let m = reporting_days as f64;
+        let n = self.observations as f64;
+        let effective_n = self.effective_observations();
+        let degrees_of_freedom = (effective_n.floor() as usize).saturating_sub(self.spec.parameters());
+        Some(t_value_90(degrees_of_freedom)? * self.rmse * (m * (n / effective_n) * (1.0 + 2.0 / effective_n)).sqrt())
+    }
+
+    // Autocorrelação negativa não aumenta o número de observações independentes
+    fn effective_observations(&self) -> f64 {
+        let rho = self.residual_autocorrelation.clamp(0.0, MAX_RESIDUAL_AUTOCORRELATION);
+        (self.observations as f64 * (1.0 - rho) / (1.0 + rho)).max(1.0)
+    }
+}
+
+// Autocorrelação de ordem 1 dos resíduos, só com pares de dias consecutivos (dias incompletos ficam fora do modelo)
+fn lag1_autocorrelation(residuals: &[(NaiveDate, f64)], squared_error: f64) -> f64 {
+    if squared_error <= 0.0 {
+        return 0.0;
+    }
+    let lagged: f64 = residuals.windows(2)
+        .filter(|pair| pair[1].0 - pair[0].0 == Duration::days(1))
+        .map(|pair| pair[0].1 * pair[1].1)
+        .sum();
+    lagged / squared_error
+}
+
+// Eliminação de Gauss com pivotamento parcial. None se o sistema for singular.
+fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let size = b.len();
+    for col in 0..size {
+        let pivot = (col..size).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-9 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col].clone();
+        for row in (col + 1)..size {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, pivot_value) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let known: f64 = ((row + 1)..size).map(|k| a[row][k] * solution[k]).sum();
+        solution[row] = (b[row] - known) / a[row][row];
+    }
+    Some(solution)
+}
+
+// Valores t de Student bicaudais para 90% de confiança, de 1 a 30 graus de liberdade
+const T_VALUES_90: [f64; 30] = [
+    6.314, 2.920, 2.353, 2.132, 2.015, 1.943, 1.895, 1.860, 1.833, 1.812,
+    1.796, 1.782, 1.771, 1.761, 1.753, 1.746, 1.740, 1.734, 1.729, 1.725,
+    1.721, 1.717, 1.714, 1.711, 1.708, 1.706, 1.703, 1.701, 1.699, 1.697,
+];
+
+// Valor t de Student bicaudal para 90% de confiança. None sem graus de liberdade.
+fn t_value_90(degrees_of_freedom: usize) -> Option<f64> {
+    match degrees_of_freedom {
+        0 => None,
+        1..=30 => Some(T_VALUES_90[degrees_of_freedom - 1]),
+        31..=40 => Some(1.684),
+        41..=60 => Some(1.671),
+        61..=120 => Some(1.658),
+        _ => Some(1.645),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLING_BASE: f64 = 20.0;
+
+    fn cooling_spec() -> BaselineSpec {
+        BaselineSpec { cooling_base_temperature: Some(COOLING_BASE), uses_operating_hours: false }
+    }
+
+    fn observation(offset: i64, consumption: Option<f64>, average_temperature: f64) -> BaselineObservation {
+        BaselineObservation {
+            day: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + Duration::days(offset),
+            consumption,
+            average_temperature: Some(average_temperature),
+            operating_hours: None,
+        }
+    }
+
+    // consumo = 100 + 5 * graus-dia, com a temperatura variando entre 18 °C e 30 °C
+    fn exact_observations(days: i64) -> Vec<BaselineObservation> {
+        (0..days).map(|offset| {
+            let temperature = 18.0 + (offset % 13) as f64;
+            observation(offset, Some(100.0 + 5.0 * (temperature - COOLING_BASE).max(0.0)), temperature)
+        }).collect()
+    }
+
+    fn model_with_autocorrelation(residual_autocorrelation: f64) -> BaselineModel {
+        BaselineModel {
+            spec: cooling_spec(),
+            intercept: 100.0,
+            cooling_degree_days_coefficient: Some(5.0),
+            operating_hours_coefficient: None,
+            observations: 60,
+            r_squared: 0.9,
+            cv_rmse: 10.0,
+            rmse: 10.0,
+            residual_autocorrelation,
+        }
+    }
+
+    #[test]
+    fn fit_recovers_the_coefficients_of_an_exact_relation() {
+        let model = BaselineModel::fit(cooling_spec(), &exact_observations(40)).unwrap();
+
+        assert!((model.intercept - 100.0).abs() < 1e-6);
+        assert!((model.cooling_degree_days_coefficient.unwrap() - 5.0).abs() < 1e-6);
+        assert_eq!(model.operating_hours_coefficient, None);
+        assert_eq!(model.observations, 40);
+        assert!((model.r_squared - 1.0).abs() < 1e-9);
+        assert!(model.rmse < 1e-6);
+
+        let predicted = model.predict(&observation(0, None, 26.0)).unwrap();
+        assert!((predicted - 130.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fit_ignores_incomplete_days_and_requires_the_minimum_of_complete_days() {
+        let mut observations = exact_observations(MIN_BASELINE_DAYS as i64);
+        observations[0].consumption = None;
+        assert!(BaselineModel::fit(cooling_spec(), &observations).is_err());
+
+        observations.push(observation(MIN_BASELINE_DAYS as i64, Some(110.0), 22.0));
+        assert_eq!(BaselineModel::fit(cooling_spec(), &observations).unwrap().observations, MIN_BASELINE_DAYS);
+    }
+
+    #[test]
+    fn fit_rejects_variables_without_variation() {
+        let observations: Vec<BaselineObservation> = (0..40).map(|offset| observation(offset, Some(100.0 + offset as f64), 15.0)).collect();
+        assert!(BaselineModel::fit(cooling_spec(), &observations).is_err());
+    }
+
+    #[test]
+    fn fit_measures_lag1_autocorrelation_of_residuals_on_consecutive_days() {
+        // Resíduos alternados (+2, -2) entre dias consecutivos: ρ próximo de -1
+        let alternating: Vec<BaselineObservation> = exact_observations(40).into_iter().enumerate()
+            .map(|(index, mut day)| {
+                day.consumption = day.consumption.map(|value| value + if index % 2 == 0 { 2.0 } else { -2.0 });
+                day
+            })
+            .collect();
+        let model = BaselineModel::fit(cooling_spec(), &alternating).unwrap();
+        assert!(model.residual_autocorrelation < -0.8, "ρ = {}", model.residual_autocorrelation);
+
+        // Resíduos em blocos de 10 dias com o mesmo sinal: ρ positivo
+        let blocks: Vec<BaselineObservation> = exact_observations(40).into_iter().enumerate()
+            .map(|(index, mut day)| {
+                day.consumption = day.consumption.map(|value| value + if (index / 10) % 2 == 0 { 2.0 } else { -2.0 });
+                day
+            })
+            .collect();
+        let model = BaselineModel::fit(cooling_spec(), &blocks).unwrap();
+        assert!(model.residual_autocorrelation > 0.5, "ρ = {}", model.residual_autocorrelation);
+    }
+
+    #[test]
+    fn savings_uncertainty_grows_with_positive_autocorrelation_only() {
+        let independent = model_with_autocorrelation(0.0).savings_uncertainty(30).unwrap();
+        // t(58 gl) * RMSE * sqrt(m * (1 + 2/n))
+        assert!((independent - 1.671 * 10.0 * (30.0_f64 * (1.0 + 2.0 / 60.0)).sqrt()).abs() < 1e-9);
+
+        assert!(model_with_autocorrelation(0.5).savings_uncertainty(30).unwrap() > independent);
+        assert_eq!(model_with_autocorrelation(-0.5).savings_uncertainty(30), Some(independent));
+        assert_eq!(model_with_autocorrelation(0.5).savings_uncertainty(0), Some(0.0));
+    }
+
+    #[test]
+    fn savings_uncertainty_uses_small_sample_t_values_with_strong_autocorrelation() {
+        // ρ = 0.9: n' = 60 * 0.1 / 1.9 ≈ 3.16, 3 - 2 parâmetros = 1 grau de liberdade
+        let effective_n: f64 = 60.0 * 0.1 / 1.9;
+        let expected = 6.314 * 10.0 * (30.0 * (60.0 / effective_n) * (1.0 + 2.0 / effective_n)).sqrt();
+        let uncertainty = model_with_autocorrelation(0.9).savings_uncertainty(30).unwrap();
+        assert!((uncertainty - expected).abs() < 1e-9, "incerteza = {}", uncertainty);
+
+        // ρ = 0.95 deixa n' ≈ 1.54 e nenhum grau de liberdade: incerteza indeterminada
+        assert_eq!(model_with_autocorrelation(0.95).savings_uncertainty(30), None);
+        assert_eq!(model_with_autocorrelation(1.0).savings_uncertainty(30), None);
+    }
+
+    #[test]
+    fn t_value_90_follows_the_student_table() {
+        assert_eq!(t_value_90(0), None);
+        assert_eq!(t_value_90(1), Some(6.314));
+        assert_eq!(t_value_90(10), Some(1.812));
+        assert_eq!(t_value_90(30), Some(1.697));
+        assert_eq!(t_value_90(58), Some(1.671));
+        assert_eq!(t_value_90(1000), Some(1.645));
+    }
+}
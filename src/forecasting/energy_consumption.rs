@@ -0,0 +1,288 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Weekday};
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::http::structs::energy_forecast::{CircuitHourConsumption, ForecastModelError};
+
+// Semanas de histórico consideradas pelos modelos
+pub const FORECAST_HISTORY_WEEKS: i64 = 12;
+// Quantidade de semanas à frente gravadas em energy_consumption_forecast
+pub const FORECAST_WEEKS_AHEAD: i64 = 5;
+
+// Modelo legado: média das últimas ocorrências do mesmo dia da semana e hora
+const WEEKDAY_AVERAGE_SAMPLES: usize = 4;
+// Mínimo de semanas de histórico para o modelo sazonal considerar a tendência
+const SEASONAL_MIN_WEEKS_FOR_TREND: i64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForecastModel {
+    WeekdayAverage,
+    Seasonal,
+}
+
+impl ForecastModel {
+    pub const ALL: [ForecastModel; 2] = [ForecastModel::WeekdayAverage, ForecastModel::Seasonal];
+
+    pub fn parse(model: &str) -> Option<Self> {
+        match model.to_uppercase().as_str() {
+            "WEEKDAY_AVERAGE" => Some(ForecastModel::WeekdayAverage),
+            "SEASONAL" => Some(ForecastModel::Seasonal),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ForecastModel::WeekdayAverage => "WEEKDAY_AVERAGE",
+            ForecastModel::Seasonal => "SEASONAL",
+        }
+    }
+
+    /// Modelo escolhido para o cliente; sem escolha (ou inválida), o padrão do configfile; sem padrão, o modelo original
+    pub fn resolve(client_model: Option<&str>, default_model: Option<&str>) -> Self {
+        client_model.and_then(Self::parse)
+            .or_else(|| default_model.and_then(Self::parse))
+            .unwrap_or(ForecastModel::WeekdayAverage)
+    }
+
+    pub fn forecaster(&self) -> Box<dyn ConsumptionForecaster> {
+        match self {
+            ForecastModel::WeekdayAverage => Box::new(WeekdayAverageForecaster),
+            ForecastModel::Seasonal => Box::new(SeasonalForecaster),
+        }
+    }
+}
+
+/// Modelo de previsão de consumo horário de um circuito.
+/// `history` contém apenas horas válidas anteriores ao momento da previsão, em ordem cronológica.
+pub trait ConsumptionForecaster {
+    fn forecast(&self, history: &[CircuitHourConsumption], holidays: &HashSet<NaiveDate>, target: NaiveDateTime) -> Option<f64>;
+}
+
+/// Média das últimas 4 ocorrências do mesmo dia da semana e hora (comportamento original)
+pub struct WeekdayAverageForecaster;
+
+impl ConsumptionForecaster for WeekdayAverageForecaster {
+    fn forecast(&self, history: &[CircuitHourConsumption], _holidays: &HashSet<NaiveDate>, target: NaiveDateTime) -> Option<f64> {
+        let values: Vec<f64> = history.iter()
+            .rev()
+            .filter(|hour| hour.record_date.hour() == target.hour() && hour.record_date.weekday() == target.weekday())
+            .take(WEEKDAY_AVERAGE_SAMPLES)
+            .filter_map(|hour| hour.consumption.to_f64())
+            .collect();
+
+        average(&values)
+    }
+}
+
+/// Decomposição por hora do dia: fator de cada tipo de dia (dia da semana, com feriados tratados como
+/// domingo) sobre o nível médio, e tendência linear do consumo dessazonalizado ao longo do histórico.
+pub struct SeasonalForecaster;
+
+impl ConsumptionForecaster for SeasonalForecaster {
+    fn forecast(&self, history: &[CircuitHourConsumption], holidays: &HashSet<NaiveDate>, target: NaiveDateTime) -> Option<f64> {
+        let samples: Vec<(NaiveDate, Weekday, f64)> = history.iter()
+            .filter(|hour| hour.record_date.hour() == target.hour())
+            .filter_map(|hour| {
+                let date = hour.record_date.date();
+                Some((date, day_type(date, holidays), hour.consumption.to_f64()?))
+            })
+            .collect();
+
+        let mut by_day_type: HashMap<Weekday, Vec<f64>> = HashMap::new();
+        for (_, weekday, value) in &samples {
+            by_day_type.entry(*weekday).or_default().push(*value);
+        }
+
+        let target_day_type = day_type(target.date(), holidays);
+        let target_average = average(by_day_type.get(&target_day_type)?)?;
+
+        let day_type_averages: Vec<f64> = by_day_type.values().filter_map(|values| average(values)).collect();
+        let level = average(&day_type_averages)?;
+        if level <= 0.0 {
+            return Some(target_average.max(0.0));
+        }
+
+        let factors: HashMap<Weekday, f64> = by_day_type.iter()
+            .filter_map(|(weekday, values)| Some((*weekday, average(values)? / level)))
+            .collect();
+        let target_factor = target_average / level;
+
+        let first_date = samples.first()?.0;
+        let last_date = samples.last()?.0;
+        if (last_date - first_date).num_weeks() < SEASONAL_MIN_WEEKS_FOR_TREND {
+            return Some(target_average.max(0.0));
+        }
+
+        let points: Vec<(f64, f64)> = samples.iter()
+            .filter_map(|(date, weekday, value)| {
+                let factor = *factors.get(weekday)?;
+                (factor > 0.0).then(|| ((*date - first_date).num_days() as f64, value / factor))
+            })
+            .collect();
+
+        let projected_level = match linear_regression(&points) {
+            Some((intercept, slope)) => intercept + slope * (target.date() - first_date).num_days() as f64,
+            None => level,
+        };
+
+        Some((projected_level * target_factor).max(0.0))
+    }
+}
+
+fn day_type(date: NaiveDate, holidays: &HashSet<NaiveDate>) -> Weekday {
+    if holidays.contains(&date) { Weekday::Sun } else { date.weekday() }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+// Mínimos quadrados: retorna (intercepto, inclinação)
+fn linear_regression(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    if variance == 0.0 {
+        return None;
+    }
+
+    let slope = covariance / variance;
+    Some((mean_y - slope * mean_x, slope))
+}
+
+/// Janela de histórico usada para prever a partir de `origin`
+pub fn history_window(history: &[CircuitHourConsumption], origin: NaiveDateTime) -> &[CircuitHourConsumption] {
+    let start = origin - Duration::weeks(FORECAST_HISTORY_WEEKS);
+    let first = history.partition_point(|hour| hour.record_date < start);
+    let last = history.partition_point(|hour| hour.record_date <= origin);
+    &history[first..last.max(first)]
+}
+
+/// Reproduz a previsão de cada modelo sobre um período passado: cada hora medida entre `start` e `end` é prevista
+/// com o histórico disponível `horizon_days` antes dela, e comparada com o consumo real.
+/// `history` deve estar ordenado por data e conter as horas válidas do circuito desde `start` menos a janela e o horizonte.
+pub fn backtest_models(history: &[CircuitHourConsumption], holidays: &HashSet<NaiveDate>, models: &[ForecastModel], start: NaiveDateTime, end: NaiveDateTime, horizon_days: i64, errors: &mut BacktestErrors) {
+    let forecasters: Vec<(ForecastModel, Box<dyn ConsumptionForecaster>)> = models.iter().map(|model| (*model, model.forecaster())).collect();
+
+    for actual in history.iter().filter(|hour| hour.record_date >= start && hour.record_date <= end) {
+        let Some(actual_value) = actual.consumption.to_f64() else { continue };
+        let window = history_window(history, actual.record_date - Duration::days(horizon_days));
+
+        let predictions: Vec<(ForecastModel, Option<f64>)> = forecasters.iter()
+            .map(|(model, forecaster)| (*model, forecaster.forecast(window, holidays, actual.record_date)))
+            .collect();
+        let forecast_by_all = predictions.iter().all(|(_, predicted)| predicted.is_some());
+
+        for (model, predicted) in predictions {
+            let accumulator = errors.models.entry(model.as_str()).or_default();
+            match predicted {
+                Some(predicted) => {
+                    accumulator.add(actual_value, predicted);
+                    if forecast_by_all {
+                        errors.common_hours.entry(model.as_str()).or_default().add(actual_value, predicted);
+                    }
+                },
+                None => accumulator.missing += 1,
+            }
+        }
+    }
+}
+
+/// Erros acumulados pelo backtest de cada modelo
+#[derive(Debug, Default)]
+pub struct BacktestErrors {
+    /// Todas as horas que o modelo conseguiu prever
+    pub models: HashMap<&'static str, ForecastErrorAccumulator>,
+    /// Só as horas previstas por todos os modelos, para compará-los sobre as mesmas horas
+    pub common_hours: HashMap<&'static str, ForecastErrorAccumulator>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ForecastErrorAccumulator {
+    samples: i64,
+    missing: i64,
+    squared_error: f64,
+    percentage_error: f64,
+    percentage_samples: i64,
+}
+
+impl ForecastErrorAccumulator {
+    fn add(&mut self, actual: f64, predicted: f64) {
+        self.samples += 1;
+        self.squared_error += (actual - predicted).powi(2);
+        // Horas sem consumo não entram no MAPE (erro percentual indefinido)
+        if actual > 0.0 {
+            self.percentage_error += ((actual - predicted) / actual).abs();
+            self.percentage_samples += 1;
+        }
+    }
+
+    pub fn summary(&self, model: &str) -> ForecastModelError {
+        ForecastModelError {
+            model: model.to_owned(),
+            samples: self.samples,
+            missing_forecasts: self.missing,
+            mape: (self.percentage_samples > 0).then(|| round2(self.percentage_error / self.percentage_samples as f64 * 100.0)),
+            rmse: (self.samples > 0).then(|| round2((self.squared_error / self.samples as f64).sqrt())),
+        }
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[test]
+    fn backtest_compares_models_only_on_hours_forecast_by_all_of_them() {
+        let first_day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        // Feriado numa quarta-feira com consumo fora do padrão. Sem domingos no histórico, o modelo sazonal
+        // (que trata feriados como domingo) não prevê o feriado, e a média por dia da semana erra
+        let holiday = NaiveDate::from_ymd_opt(2024, 1, 17).unwrap();
+        let history: Vec<CircuitHourConsumption> = (0..20 * 24)
+            .map(|hour| first_day.and_time(NaiveTime::MIN) + Duration::hours(hour))
+            .filter(|record_date| record_date.weekday() != Weekday::Sun)
+            .map(|record_date| CircuitHourConsumption {
+                electric_circuit_id: 1,
+                record_date,
+                consumption: Decimal::from(if record_date.date() == holiday { 100 } else { 10 }),
+            })
+            .collect();
+        let start = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap().and_time(NaiveTime::MIN);
+        let end = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap().and_hms_opt(23, 0, 0).unwrap();
+
+        let mut errors = BacktestErrors::default();
+        backtest_models(&history, &HashSet::from([holiday]), &ForecastModel::ALL, start, end, 7, &mut errors);
+
+        let weekday = errors.models[ForecastModel::WeekdayAverage.as_str()].summary("WEEKDAY_AVERAGE");
+        let seasonal = errors.models[ForecastModel::Seasonal.as_str()].summary("SEASONAL");
+        assert_eq!((weekday.samples, weekday.missing_forecasts), (144, 0));
+        assert_eq!((seasonal.samples, seasonal.missing_forecasts), (120, 24));
+        assert!(weekday.rmse.unwrap() > 0.0);
+
+        // Nas horas previstas pelos dois modelos, ambos acertam
+        for model in ForecastModel::ALL {
+            let common = errors.common_hours[model.as_str()].summary(model.as_str());
+            assert_eq!(common.samples, 120);
+            assert_eq!(common.rmse, Some(0.0));
+        }
+    }
+}
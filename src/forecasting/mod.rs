@@ -0,0 +1,2 @@
+pub mod energy_consumption;
+pub mod energy_baseline;
@@ -0,0 +1,32 @@
+use actix_web::{HttpRequest, HttpResponse};
+
+use crate::GlobalVars;
+
+/// Verifica o token das rotas administrativas (header `Authorization: Bearer <ADMIN_API_TOKEN>`).
+/// Retorna a resposta de erro a ser enviada quando o acesso não é permitido.
+pub fn check_admin_token(req: &HttpRequest, globs: &GlobalVars) -> Result<(), HttpResponse> {
+    let expected = match &globs.configfile.ADMIN_API_TOKEN {
+        Some(token) if !token.is_empty() => token,
+        _ => return Err(HttpResponse::Forbidden().body("Rotas administrativas desabilitadas")),
+    };
+
+    let received = req.headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match received {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(()),
+        _ => Err(HttpResponse::Unauthorized().body("Token inválido")),
+    }
+}
+
+// Compara todos os bytes mesmo após a primeira diferença, para que o tempo de resposta não revele o prefixo correto do token
+fn constant_time_eq(received: &[u8], expected: &[u8]) -> bool {
+    let mut diff = received.len() ^ expected.len();
+    for (index, expected_byte) in expected.iter().enumerate() {
+        let received_byte = received.get(index).copied().unwrap_or(0);
+        diff |= (received_byte ^ expected_byte) as usize;
+    }
+    diff == 0
+}
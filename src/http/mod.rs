@@ -1,2 +1,3 @@
 pub mod routes;
-pub mod structs;
\ No newline at end of file
+pub mod structs;
+pub mod auth;
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use crate::calendar::holidays::HolidayScope;
+use crate::calendar::unit_calendar::build_unit_calendar;
+use crate::db::entities::calendar_holidays::{delete_calendar_holiday, get_calendar_holidays, get_unit_calendar_holidays, insert_calendar_holiday, update_calendar_holiday};
+use crate::db::entities::clients::get_client;
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::calendar::{NonWorkingDay, ReqParamsGetCalendarHolidays, ReqParamsGetNonWorkingDays, ReqParamsSetCalendarHoliday};
+use crate::models::database_models::calendar_holidays::CalendarHoliday;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+// Limite do período consultado, para não expandir feriados recorrentes por anos demais
+const MAX_CALENDAR_DAYS: i64 = 731;
+
+pub fn calendar_routes() -> actix_web::Scope {
+    web::scope("/calendar")
+    .service(get_holidays)
+    .service(get_unit_non_working_days)
+    .service(create_holiday)
+    .service(edit_holiday)
+    .service(remove_holiday)
+}
+
+#[get("/holidays")]
+async fn get_holidays(query: web::Query<ReqParamsGetCalendarHolidays>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let scope = match query.scope.as_deref().map(HolidayScope::parse) {
+        Some(None) => return HttpResponse::BadRequest().body(format!("Abrangência de feriado inválida, {:?}", query)),
+        Some(Some(scope)) => Some(scope.as_str()),
+        None => None,
+    };
+
+    let client_id = match &query.client_name {
+        Some(client_name) => match find_client_id(client_name, &globs) {
+            Ok(id) => Some(id),
+            Err(response) => return response,
+        },
+        None => None,
+    };
+
+    match get_calendar_holidays(scope, query.state_name.as_deref(), query.city_name.as_deref(), client_id, &globs) {
+        Ok(holidays) => HttpResponse::Ok().json(holidays),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter feriados, {:?}: {}", query, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[get("/unit/{unit_id}/non-working-days")]
+async fn get_unit_non_working_days(unit_id: web::Path<i32>, query: web::Query<ReqParamsGetNonWorkingDays>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if query.end_date < query.start_date || (query.end_date - query.start_date).num_days() > MAX_CALENDAR_DAYS {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", query));
+    }
+
+    let unit_db_id = match find_unit_id(*unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match get_unit_calendar_holidays(unit_db_id, query.start_date, query.end_date, &globs) {
+        Ok(holidays) => {
+            let days: Vec<NonWorkingDay> = build_unit_calendar(&holidays, query.start_date, query.end_date).into_values().collect();
+            HttpResponse::Ok().json(days)
+        },
+        Err(err) => {
+            let msg_error = format!("Erro ao obter calendário da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/holiday")]
+async fn create_holiday(req: HttpRequest, req_body: web::Json<ReqParamsSetCalendarHoliday>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let holiday = match parse_holiday(&req_body, &globs) {
+        Ok(holiday) => holiday,
+        Err(response) => return response,
+    };
+
+    match insert_calendar_holiday(&holiday, &globs) {
+        Ok(id) => HttpResponse::Ok().json(json!({ "id": id })),
+        Err(err) => {
+            let msg_error = format!("Erro ao criar feriado, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[put("/holiday/{id}")]
+async fn edit_holiday(req: HttpRequest, id: web::Path<i32>, req_body: web::Json<ReqParamsSetCalendarHoliday>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let holiday = match parse_holiday(&req_body, &globs) {
+        Ok(holiday) => holiday,
+        Err(response) => return response,
+    };
+
+    match update_calendar_holiday(*id, &holiday, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Feriado {} não encontrado", id)),
+        Ok(updated) => HttpResponse::Ok().json(json!({ "updated": updated })),
+        Err(err) => {
+            let msg_error = format!("Erro ao alterar feriado {}, {:?}: {}", id, req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/holiday/{id}")]
+async fn remove_holiday(req: HttpRequest, id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    match delete_calendar_holiday(*id, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Feriado {} não encontrado", id)),
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover feriado {}: {}", id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+// Cada abrangência guarda apenas os campos que a identificam
+fn parse_holiday(req_body: &ReqParamsSetCalendarHoliday, globs: &Arc<GlobalVars>) -> Result<CalendarHoliday, HttpResponse> {
+    let bad_request = || HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+
+    let scope = HolidayScope::parse(&req_body.scope).ok_or_else(bad_request)?;
+    if req_body.name.trim().is_empty() || req_body.end_date.map(|end| end < req_body.start_date).unwrap_or(false) {
+        return Err(bad_request());
+    }
+
+    let (state_name, city_name, client_id) = match scope {
+        HolidayScope::National => (None, None, None),
+        HolidayScope::State => (Some(req_body.state_name.clone().ok_or_else(bad_request)?), None, None),
+        HolidayScope::City => (req_body.state_name.clone(), Some(req_body.city_name.clone().ok_or_else(bad_request)?), None),
+        HolidayScope::Client => {
+            let client_name = req_body.client_name.as_deref().ok_or_else(bad_request)?;
+            (None, None, Some(find_client_id(client_name, globs)?))
+        },
+    };
+
+    Ok(CalendarHoliday {
+        name: req_body.name.trim().to_owned(),
+        scope: scope.as_str().to_owned(),
+        start_date: req_body.start_date,
+        end_date: req_body.end_date.filter(|end| *end > req_body.start_date),
+        recurring: req_body.recurring.unwrap_or(false),
+        state_name,
+        city_name,
+        client_id,
+    })
+}
+
+fn find_client_id(client_name: &str, globs: &Arc<GlobalVars>) -> Result<i32, HttpResponse> {
+    match get_client(client_name, globs) {
+        Ok(Some(client)) => Ok(client.id.unwrap_or_default()),
+        Ok(None) => Err(HttpResponse::NotFound().body(format!("Cliente {} não encontrado", client_name))),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter cliente {}: {}", client_name, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            Err(HttpResponse::InternalServerError().body(msg_error))
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::json;
+use crate::db::entities::grid_emission_factors::{delete_grid_emission_factor, emission_factor_grid, get_emissions, get_grid_emission_factors, insert_grid_emission_factor};
+use crate::http::auth::check_admin_token;
+use crate::http::structs::carbon_emissions::{ReqParamsGetEmissionFactors, ReqParamsGetEmissions, ReqParamsSetEmissionFactor};
+use crate::models::database_models::grid_emission_factors::GridEmissionFactor;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn carbon_emissions_routes() -> actix_web::Scope {
+    web::scope("/carbon_emissions")
+    .service(get_emission_factors)
+    .service(set_emission_factor)
+    .service(remove_emission_factor)
+    .service(get_units_emissions)
+}
+
+#[get("/emission-factors")]
+async fn get_emission_factors(query: web::Query<ReqParamsGetEmissionFactors>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let grid = query.grid.clone().unwrap_or_else(|| emission_factor_grid(&globs));
+
+    match get_grid_emission_factors(&grid, &globs) {
+        Ok(factors) => HttpResponse::Ok().json(factors),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter fatores de emissão da rede {}: {}", grid, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/emission-factor")]
+async fn set_emission_factor(req: HttpRequest, req_body: web::Json<ReqParamsSetEmissionFactor>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    if req_body.emission_factor < Decimal::ZERO || req_body.end_date.map(|end| end < req_body.start_date).unwrap_or(false) {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let factor = GridEmissionFactor {
+        grid: req_body.grid.clone().unwrap_or_else(|| emission_factor_grid(&globs)),
+        start_date: req_body.start_date,
+        end_date: req_body.end_date,
+        emission_factor: req_body.emission_factor,
+        source: req_body.source.clone(),
+    };
+
+    match insert_grid_emission_factor(&factor, Utc::now().date_naive(), &globs) {
+        Ok(Some(id)) => HttpResponse::Ok().json(json!({ "id": id })),
+        Ok(None) => HttpResponse::Conflict().body(format!("Período já coberto por um fator de emissão em vigor da rede {}, fatores já aplicados não podem ser alterados", factor.grid)),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar fator de emissão, {:?}: {}", factor, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/emission-factor/{id}")]
+async fn remove_emission_factor(req: HttpRequest, id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    match delete_grid_emission_factor(*id, Utc::now().date_naive(), &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Fator de emissão {} não encontrado ou já em vigor", id)),
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover fator de emissão {}: {}", id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-emissions")]
+async fn get_units_emissions(req_body: web::Json<ReqParamsGetEmissions>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if req_body.end_date < req_body.start_date {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    match get_emissions(&req_body, &emission_factor_grid(&globs), &globs) {
+        Ok(emissions) => HttpResponse::Ok().json(emissions),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter emissões, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
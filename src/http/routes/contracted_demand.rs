@@ -0,0 +1,129 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use rust_decimal::Decimal;
+use serde_json::json;
+use crate::db::entities::contracted_demands::{delete_contracted_demand, get_contracted_demands, get_demand_overrun_events, insert_contracted_demand};
+use crate::db::entities::electric_circuits::get_unit_electric_circuit;
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::contracted_demand::{GetDemandOverrunsResponse, ReqParamsGetDemandOverruns, ReqParamsSetContractedDemand};
+use crate::models::database_models::contracted_demands::ContractedDemand;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+// Tolerância de ultrapassagem usada quando não informada (REN ANEEL 1000/2021)
+const DEFAULT_TOLERANCE_PERCENTAGE: i64 = 5;
+
+pub fn contracted_demand_routes() -> actix_web::Scope {
+    web::scope("/contracted_demand")
+    .service(get_unit_contracted_demands)
+    .service(set_contracted_demand)
+    .service(remove_contracted_demand)
+    .service(get_overrun_events)
+}
+
+#[get("/unit/{unit_id}")]
+async fn get_unit_contracted_demands(unit_id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let unit_db_id = match find_unit_id(*unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match get_contracted_demands(unit_db_id, &globs) {
+        Ok(demands) => HttpResponse::Ok().json(demands),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter demandas contratadas da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/contracted-demand")]
+async fn set_contracted_demand(req: HttpRequest, req_body: web::Json<ReqParamsSetContractedDemand>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    if req_body.end_date.map(|end| end < req_body.start_date).unwrap_or(false) || req_body.off_peak_demand <= Decimal::ZERO {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let electric_circuit_id = match req_body.electric_circuit_id {
+        Some(reference_id) => match get_unit_electric_circuit(unit_db_id, reference_id, &globs) {
+            Ok(Some(id)) => Some(id),
+            Ok(None) => return HttpResponse::NotFound().body(format!("Circuito elétrico {} não encontrado na unidade {}", reference_id, req_body.unit_id)),
+            Err(err) => {
+                let msg_error = format!("Erro ao obter circuito elétrico {}: {}", reference_id, err);
+                write_to_log_file_thread(&msg_error, 0, "ERROR");
+                println!("{}", msg_error);
+                return HttpResponse::InternalServerError().body(msg_error)
+            }
+        },
+        None => None,
+    };
+
+    let contracted_demand = ContractedDemand {
+        unit_id: unit_db_id,
+        electric_circuit_id,
+        start_date: req_body.start_date,
+        end_date: req_body.end_date,
+        peak_demand: req_body.peak_demand,
+        off_peak_demand: req_body.off_peak_demand,
+        tolerance_percentage: req_body.tolerance_percentage.unwrap_or(Decimal::from(DEFAULT_TOLERANCE_PERCENTAGE)),
+    };
+
+    match insert_contracted_demand(&contracted_demand, &globs) {
+        Ok(id) => HttpResponse::Ok().json(json!({ "id": id })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar demanda contratada, {:?}: {}", contracted_demand, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/contracted-demand/{id}")]
+async fn remove_contracted_demand(req: HttpRequest, id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    match delete_contracted_demand(*id, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Demanda contratada {} não encontrada", id)),
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover demanda contratada {}: {}", id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-overrun-events")]
+async fn get_overrun_events(req_body: web::Json<ReqParamsGetDemandOverruns>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let events = match get_demand_overrun_events(req_body.unit_id, &req_body.start_date, &req_body.end_date, &globs) {
+        Ok(res) => res,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter ultrapassagens de demanda: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    let total_estimated_penalty = events.iter().filter_map(|event| event.estimated_penalty).sum();
+
+    HttpResponse::Ok().json(GetDemandOverrunsResponse {
+        events,
+        total_estimated_penalty,
+    })
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use actix_web::{post, web, HttpResponse, Responder};
+use serde_json::json;
+use crate::db::entities::dynamo_consumed_capacity_hist::get_consumed_capacity;
+use crate::http::structs::dynamo_consumed_capacity::ReqParamsGetConsumedCapacity;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn dynamo_consumed_capacity_routes() -> actix_web::Scope {
+    web::scope("/dynamo_consumed_capacity")
+    .service(get_dynamo_consumed_capacity)
+}
+
+#[post("/get-consumed-capacity")]
+async fn get_dynamo_consumed_capacity(req_body: web::Json<ReqParamsGetConsumedCapacity>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let consumed_capacity = match get_consumed_capacity(&req_body.start_date, &req_body.end_date, req_body.client_ids.clone(), &globs) {
+        Ok(res) => res,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter capacidade consumida do DynamoDB, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    HttpResponse::Ok().json(json!({
+        "consumed_capacity": consumed_capacity,
+    }))
+}
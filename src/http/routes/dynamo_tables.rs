@@ -0,0 +1,134 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use crate::db::entities::dynamo_table_rules::{delete_dynamo_table_rule, get_dynamo_table_rules, upsert_dynamo_table_rule};
+use crate::http::auth::check_admin_token;
+use crate::db::config::table_resolution::DeviceType;
+use crate::http::structs::dynamo_tables::{ReqParamsResolveDeviceTable, ReqParamsSetTableRule};
+use crate::models::database_models::dynamo_table_rules::DynamoTableRule;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn dynamo_tables_routes() -> actix_web::Scope {
+    web::scope("/dynamo_tables")
+    .service(resolve_device_table)
+    .service(get_table_rules)
+    .service(set_table_rule)
+    .service(remove_table_rule)
+}
+
+// Rota de diagnóstico: mostra qual tabela/chaves do DynamoDB seriam usadas para o dispositivo
+#[get("/resolve/{device_code}")]
+async fn resolve_device_table(device_code: web::Path<String>, query: web::Query<ReqParamsResolveDeviceTable>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let device_type = match query.device_type.as_deref().or_else(|| device_code.get(0..3)).and_then(DeviceType::parse) {
+        Some(device_type) => device_type,
+        None => return HttpResponse::BadRequest().body(format!("Tipo de dispositivo inválido, {:?}", query)),
+    };
+
+    match globs.table_resolver.resolve(device_type, &device_code) {
+        Some(table) => HttpResponse::Ok().json(json!({
+            "device_code": device_code.as_str(),
+            "device_type": device_type.as_str(),
+            "table_name": table.table_name,
+            "partition_key": table.partition_key,
+            "sort_key": table.sort_key,
+            "rule": table.rule,
+        })),
+        None => HttpResponse::NotFound().body(format!("Nenhuma regra de tabela encontrada para o dispositivo {}", device_code)),
+    }
+}
+
+#[get("/rules")]
+async fn get_table_rules(req: HttpRequest, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let db_rules = match get_dynamo_table_rules(&globs) {
+        Ok(res) => res,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter regras de tabelas do DynamoDB: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    HttpResponse::Ok().json(json!({
+        "db_rules": db_rules,
+        "config_rules": globs.table_resolver.config_rules(),
+    }))
+}
+
+#[post("/rules")]
+async fn set_table_rule(req: HttpRequest, req_body: web::Json<ReqParamsSetTableRule>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let device_type = match req_body.device_type.as_deref().map(DeviceType::parse) {
+        Some(None) => return HttpResponse::BadRequest().body(format!("Tipo de dispositivo inválido, {:?}", req_body)),
+        Some(Some(device_type)) => Some(device_type.as_str().to_owned()),
+        None => None,
+    };
+
+    if req_body.pattern.is_empty() || req_body.table_template.is_empty() {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let rule = DynamoTableRule {
+        device_type,
+        pattern: req_body.pattern.to_uppercase(),
+        table_template: req_body.table_template.clone(),
+        partition_key: req_body.partition_key.clone().unwrap_or_else(|| "dev_id".to_owned()),
+        sort_key: req_body.sort_key.clone().unwrap_or_else(|| "timestamp".to_owned()),
+        priority: req_body.priority.unwrap_or(0),
+    };
+
+    let id = match upsert_dynamo_table_rule(&rule, &globs) {
+        Ok(id) => id,
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar regra de tabela do DynamoDB, {:?}: {}", rule, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    reload_table_rules(&globs);
+
+    HttpResponse::Ok().json(json!({ "id": id }))
+}
+
+#[delete("/rules/{id}")]
+async fn remove_table_rule(req: HttpRequest, id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let deleted = match delete_dynamo_table_rule(*id, &globs) {
+        Ok(deleted) => deleted,
+        Err(err) => {
+            let msg_error = format!("Erro ao remover regra de tabela do DynamoDB {}: {}", id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    if deleted == 0 {
+        return HttpResponse::NotFound().body(format!("Regra {} não encontrada", id));
+    }
+
+    reload_table_rules(&globs);
+
+    HttpResponse::Ok().json(json!({ "deleted": deleted }))
+}
+
+fn reload_table_rules(globs: &Arc<GlobalVars>) {
+    if let Err(err) = globs.table_resolver.reload(globs) {
+        let msg_error = format!("Erro ao recarregar regras de tabelas do DynamoDB: {}", err);
+        write_to_log_file_thread(&msg_error, 0, "ERROR");
+        println!("{}", msg_error);
+    }
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use rust_decimal::Decimal;
+use serde_json::json;
+use crate::db::entities::electric_circuits::{delete_virtual_electric_circuit, get_unit_electric_circuits, get_unit_measured_circuits, get_virtual_electric_circuit, insert_virtual_electric_circuit, update_electric_circuit_energy_settings, update_virtual_electric_circuit};
+use crate::db::entities::energy_hist::get_unmeasured_load_by_hour;
+use crate::db::entities::meter_discontinuity_events::get_meter_discontinuity_events;
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::electric_circuits::{ReqParamsCreateVirtualCircuit, ReqParamsGetMeterEvents, ReqParamsGetUnmeasuredLoad, ReqParamsSetCircuitEnergySettings, ReqParamsUpdateVirtualCircuit};
+use crate::schedules::energy_gap_fill::GapFillStrategy;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::schedules::virtual_circuits::VirtualCircuitFormula;
+use crate::GlobalVars;
+
+pub fn electric_circuits_routes() -> actix_web::Scope {
+    web::scope("/electric_circuits")
+    .service(set_circuit_energy_settings)
+    .service(get_meter_events)
+    .service(get_circuits_by_unit)
+    .service(get_unmeasured_load)
+    .service(create_virtual_circuit)
+    .service(edit_virtual_circuit)
+    .service(remove_virtual_circuit)
+}
+
+#[post("/energy-settings")]
+async fn set_circuit_energy_settings(req: HttpRequest, req_body: web::Json<ReqParamsSetCircuitEnergySettings>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let gap_fill_strategy = match req_body.gap_fill_strategy.as_deref() {
+        Some(strategy) => match GapFillStrategy::parse(strategy) {
+            Some(strategy) => Some(strategy.as_str().to_owned()),
+            None => return HttpResponse::BadRequest().body(format!("Estratégia de preenchimento inválida: {}", strategy)),
+        },
+        None => None,
+    };
+
+    if req_body.max_hour_consumption.map(|value| value <= Decimal::ZERO).unwrap_or(false) || req_body.register_rollover_value.map(|value| value <= Decimal::ZERO).unwrap_or(false) {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    match update_electric_circuit_energy_settings(req_body.electric_circuit_id, gap_fill_strategy, req_body.max_hour_consumption, req_body.register_rollover_value, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Circuito elétrico {} não encontrado", req_body.electric_circuit_id)),
+        Ok(updated) => HttpResponse::Ok().json(json!({ "updated": updated })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar configuração de consumo do circuito {}: {}", req_body.electric_circuit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-meter-events")]
+async fn get_meter_events(req_body: web::Json<ReqParamsGetMeterEvents>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    match get_meter_discontinuity_events(req_body.unit_id, req_body.electric_circuits_ids.clone().unwrap_or_default(), &req_body.start_date, &req_body.end_date, &globs) {
+        Ok(res) => HttpResponse::Ok().json(res),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter eventos de descontinuidade dos medidores: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[get("/unit/{unit_id}")]
+async fn get_circuits_by_unit(unit_id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    match get_unit_electric_circuits(*unit_id, &globs) {
+        Ok(circuits) => HttpResponse::Ok().json(circuits),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter circuitos elétricos da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-unmeasured-load")]
+async fn get_unmeasured_load(req_body: web::Json<ReqParamsGetUnmeasuredLoad>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let start_date_formatted = format!("{} 00:00:00", req_body.start_date);
+    let end_date_formatted = format!("{} 23:59:59", req_body.end_date);
+
+    match get_unmeasured_load_by_hour(req_body.unit_id, &start_date_formatted, &end_date_formatted, &globs) {
+        Ok(res) => HttpResponse::Ok().json(res),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter consumo não medido da unidade {}: {}", req_body.unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/virtual-circuit")]
+async fn create_virtual_circuit(req: HttpRequest, req_body: web::Json<ReqParamsCreateVirtualCircuit>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    if let Err(response) = validate_virtual_formula(unit_db_id, &req_body.formula, &globs) {
+        return response;
+    }
+
+    match insert_virtual_electric_circuit(unit_db_id, &req_body.name, &req_body.formula, &globs) {
+        Ok(reference_id) => HttpResponse::Ok().json(json!({ "electric_circuit_id": reference_id })),
+        Err(err) => {
+            let msg_error = format!("Erro ao criar circuito virtual, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[put("/virtual-circuit")]
+async fn edit_virtual_circuit(req: HttpRequest, req_body: web::Json<ReqParamsUpdateVirtualCircuit>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let (electric_circuit_id, unit_db_id) = match find_virtual_circuit(req_body.electric_circuit_id, &globs) {
+        Ok(circuit) => circuit,
+        Err(response) => return response,
+    };
+
+    if let Err(response) = validate_virtual_formula(unit_db_id, &req_body.formula, &globs) {
+        return response;
+    }
+
+    match update_virtual_electric_circuit(electric_circuit_id, &req_body.name, &req_body.formula, &globs) {
+        Ok(updated) => HttpResponse::Ok().json(json!({ "updated": updated })),
+        Err(err) => {
+            let msg_error = format!("Erro ao alterar circuito virtual, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/virtual-circuit/{electric_circuit_id}")]
+async fn remove_virtual_circuit(req: HttpRequest, reference_id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let (electric_circuit_id, _) = match find_virtual_circuit(*reference_id, &globs) {
+        Ok(circuit) => circuit,
+        Err(response) => return response,
+    };
+
+    match delete_virtual_electric_circuit(electric_circuit_id, &globs) {
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover circuito virtual {}: {}", reference_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+fn find_virtual_circuit(reference_id: i32, globs: &Arc<GlobalVars>) -> Result<(i32, i32), HttpResponse> {
+    match get_virtual_electric_circuit(reference_id, globs) {
+        Ok(Some(circuit)) => Ok(circuit),
+        Ok(None) => Err(HttpResponse::NotFound().body(format!("Circuito virtual {} não encontrado", reference_id))),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter circuito virtual {}: {}", reference_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            Err(HttpResponse::InternalServerError().body(msg_error))
+        }
+    }
+}
+
+// A fórmula só pode usar circuitos medidos da própria unidade
+fn validate_virtual_formula(unit_id: i32, formula: &str, globs: &Arc<GlobalVars>) -> Result<(), HttpResponse> {
+    let compiled = VirtualCircuitFormula::compile(formula).map_err(|err| HttpResponse::BadRequest().body(err))?;
+
+    let measured_circuits = match get_unit_measured_circuits(unit_id, globs) {
+        Ok(circuits) => circuits,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter circuitos da unidade: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return Err(HttpResponse::InternalServerError().body(msg_error));
+        }
+    };
+
+    match compiled.circuits().into_iter().find(|circuit| !measured_circuits.iter().any(|(_, reference_id)| reference_id == circuit)) {
+        Some(circuit) => Err(HttpResponse::BadRequest().body(format!("Circuito {} da fórmula não é um circuito medido da unidade", circuit))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use chrono::NaiveDate;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde_json::json;
+use crate::db::entities::energy_baselines::{delete_energy_baseline, get_energy_baseline, get_unit_baseline_variables, get_unit_daily_consumption, get_unit_energy_baselines, insert_energy_baseline, upsert_unit_baseline_variables};
+use crate::db::entities::unit_tariffs::{get_unit_hourly_consumption, get_unit_tariffs};
+use crate::energy_billing::tariffs::{calculate_energy_cost, tariff_for_date, CostPeriod};
+use crate::forecasting::energy_baseline::{BaselineModel, BaselineObservation, BaselineSpec, SAVINGS_CONFIDENCE_LEVEL};
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::energy_baselines::{EnergySavingsDay, EnergySavingsResponse, ReqParamsCreateEnergyBaseline, ReqParamsGetEnergySavings, ReqParamsSetBaselineVariables};
+use crate::models::database_models::energy_baselines::{EnergyBaseline, EnergyBaselineRow, UnitBaselineVariables};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+// Limite dos períodos de referência e de relatório, para não carregar histórico demais em uma requisição
+const MAX_PERIOD_DAYS: i64 = 731;
+
+pub fn energy_baselines_routes() -> actix_web::Scope {
+    web::scope("/energy_baselines")
+    .service(set_baseline_variables)
+    .service(get_baselines)
+    .service(create_baseline)
+    .service(remove_baseline)
+    .service(get_energy_savings)
+}
+
+#[post("/unit-variables")]
+async fn set_baseline_variables(req: HttpRequest, req_body: web::Json<ReqParamsSetBaselineVariables>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let invalid_hours = req_body.days.iter()
+        .filter_map(|day| day.operating_hours)
+        .any(|hours| hours < Decimal::ZERO || hours > Decimal::from(24));
+    if req_body.days.is_empty() || invalid_hours {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let variables: Vec<UnitBaselineVariables> = req_body.days.iter().map(|day| UnitBaselineVariables {
+        unit_id: unit_db_id,
+        day: day.day,
+        average_temperature: day.average_temperature,
+        operating_hours: day.operating_hours,
+    }).collect();
+
+    match upsert_unit_baseline_variables(&variables, &globs) {
+        Ok(upserted) => HttpResponse::Ok().json(json!({ "upserted": upserted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar variáveis da linha de base da unidade {}: {}", req_body.unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[get("/unit/{unit_id}")]
+async fn get_baselines(unit_id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let unit_db_id = match find_unit_id(*unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match get_unit_energy_baselines(unit_db_id, &globs) {
+        Ok(baselines) => HttpResponse::Ok().json(baselines),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter linhas de base da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/baseline")]
+async fn create_baseline(req: HttpRequest, req_body: web::Json<ReqParamsCreateEnergyBaseline>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    if req_body.reference_end < req_body.reference_start || (req_body.reference_end - req_body.reference_start).num_days() > MAX_PERIOD_DAYS {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let spec = BaselineSpec {
+        cooling_base_temperature: req_body.cooling_base_temperature.and_then(|temperature| temperature.to_f64()),
+        uses_operating_hours: req_body.uses_operating_hours.unwrap_or(false),
+    };
+
+    let observations = match get_observations(unit_db_id, req_body.reference_start, req_body.reference_end, &globs) {
+        Ok(observations) => observations,
+        Err(response) => return response,
+    };
+
+    let model = match BaselineModel::fit(spec, &observations) {
+        Ok(model) => model,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+
+    let baseline = EnergyBaseline {
+        unit_id: unit_db_id,
+        name: req_body.name.clone(),
+        reference_start: req_body.reference_start,
+        reference_end: req_body.reference_end,
+        cooling_base_temperature: req_body.cooling_base_temperature,
+        uses_operating_hours: spec.uses_operating_hours,
+        intercept: to_decimal(model.intercept, 6),
+        cooling_degree_days_coefficient: model.cooling_degree_days_coefficient.map(|value| to_decimal(value, 6)),
+        operating_hours_coefficient: model.operating_hours_coefficient.map(|value| to_decimal(value, 6)),
+        observations: model.observations as i32,
+        r_squared: to_decimal(model.r_squared, 6),
+        cv_rmse: to_decimal(model.cv_rmse, 6),
+        rmse: to_decimal(model.rmse, 6),
+        residual_autocorrelation: to_decimal(model.residual_autocorrelation, 6),
+    };
+
+    match insert_energy_baseline(&baseline, &globs) {
+        Ok(row) => HttpResponse::Ok().json(row),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar linha de base, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/baseline/{id}")]
+async fn remove_baseline(req: HttpRequest, id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    match delete_energy_baseline(*id, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Linha de base {} não encontrada", id)),
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover linha de base {}: {}", id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-savings")]
+async fn get_energy_savings(req_body: web::Json<ReqParamsGetEnergySavings>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if req_body.end_date < req_body.start_date || (req_body.end_date - req_body.start_date).num_days() > MAX_PERIOD_DAYS {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let baseline = match get_energy_baseline(req_body.baseline_id, &globs) {
+        Ok(Some(baseline)) => baseline,
+        Ok(None) => return HttpResponse::NotFound().body(format!("Linha de base {} não encontrada", req_body.baseline_id)),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter linha de base {}: {}", req_body.baseline_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    let observations = match get_observations(baseline.unit_id, req_body.start_date, req_body.end_date, &globs) {
+        Ok(observations) => observations,
+        Err(response) => return response,
+    };
+
+    let prices = match get_daily_energy_prices(baseline.unit_id, req_body.start_date, req_body.end_date, &globs) {
+        Ok(prices) => prices,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter tarifas da unidade {}: {}", baseline.unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    HttpResponse::Ok().json(calculate_savings(&baseline, &observations, &prices, req_body.start_date, req_body.end_date))
+}
+
+fn get_observations(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<Vec<BaselineObservation>, HttpResponse> {
+    let consumption = get_unit_daily_consumption(unit_id, start_date, end_date, globs);
+    let variables = get_unit_baseline_variables(unit_id, start_date, end_date, globs);
+
+    let (consumption, variables) = match (consumption, variables) {
+        (Ok(consumption), Ok(variables)) => (consumption, variables),
+        (Err(err), _) | (_, Err(err)) => {
+            let msg_error = format!("Erro ao obter dados da linha de base da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return Err(HttpResponse::InternalServerError().body(msg_error));
+        }
+    };
+
+    let variables: HashMap<NaiveDate, _> = variables.into_iter().map(|row| (row.day, row)).collect();
+
+    Ok(consumption.into_iter().map(|day| {
+        let day_variables = variables.get(&day.day);
+        BaselineObservation {
+            day: day.day,
+            // Só entram no modelo os dias com as 24 horas válidas
+            consumption: (day.all_valid && day.hours == 24).then(|| day.consumption.to_f64()).flatten(),
+            average_temperature: day_variables.and_then(|row| row.average_temperature).and_then(|value| value.to_f64()),
+            operating_hours: day_variables.and_then(|row| row.operating_hours).and_then(|value| value.to_f64()),
+        }
+    }).collect())
+}
+
+/// Preço médio da energia em cada dia, pelo custo horário da tarifa vigente sobre o consumo medido.
+/// Dias sem consumo usam o preço fora de ponta.
+fn get_daily_energy_prices(unit_id: i32, start_date: NaiveDate, end_date: NaiveDate, globs: &Arc<GlobalVars>) -> Result<HashMap<NaiveDate, Decimal>, Box<dyn std::error::Error>> {
+    let tariffs = get_unit_tariffs(unit_id, globs)?;
+    let consumption = get_unit_hourly_consumption(unit_id, &start_date.format("%Y-%m-%d").to_string(), &end_date.format("%Y-%m-%d").to_string(), globs)?;
+
+    let mut prices = HashMap::new();
+    for cost in calculate_energy_cost(&tariffs, &consumption, &[], CostPeriod::Day) {
+        let day_consumption = cost.peak_consumption + cost.off_peak_consumption;
+        if cost.hours_without_tariff == 0 && day_consumption > Decimal::ZERO {
+            prices.insert(cost.time, (cost.peak_cost + cost.off_peak_cost) / day_consumption);
+        } else if let Some(tariff) = tariff_for_date(&tariffs, cost.time) {
+            prices.insert(cost.time, tariff.off_peak_energy_price);
+        }
+    }
+
+    Ok(prices)
+}
+
+fn calculate_savings(baseline: &EnergyBaselineRow, observations: &[BaselineObservation], prices: &HashMap<NaiveDate, Decimal>, start_date: NaiveDate, end_date: NaiveDate) -> EnergySavingsResponse {
+    let model = baseline_model(baseline);
+
+    let mut days = Vec::new();
+    for observation in observations {
+        let (Some(actual), Some(adjusted)) = (observation.consumption, model.predict(observation)) else { continue };
+        let actual = to_decimal(actual, 3);
+        let adjusted = to_decimal(adjusted, 3);
+        let avoided = adjusted - actual;
+
+        days.push(EnergySavingsDay {
+            day: observation.day,
+            actual_consumption: actual,
+            adjusted_baseline: adjusted,
+            avoided_energy: avoided,
+            cost_savings: prices.get(&observation.day).map(|price| (avoided * price).round_dp(2)),
+        });
+    }
+
+    let actual_consumption: Decimal = days.iter().map(|day| day.actual_consumption).sum();
+    let adjusted_baseline: Decimal = days.iter().map(|day| day.adjusted_baseline).sum();
+    let avoided_energy = adjusted_baseline - actual_consumption;
+    let avoided_energy_uncertainty = model.savings_uncertainty(days.len()).map(|uncertainty| to_decimal(uncertainty, 3));
+
+    let priced_days: Vec<Decimal> = days.iter().filter_map(|day| prices.get(&day.day).copied()).collect();
+    let average_price = if priced_days.is_empty() { Decimal::ZERO } else { priced_days.iter().sum::<Decimal>() / Decimal::from(priced_days.len()) };
+
+    let total_days = (end_date - start_date).num_days() as i32 + 1;
+
+    EnergySavingsResponse {
+        baseline_id: baseline.id,
+        start_date,
+        end_date,
+        confidence_level: SAVINGS_CONFIDENCE_LEVEL,
+        actual_consumption,
+        adjusted_baseline,
+        avoided_energy,
+        avoided_energy_uncertainty,
+        savings_percentage: (adjusted_baseline > Decimal::ZERO).then(|| (avoided_energy / adjusted_baseline * Decimal::from(100)).round_dp(2)),
+        cost_savings: days.iter().filter_map(|day| day.cost_savings).sum(),
+        cost_savings_uncertainty: avoided_energy_uncertainty.map(|uncertainty| (uncertainty * average_price).round_dp(2)),
+        excluded_days: total_days - days.len() as i32,
+        days_without_tariff: days.len() as i32 - priced_days.len() as i32,
+        days,
+    }
+}
+
+fn baseline_model(baseline: &EnergyBaselineRow) -> BaselineModel {
+    BaselineModel {
+        spec: BaselineSpec {
+            cooling_base_temperature: baseline.cooling_base_temperature.and_then(|value| value.to_f64()),
+            uses_operating_hours: baseline.uses_operating_hours,
+        },
+        intercept: baseline.intercept.to_f64().unwrap_or_default(),
+        cooling_degree_days_coefficient: baseline.cooling_degree_days_coefficient.and_then(|value| value.to_f64()),
+        operating_hours_coefficient: baseline.operating_hours_coefficient.and_then(|value| value.to_f64()),
+        observations: baseline.observations as usize,
+        r_squared: baseline.r_squared.to_f64().unwrap_or_default(),
+        cv_rmse: baseline.cv_rmse.to_f64().unwrap_or_default(),
+        rmse: baseline.rmse.to_f64().unwrap_or_default(),
+        residual_autocorrelation: baseline.residual_autocorrelation.to_f64().unwrap_or_default(),
+    }
+}
+
+fn to_decimal(value: f64, decimals: u32) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default().round_dp(decimals)
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, NaiveTime};
+use serde_json::json;
+use crate::calendar::unit_calendar::unit_non_working_days;
+use crate::db::entities::client_energy_forecast_models::{get_unit_forecast_model, set_client_forecast_model};
+use crate::db::entities::clients::get_client;
+use crate::db::entities::energy_hist::get_unit_circuits_hour_consumption;
+use crate::forecasting::energy_consumption::{backtest_models, BacktestErrors, ForecastModel, FORECAST_HISTORY_WEEKS};
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::energy_forecast::{EnergyForecastBacktestResponse, ReqParamsEnergyForecastBacktest, ReqParamsSetClientForecastModel};
+use crate::models::database_models::client_energy_forecast_models::ClientEnergyForecastModel;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+// Mesmo horizonte da primeira semana gravada em energy_consumption_forecast
+const DEFAULT_BACKTEST_HORIZON_DAYS: i64 = 7;
+// Limite do período de backtest, para não carregar histórico demais em uma requisição
+const MAX_BACKTEST_DAYS: i64 = 93;
+
+pub fn energy_forecast_routes() -> actix_web::Scope {
+    web::scope("/energy_forecast")
+    .service(backtest_energy_forecast)
+    .service(set_energy_forecast_client_model)
+}
+
+#[post("/backtest")]
+async fn backtest_energy_forecast(req_body: web::Json<ReqParamsEnergyForecastBacktest>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let horizon_days = req_body.horizon_days.unwrap_or(DEFAULT_BACKTEST_HORIZON_DAYS);
+    let period_days = (req_body.end_date - req_body.start_date).num_days();
+    if req_body.end_date < req_body.start_date || period_days > MAX_BACKTEST_DAYS || horizon_days < 1 {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let models = match &req_body.models {
+        Some(names) => {
+            let parsed: Option<Vec<ForecastModel>> = names.iter().map(|name| ForecastModel::parse(name)).collect();
+            match parsed {
+                Some(models) if !models.is_empty() => models,
+                _ => return HttpResponse::BadRequest().body(format!("Modelo de previsão inválido, {:?}", names)),
+            }
+        },
+        None => ForecastModel::ALL.to_vec(),
+    };
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let start = req_body.start_date.and_time(NaiveTime::MIN);
+    let end = req_body.end_date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default());
+    let history_start = start - Duration::days(horizon_days) - Duration::weeks(FORECAST_HISTORY_WEEKS);
+
+    let history = match get_unit_circuits_hour_consumption(unit_db_id, &history_start.format("%Y-%m-%d %H:%M:%S").to_string(), &end.format("%Y-%m-%d %H:%M:%S").to_string(), &globs) {
+        Ok(history) => history,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter consumo horário da unidade {}: {}", req_body.unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    let client_model = match get_unit_forecast_model(unit_db_id, &globs) {
+        Ok(model) => model,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter modelo de previsão da unidade {}: {}", req_body.unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+    let current_model = ForecastModel::resolve(client_model.as_deref(), globs.configfile.ENERGY_FORECAST_MODEL.as_deref());
+
+    let holidays = unit_non_working_days(unit_db_id, history_start.date(), end.date(), &globs);
+    let mut errors = BacktestErrors::default();
+    for circuit_history in history.chunk_by(|a, b| a.electric_circuit_id == b.electric_circuit_id) {
+        backtest_models(circuit_history, &holidays, &models, start, end, horizon_days, &mut errors);
+    }
+
+    let models_errors: Vec<_> = models.iter()
+        .map(|model| errors.models.get(model.as_str()).cloned().unwrap_or_default().summary(model.as_str()))
+        .collect();
+
+    // O melhor modelo é escolhido só pelas horas que todos previram, para um modelo com menos previsões não levar vantagem
+    let common_errors: Vec<_> = models.iter()
+        .map(|model| errors.common_hours.get(model.as_str()).cloned().unwrap_or_default().summary(model.as_str()))
+        .collect();
+    let comparison_samples = common_errors.first().map(|error| error.samples).unwrap_or_default();
+
+    let best_model = common_errors.iter()
+        .filter_map(|error| Some((error.mape.or(error.rmse)?, error)))
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, error)| error.model.clone());
+
+    HttpResponse::Ok().json(EnergyForecastBacktestResponse {
+        unit_id: req_body.unit_id,
+        start_date: req_body.start_date,
+        end_date: req_body.end_date,
+        horizon_days,
+        current_model: current_model.as_str().to_owned(),
+        best_model,
+        comparison_samples,
+        models: models_errors,
+    })
+}
+
+#[post("/client-model")]
+async fn set_energy_forecast_client_model(req: HttpRequest, req_body: web::Json<ReqParamsSetClientForecastModel>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let Some(model) = ForecastModel::parse(&req_body.forecast_model) else {
+        return HttpResponse::BadRequest().body(format!("Modelo de previsão inválido, {:?}", req_body));
+    };
+
+    let client_id = match get_client(&req_body.client_name, &globs) {
+        Ok(Some(client)) => client.id.unwrap_or_default(),
+        Ok(None) => return HttpResponse::NotFound().body(format!("Cliente {} não encontrado", req_body.client_name)),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter cliente {}: {}", req_body.client_name, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    match set_client_forecast_model(ClientEnergyForecastModel { client_id, forecast_model: model.as_str().to_owned() }, &globs) {
+        Ok(_) => HttpResponse::Ok().json(json!({ "client_name": req_body.client_name, "forecast_model": model.as_str() })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar modelo de previsão do cliente {}: {}", req_body.client_name, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use actix_web::{post, web, HttpResponse, Responder};
+use rust_decimal::Decimal;
+use crate::db::entities::energy_reactive_hist::get_reactive_energy_by_day;
+use crate::http::structs::energy_reactive::{GetReactiveEnergyResponse, ReactiveEnergyDay, ReqParamsGetReactiveEnergy};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn energy_reactive_routes() -> actix_web::Scope {
+    web::scope("/energy_reactive")
+    .service(get_reactive_energy)
+}
+
+#[post("/get-reactive-energy")]
+async fn get_reactive_energy(req_body: web::Json<ReqParamsGetReactiveEnergy>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let electric_circuits_ids = req_body.electric_circuits_ids.clone().unwrap_or_default();
+
+    let reactive_hist = match get_reactive_energy_by_day(req_body.unit_id, electric_circuits_ids, &req_body.start_date, &req_body.end_date, &globs) {
+        Ok(res) => res,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter dados do histórico de energia reativa: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    let mut response = GetReactiveEnergyResponse {
+        days: Vec::new(),
+        total_active_consumption: Decimal::ZERO,
+        total_reactive_consumption: Decimal::ZERO,
+        total_ufer: Decimal::ZERO,
+        total_estimated_cost: None,
+    };
+
+    for row in reactive_hist {
+        let tariff = req_body.tariff_kvarh.or(row.tarifa_kwh);
+        let estimated_cost = tariff.map(|tariff| (row.ufer * tariff).round_dp(2));
+
+        response.total_active_consumption += row.active_consumption;
+        response.total_reactive_consumption += row.reactive_consumption;
+        response.total_ufer += row.ufer;
+        if let Some(cost) = estimated_cost {
+            response.total_estimated_cost = Some(response.total_estimated_cost.unwrap_or_default() + cost);
+        }
+
+        response.days.push(ReactiveEnergyDay {
+            electric_circuit_reference_id: row.electric_circuit_reference_id,
+            record_date: row.record_date,
+            active_consumption: row.active_consumption,
+            reactive_consumption: row.reactive_consumption,
+            average_power_factor: row.average_power_factor,
+            min_power_factor: row.min_power_factor,
+            ufer: row.ufer,
+            estimated_cost,
+        });
+    }
+
+    HttpResponse::Ok().json(response)
+}
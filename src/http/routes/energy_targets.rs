@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
+use serde_json::json;
+use crate::db::entities::energy_monthly_consumption_target::{delete_manual_monthly_target, get_unit_monthly_targets, insert_manual_monthly_target, update_manual_monthly_target};
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::energy_targets::{ReqParamsDeleteMonthlyTarget, ReqParamsGetMonthlyTargets, ReqParamsSetMonthlyTarget};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn energy_targets_routes() -> actix_web::Scope {
+    web::scope("/energy_targets")
+    .service(get_monthly_targets)
+    .service(create_monthly_target)
+    .service(update_monthly_target)
+    .service(remove_monthly_target)
+}
+
+#[get("/unit/{unit_id}")]
+async fn get_monthly_targets(unit_id: web::Path<i32>, query: web::Query<ReqParamsGetMonthlyTargets>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let unit_db_id = match find_unit_id(*unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match get_unit_monthly_targets(unit_db_id, query.start_month.map(first_day_of_month), query.end_month.map(first_day_of_month), &globs) {
+        Ok(targets) => HttpResponse::Ok().json(targets),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter metas mensais da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/monthly-target")]
+async fn create_monthly_target(req: HttpRequest, req_body: web::Json<ReqParamsSetMonthlyTarget>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    if req_body.consumption_target <= Decimal::ZERO {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match insert_manual_monthly_target(unit_db_id, first_day_of_month(req_body.month), req_body.consumption_target, &globs) {
+        Ok(0) => HttpResponse::Conflict().body(format!("Unidade {} já possui meta manual no mês {}", req_body.unit_id, req_body.month.format("%Y-%m"))),
+        Ok(_) => HttpResponse::Ok().json(json!({ "unit_id": req_body.unit_id, "month": req_body.month.format("%Y-%m").to_string(), "consumption_target": req_body.consumption_target })),
+        Err(err) => {
+            let msg_error = format!("Erro ao criar meta mensal, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[put("/monthly-target")]
+async fn update_monthly_target(req: HttpRequest, req_body: web::Json<ReqParamsSetMonthlyTarget>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    if req_body.consumption_target <= Decimal::ZERO {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match update_manual_monthly_target(unit_db_id, first_day_of_month(req_body.month), req_body.consumption_target, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Unidade {} sem meta manual no mês {}", req_body.unit_id, req_body.month.format("%Y-%m"))),
+        Ok(_) => HttpResponse::Ok().json(json!({ "unit_id": req_body.unit_id, "month": req_body.month.format("%Y-%m").to_string(), "consumption_target": req_body.consumption_target })),
+        Err(err) => {
+            let msg_error = format!("Erro ao alterar meta mensal, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/monthly-target")]
+async fn remove_monthly_target(req: HttpRequest, req_body: web::Json<ReqParamsDeleteMonthlyTarget>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match delete_manual_monthly_target(unit_db_id, first_day_of_month(req_body.month), &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Unidade {} sem meta manual no mês {}", req_body.unit_id, req_body.month.format("%Y-%m"))),
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover meta mensal, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+// As metas são gravadas no primeiro dia do mês, como as automáticas
+fn first_day_of_month(date: NaiveDate) -> NaiveDateTime {
+    date.with_day(1).unwrap_or(date).and_time(NaiveTime::MIN)
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use chrono::{Datelike, Months, NaiveDate, NaiveTime};
+use serde_json::json;
+use crate::db::entities::units::get_unit;
+use crate::db::entities::unit_tariffs::{delete_unit_tariff, get_unit_demand_by_15_minutes, get_unit_hourly_consumption, get_unit_tariffs, upsert_unit_tariff};
+use crate::energy_billing::tariffs::{calculate_energy_cost, CostPeriod, TariffModality};
+use crate::http::auth::check_admin_token;
+use crate::http::structs::energy_tariffs::{ReqParamsGetEnergyCost, ReqParamsSetUnitTariff};
+use crate::models::database_models::unit_tariffs::UnitTariff;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn energy_tariffs_routes() -> actix_web::Scope {
+    web::scope("/energy_tariffs")
+    .service(get_tariffs)
+    .service(set_tariff)
+    .service(remove_tariff)
+    .service(get_energy_cost_by_day)
+    .service(get_energy_cost_by_month)
+}
+
+#[get("/unit/{unit_id}")]
+async fn get_tariffs(unit_id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let unit_db_id = match find_unit_id(*unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match get_unit_tariffs(unit_db_id, &globs) {
+        Ok(tariffs) => HttpResponse::Ok().json(tariffs),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter tarifas da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/unit-tariff")]
+async fn set_tariff(req: HttpRequest, req_body: web::Json<ReqParamsSetUnitTariff>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let modality = match TariffModality::parse(&req_body.modality) {
+        Some(modality) => modality,
+        None => return HttpResponse::BadRequest().body(format!("Modalidade tarifária inválida: {}", req_body.modality)),
+    };
+
+    if req_body.end_date.map(|end| end < req_body.start_date).unwrap_or(false) {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let tariff = UnitTariff {
+        unit_id: unit_db_id,
+        modality: modality.as_str().to_owned(),
+        start_date: req_body.start_date,
+        end_date: req_body.end_date,
+        peak_start_time: req_body.peak_start_time.unwrap_or_else(|| NaiveTime::from_hms_opt(18, 0, 0).unwrap()),
+        peak_end_time: req_body.peak_end_time.unwrap_or_else(|| NaiveTime::from_hms_opt(21, 0, 0).unwrap()),
+        peak_weekdays_only: req_body.peak_weekdays_only.unwrap_or(true),
+        peak_energy_price: req_body.peak_energy_price,
+        off_peak_energy_price: req_body.off_peak_energy_price,
+        peak_demand_price: req_body.peak_demand_price,
+        off_peak_demand_price: req_body.off_peak_demand_price,
+    };
+
+    match upsert_unit_tariff(&tariff, &globs) {
+        Ok(id) => HttpResponse::Ok().json(json!({ "id": id })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar tarifa da unidade, {:?}: {}", tariff, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[delete("/unit-tariff/{id}")]
+async fn remove_tariff(req: HttpRequest, id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    match delete_unit_tariff(*id, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Tarifa {} não encontrada", id)),
+        Ok(deleted) => HttpResponse::Ok().json(json!({ "deleted": deleted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao remover tarifa {}: {}", id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-energy-cost-by-day")]
+async fn get_energy_cost_by_day(req_body: web::Json<ReqParamsGetEnergyCost>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if req_body.end_date < req_body.start_date {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    energy_cost_response(&req_body, req_body.start_date, req_body.end_date, CostPeriod::Day, &globs)
+}
+
+#[post("/get-energy-cost-by-month")]
+async fn get_energy_cost_by_month(req_body: web::Json<ReqParamsGetEnergyCost>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if req_body.end_date < req_body.start_date {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    // A demanda é faturada pelo mês inteiro: o período é estendido até o início e o fim dos meses
+    let start_date = req_body.start_date.with_day(1).unwrap();
+    let end_date = req_body.end_date.with_day(1).unwrap() + Months::new(1) - chrono::Duration::days(1);
+
+    energy_cost_response(&req_body, start_date, end_date, CostPeriod::Month, &globs)
+}
+
+fn energy_cost_response(req_body: &ReqParamsGetEnergyCost, start_date: NaiveDate, end_date: NaiveDate, period: CostPeriod, globs: &Arc<GlobalVars>) -> HttpResponse {
+    let unit_db_id = match find_unit_id(req_body.unit_id, globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let start_date = start_date.format("%Y-%m-%d").to_string();
+    let end_date = end_date.format("%Y-%m-%d").to_string();
+
+    let result = get_unit_tariffs(unit_db_id, globs).and_then(|tariffs| {
+        let consumption = get_unit_hourly_consumption(unit_db_id, &start_date, &end_date, globs)?;
+        let demand = match period {
+            CostPeriod::Month => get_unit_demand_by_15_minutes(unit_db_id, &start_date, &end_date, globs)?,
+            CostPeriod::Day => Vec::new(),
+        };
+        Ok((tariffs, consumption, demand))
+    });
+
+    let (tariffs, consumption, demand) = match result {
+        Ok(res) => res,
+        Err(err) => {
+            let msg_error = format!("Erro ao obter dados para o custo de energia da unidade {}: {}", req_body.unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            return HttpResponse::InternalServerError().body(msg_error)
+        }
+    };
+
+    if tariffs.is_empty() {
+        return HttpResponse::NotFound().body(format!("Unidade {} sem tarifa cadastrada", req_body.unit_id));
+    }
+
+    HttpResponse::Ok().json(calculate_energy_cost(&tariffs, &consumption, &demand, period))
+}
+
+pub fn find_unit_id(reference_id: i32, globs: &Arc<GlobalVars>) -> Result<i32, HttpResponse> {
+    match get_unit(reference_id, globs) {
+        Ok(Some(unit)) => unit.id.ok_or_else(|| HttpResponse::NotFound().body(format!("Unidade {} não encontrada", reference_id))),
+        Ok(None) => Err(HttpResponse::NotFound().body(format!("Unidade {} não encontrada", reference_id))),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter unidade {}: {}", reference_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            Err(HttpResponse::InternalServerError().body(msg_error))
+        }
+    }
+}
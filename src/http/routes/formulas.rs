@@ -0,0 +1,62 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use serde_json::Value;
+use crate::http::structs::formulas::{FormulaSampleResult, FormulaValidation, ReqParamsValidateFormulas, ValidateFormulasResponse};
+use crate::telemetry_payloads::formulas::{FormulaError, FormulaSet};
+
+pub fn formulas_routes() -> actix_web::Scope {
+    web::scope("/formulas")
+    .service(validate_formulas)
+}
+
+// Valida as fórmulas de um dispositivo antes de serem salvas no API Server, calculando-as sobre telemetrias de exemplo
+#[post("/validate")]
+async fn validate_formulas(req_body: web::Json<ReqParamsValidateFormulas>) -> impl Responder {
+    let (formula_set, compile_errors) = FormulaSet::compile_all(&req_body.formulas);
+    let telemetries = req_body.telemetries.clone().unwrap_or_default();
+    let is_ieee754_fp = req_body.is_ieee754_fp.unwrap_or(false);
+
+    let mut errors: Vec<String> = compile_errors.iter().map(|err| err.to_string()).collect();
+    let mut formulas = Vec::new();
+
+    let mut params: Vec<&String> = req_body.formulas.keys().collect();
+    params.sort();
+
+    for param in params {
+        let compiled = match formula_set.get(param) {
+            Some(compiled) => compiled,
+            None => continue,
+        };
+
+        let mut samples = Vec::with_capacity(telemetries.len());
+        for tel in &telemetries {
+            let input = tel.get(param).and_then(Value::as_f64);
+            let result = match input {
+                Some(input) => formula_set.evaluate(param, input, tel, is_ieee754_fp),
+                None => Err(FormulaError::MissingInput { param: param.clone(), variable: param.clone() }),
+            };
+
+            let sample = match result {
+                Ok(value) => FormulaSampleResult { input, value: Some(value), missing_input: None, error: None },
+                Err(FormulaError::MissingInput { variable, .. }) => FormulaSampleResult { input, value: None, missing_input: Some(variable), error: None },
+                Err(err) => {
+                    errors.push(err.to_string());
+                    FormulaSampleResult { input, value: None, missing_input: None, error: Some(err.to_string()) }
+                },
+            };
+            samples.push(sample);
+        }
+
+        formulas.push(FormulaValidation {
+            param: param.clone(),
+            formula: compiled.formula.clone(),
+            variables: compiled.variables().to_vec(),
+            samples,
+        });
+    }
+
+    HttpResponse::Ok().json(ValidateFormulasResponse {
+        valid: errors.is_empty(),
+        errors,
+        formulas,
+    })
+}
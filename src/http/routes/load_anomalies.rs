@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use crate::db::entities::load_anomalies::{get_load_anomalies, get_unit_daily_load_profiles, get_unit_operating_schedule, replace_unit_operating_schedule};
+use crate::http::auth::check_admin_token;
+use crate::http::routes::energy_tariffs::find_unit_id;
+use crate::http::structs::load_anomalies::{GetLoadAnomaliesResponse, ReqParamsGetLoadAnomalies, ReqParamsSetOperatingSchedule};
+use crate::models::database_models::load_anomalies::UnitOperatingSchedule;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn load_anomalies_routes() -> actix_web::Scope {
+    web::scope("/load_anomalies")
+    .service(get_operating_schedule)
+    .service(set_operating_schedule)
+    .service(get_unit_load_anomalies)
+}
+
+#[get("/unit/{unit_id}/operating-schedule")]
+async fn get_operating_schedule(unit_id: web::Path<i32>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let unit_db_id = match find_unit_id(*unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match get_unit_operating_schedule(unit_db_id, &globs) {
+        Ok(schedule) => HttpResponse::Ok().json(schedule),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter horário de funcionamento da unidade {}: {}", unit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[put("/operating-schedule")]
+async fn set_operating_schedule(req: HttpRequest, req_body: web::Json<ReqParamsSetOperatingSchedule>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    let mut weekdays = HashSet::new();
+    if req_body.days.iter().any(|day| !(1..=7).contains(&day.weekday) || !weekdays.insert(day.weekday)) {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let days: Vec<UnitOperatingSchedule> = req_body.days.iter().map(|day| UnitOperatingSchedule {
+        unit_id: unit_db_id,
+        weekday: day.weekday,
+        start_time: day.start_time,
+        end_time: day.end_time,
+    }).collect();
+
+    match replace_unit_operating_schedule(unit_db_id, &days, &globs) {
+        Ok(inserted) => HttpResponse::Ok().json(json!({ "inserted": inserted })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar horário de funcionamento, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-anomalies")]
+async fn get_unit_load_anomalies(req_body: web::Json<ReqParamsGetLoadAnomalies>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if req_body.end_date < req_body.start_date {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    let unit_db_id = match find_unit_id(req_body.unit_id, &globs) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let anomalies = get_load_anomalies(unit_db_id, req_body.start_date, req_body.end_date, &globs);
+    let load_profiles = get_unit_daily_load_profiles(unit_db_id, req_body.start_date, req_body.end_date, &globs);
+
+    match (anomalies, load_profiles) {
+        (Ok(anomalies), Ok(load_profiles)) => HttpResponse::Ok().json(GetLoadAnomaliesResponse { anomalies, load_profiles }),
+        (Err(err), _) | (_, Err(err)) => {
+            let msg_error = format!("Erro ao obter anomalias de consumo, {:?}: {}", req_body, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use actix_web::{post, put, web, HttpRequest, HttpResponse, Responder};
+use rust_decimal::Decimal;
+use serde_json::json;
+use crate::db::entities::electric_circuits::update_electric_circuit_nominal_voltage;
+use crate::db::entities::power_quality::{get_power_quality_events, get_power_quality_stats};
+use crate::http::auth::check_admin_token;
+use crate::http::structs::power_quality::{ReqParamsGetPowerQuality, ReqParamsSetNominalVoltage};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub fn power_quality_routes() -> actix_web::Scope {
+    web::scope("/power_quality")
+    .service(get_power_quality_stats_by_unit)
+    .service(get_power_quality_events_by_unit)
+    .service(set_circuit_nominal_voltage)
+}
+
+#[put("/nominal-voltage")]
+async fn set_circuit_nominal_voltage(req: HttpRequest, req_body: web::Json<ReqParamsSetNominalVoltage>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    if let Err(response) = check_admin_token(&req, &globs) {
+        return response;
+    }
+
+    if req_body.nominal_voltage.map(|value| value <= Decimal::ZERO).unwrap_or(false) {
+        return HttpResponse::BadRequest().body(format!("Parâmetros incorretos, {:?}", req_body));
+    }
+
+    match update_electric_circuit_nominal_voltage(req_body.electric_circuit_id, req_body.nominal_voltage, &globs) {
+        Ok(0) => HttpResponse::NotFound().body(format!("Circuito elétrico {} não encontrado", req_body.electric_circuit_id)),
+        Ok(updated) => HttpResponse::Ok().json(json!({ "updated": updated })),
+        Err(err) => {
+            let msg_error = format!("Erro ao salvar tensão nominal do circuito {}: {}", req_body.electric_circuit_id, err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-power-quality-stats")]
+async fn get_power_quality_stats_by_unit(req_body: web::Json<ReqParamsGetPowerQuality>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let start_date_formatted = format!("{} 00:00:00", req_body.start_date);
+    let end_date_formatted = format!("{} 23:59:59", req_body.end_date);
+
+    match get_power_quality_stats(req_body.unit_id, req_body.electric_circuits_ids.clone().unwrap_or_default(), &start_date_formatted, &end_date_formatted, &globs) {
+        Ok(res) => HttpResponse::Ok().json(res),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter estatísticas de qualidade de energia: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
+
+#[post("/get-power-quality-events")]
+async fn get_power_quality_events_by_unit(req_body: web::Json<ReqParamsGetPowerQuality>, globs: web::Data<Arc<GlobalVars>>) -> impl Responder {
+    let start_date_formatted = format!("{} 00:00:00", req_body.start_date);
+    let end_date_formatted = format!("{} 23:59:59", req_body.end_date);
+
+    match get_power_quality_events(req_body.unit_id, req_body.electric_circuits_ids.clone().unwrap_or_default(), &start_date_formatted, &end_date_formatted, &globs) {
+        Ok(res) => HttpResponse::Ok().json(res),
+        Err(err) => {
+            let msg_error = format!("Erro ao obter eventos de qualidade de energia: {}", err);
+            write_to_log_file_thread(&msg_error, 0, "ERROR");
+            println!("{}", msg_error);
+            HttpResponse::InternalServerError().body(msg_error)
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::{str::FromStr, sync::Arc};
 use actix_web::{post, web, HttpResponse, Responder};
 use chrono::{Duration, NaiveDate};
 
-use crate::{http::structs::script_days::ReqParamsScriptDays, schedules::scheduler::run_nightly_tasks, GlobalVars};
+use crate::{external_api::config_snapshots::ConfigSource, http::structs::script_days::ReqParamsScriptDays, schedules::scheduler::run_nightly_tasks, GlobalVars};
 
 pub fn scrip_days_route() -> actix_web::Scope {
     web::scope("/script_days")
@@ -31,9 +31,11 @@ async fn compile_days(req_body: web::Json<ReqParamsScriptDays>, globs: web::Data
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "all", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "all", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
@@ -54,9 +56,11 @@ async fn compile_days_energy(req_body: web::Json<ReqParamsScriptDays>, globs: we
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "energy", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "energy", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
@@ -77,9 +81,11 @@ async fn compile_days_chiller(req_body: web::Json<ReqParamsScriptDays>, globs: w
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "chiller", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "chiller", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
@@ -100,9 +106,11 @@ async fn compile_days_water(req_body: web::Json<ReqParamsScriptDays>, globs: web
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "water", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "water", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
@@ -123,9 +131,11 @@ async fn compile_days_energy_efficiency(req_body: web::Json<ReqParamsScriptDays>
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "energy_efficiency", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "energy_efficiency", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
@@ -146,9 +156,11 @@ async fn compile_days_energy_demand(req_body: web::Json<ReqParamsScriptDays>, gl
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "energy_demand", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "energy_demand", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
@@ -169,9 +181,11 @@ async fn compile_days_on_outside_programming(req_body: web::Json<ReqParamsScript
         date_array.push(date.to_string());
     }
     
+    let config_source = ConfigSource::from_stored_snapshots_flag(req_body.use_stored_snapshots);
+
     tokio::spawn(async move {
         for day in date_array {
-            run_nightly_tasks(&globs, &day, None, "process_unit_on_outside_programming", req_body.client_ids.clone(), req_body.unit_ids.clone()).await;
+            run_nightly_tasks(&globs, &day, None, "process_unit_on_outside_programming", req_body.client_ids.clone(), req_body.unit_ids.clone(), config_source).await;
         }
     });
 
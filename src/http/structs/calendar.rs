@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetCalendarHolidays {
+    pub scope: Option<String>,
+    pub state_name: Option<String>,
+    pub city_name: Option<String>,
+    pub client_name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetCalendarHoliday {
+    pub name: String,
+    /* NATIONAL, STATE, CITY ou CLIENT */
+    pub scope: String,
+    pub start_date: NaiveDate,
+    /* Último dia de um período sem expediente; vazio para um único dia */
+    pub end_date: Option<NaiveDate>,
+    /* Repete todo ano no mesmo dia e mês */
+    pub recurring: Option<bool>,
+    /* Obrigatório para STATE e opcional para CITY, comparado com units.state_name */
+    pub state_name: Option<String>,
+    /* Obrigatório para CITY, comparado com units.city_name */
+    pub city_name: Option<String>,
+    /* Obrigatório para CLIENT */
+    pub client_name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetNonWorkingDays {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NonWorkingDay {
+    pub date: NaiveDate,
+    pub name: String,
+    pub scope: String,
+}
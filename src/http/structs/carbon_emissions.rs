@@ -0,0 +1,66 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Date, Integer, Nullable, Numeric, Text}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetEmissionFactors {
+    pub grid: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetEmissionFactor {
+    /* Padrão: EMISSION_FACTOR_GRID do configfile */
+    pub grid: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    /* tCO2e/MWh */
+    pub emission_factor: Decimal,
+    pub source: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EmissionsPeriod {
+    Day,
+    Month,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EmissionsGroupBy {
+    Unit,
+    Client,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetEmissions {
+    /* Ids de referência das unidades; vazio considera todas */
+    pub units: Vec<i32>,
+    pub client_name: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub period: EmissionsPeriod,
+    pub group_by: EmissionsGroupBy,
+}
+
+#[derive(QueryableByName, Serialize, Debug, Clone)]
+pub struct EmissionsSummary {
+    #[diesel(sql_type = Date)]
+    pub time: NaiveDate,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub unit_id: Option<i32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub unit_name: Option<String>,
+    #[diesel(sql_type = Text)]
+    pub client_name: String,
+    /* kWh */
+    #[diesel(sql_type = Numeric)]
+    pub consumption: Decimal,
+    /* tCO2e */
+    #[diesel(sql_type = Numeric)]
+    pub emissions: Decimal,
+    /* Consumo sem fator de emissão vigente, que ficou fora das emissões */
+    #[diesel(sql_type = Numeric)]
+    pub consumption_without_factor: Decimal,
+}
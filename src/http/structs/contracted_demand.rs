@@ -0,0 +1,53 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Date, Integer, Nullable, Numeric, Text, Timestamp}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetContractedDemand {
+    pub unit_id: i32,
+    /* Sem circuito, a demanda contratada vale para a soma dos circuitos da unidade */
+    pub electric_circuit_id: Option<i32>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    /* Demanda de ponta (kW), usada apenas na modalidade azul */
+    pub peak_demand: Option<Decimal>,
+    pub off_peak_demand: Decimal,
+    /* Tolerância de ultrapassagem em %, padrão 5% */
+    pub tolerance_percentage: Option<Decimal>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetDemandOverruns {
+    pub unit_id: i32,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetDemandOverrunEventResponse {
+    #[diesel(sql_type = Date)]
+    pub event_date: NaiveDate,
+    #[diesel(sql_type = Text)]
+    pub tariff_period: String,
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub electric_circuit_reference_id: Option<i32>,
+    #[diesel(sql_type = Numeric)]
+    pub contracted_demand: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub tolerance_limit: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub measured_demand: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub overrun_demand: Decimal,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub estimated_penalty: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GetDemandOverrunsResponse {
+    pub events: Vec<GetDemandOverrunEventResponse>,
+    pub total_estimated_penalty: Decimal,
+}
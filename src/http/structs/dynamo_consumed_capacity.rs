@@ -0,0 +1,29 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{BigInt, Date, Integer, Numeric, Text}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetConsumedCapacity {
+    pub start_date: String,
+    pub end_date: String,
+    pub client_ids: Option<Vec<i32>>,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetConsumedCapacityResponse {
+    #[diesel(sql_type = Date)]
+    pub record_date: NaiveDate,
+    #[diesel(sql_type = Integer)]
+    pub client_reference_id: i32,
+    #[diesel(sql_type = Text)]
+    pub pipeline: String,
+    #[diesel(sql_type = Text)]
+    pub table_name: String,
+    #[diesel(sql_type = Numeric)]
+    pub consumed_rcu: Decimal,
+    #[diesel(sql_type = BigInt)]
+    pub cache_hits: i64,
+    #[diesel(sql_type = Numeric)]
+    pub cache_hit_rcu: Decimal,
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetTableRule {
+    /* Restringe a regra a um tipo de dispositivo (DAC, DUT, DRI, DMA, DMT, DAL, DAM); sem ele vale para todos */
+    pub device_type: Option<String>,
+    pub pattern: String,
+    pub table_template: String,
+    pub partition_key: Option<String>,
+    pub sort_key: Option<String>,
+    /* Regras de maior prioridade são avaliadas primeiro (padrão 0) */
+    pub priority: Option<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsResolveDeviceTable {
+    /* Sem ele, o tipo é deduzido dos 3 primeiros caracteres do código */
+    pub device_type: Option<String>,
+}
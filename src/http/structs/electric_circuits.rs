@@ -0,0 +1,93 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Bool, Integer, Nullable, Numeric, Text, Timestamp}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetCircuitEnergySettings {
+    pub electric_circuit_id: i32,
+    /* EVEN_SPLIT, PROFILE_WEIGHTED ou LEAVE_EMPTY. Se vazio, usa o padrão do configfile */
+    pub gap_fill_strategy: Option<String>,
+    /* Consumo máximo plausível por hora (kWh). Se vazio, usa 1000 kWh */
+    pub max_hour_consumption: Option<Decimal>,
+    /* Valor em que o registrador de energia do medidor volta a zero (kWh). Se vazio, viradas não são identificadas */
+    pub register_rollover_value: Option<Decimal>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetMeterEvents {
+    pub unit_id: i32,
+    pub electric_circuits_ids: Option<Vec<i32>>,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetMeterDiscontinuityEventResponse {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_reference_id: i32,
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Text)]
+    pub event_type: String,
+    #[diesel(sql_type = Numeric)]
+    pub previous_value: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub new_value: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub applied_offset: Decimal,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub estimated_consumption: Option<Decimal>,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetUnitElectricCircuitResponse {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_reference_id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub parent_electric_circuit_reference_id: Option<i32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub virtual_formula: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetUnmeasuredLoad {
+    pub unit_id: i32,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetUnmeasuredLoadResponse {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_reference_id: i32,
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Numeric)]
+    pub parent_consumption: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub children_consumption: Decimal,
+    /* Consumo do circuito pai não medido pelos filhos (demais cargas). Negativo indica erro de medição ou de hierarquia */
+    #[diesel(sql_type = Numeric)]
+    pub unmeasured_consumption: Decimal,
+    /* Falso quando o pai ou algum filho não tem consumo válido na hora */
+    #[diesel(sql_type = Bool)]
+    pub is_complete: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsCreateVirtualCircuit {
+    pub unit_id: i32,
+    pub name: String,
+    /* Expressão sobre os circuitos medidos da unidade, ex.: "C1 + C2 - C7" (C<id do circuito>) */
+    pub formula: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsUpdateVirtualCircuit {
+    pub electric_circuit_id: i32,
+    pub name: String,
+    pub formula: String,
+}
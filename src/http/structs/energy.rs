@@ -370,6 +370,9 @@ pub struct GetProcelInsigthsResponse {
     pub averageConsumptionPreviousMonthPercentage: Decimal,
     pub totalConsumption: Decimal,
     pub totalCharged: Decimal,
+    /* tCO2e pelos fatores de emissão vigentes no período */
+    #[serde(rename = "totalEmissions")]
+    pub total_emissions: Decimal,
     pub containsProcel: bool,
     pub containsAnalysisData: bool,
 
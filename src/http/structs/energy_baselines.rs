@@ -0,0 +1,80 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{BigInt, Bool, Date, Numeric}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetBaselineVariables {
+    pub unit_id: i32,
+    pub days: Vec<BaselineVariablesDay>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BaselineVariablesDay {
+    pub day: NaiveDate,
+    /* Temperatura externa média do dia, em °C */
+    pub average_temperature: Option<Decimal>,
+    /* Horas de funcionamento da unidade no dia */
+    pub operating_hours: Option<Decimal>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsCreateEnergyBaseline {
+    pub unit_id: i32,
+    pub name: String,
+    pub reference_start: NaiveDate,
+    pub reference_end: NaiveDate,
+    /* Temperatura base dos graus-dia de resfriamento; sem ela, os graus-dia não entram no modelo */
+    pub cooling_base_temperature: Option<Decimal>,
+    pub uses_operating_hours: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetEnergySavings {
+    pub baseline_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+pub struct UnitDayConsumption {
+    #[diesel(sql_type = Date)]
+    pub day: NaiveDate,
+    #[diesel(sql_type = Numeric)]
+    pub consumption: Decimal,
+    #[diesel(sql_type = BigInt)]
+    pub hours: i64,
+    #[diesel(sql_type = Bool)]
+    pub all_valid: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EnergySavingsDay {
+    pub day: NaiveDate,
+    pub actual_consumption: Decimal,
+    pub adjusted_baseline: Decimal,
+    pub avoided_energy: Decimal,
+    pub cost_savings: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EnergySavingsResponse {
+    pub baseline_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /* Nível de confiança das incertezas, em % */
+    pub confidence_level: i32,
+    pub actual_consumption: Decimal,
+    pub adjusted_baseline: Decimal,
+    pub avoided_energy: Decimal,
+    /* None quando a autocorrelação dos resíduos não deixa graus de liberdade para a incerteza */
+    pub avoided_energy_uncertainty: Option<Decimal>,
+    pub savings_percentage: Option<Decimal>,
+    pub cost_savings: Decimal,
+    pub cost_savings_uncertainty: Option<Decimal>,
+    /* Dias sem consumo completo ou sem as variáveis do modelo, que ficaram fora do relatório */
+    pub excluded_days: i32,
+    /* Dias do relatório sem tarifa vigente, que ficaram fora da economia em custo */
+    pub days_without_tariff: i32,
+    pub days: Vec<EnergySavingsDay>,
+}
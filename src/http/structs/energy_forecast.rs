@@ -0,0 +1,51 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Integer, Numeric, Timestamp}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsEnergyForecastBacktest {
+    pub unit_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub models: Option<Vec<String>>,
+    pub horizon_days: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetClientForecastModel {
+    pub client_name: String,
+    pub forecast_model: String,
+}
+
+#[derive(QueryableByName, Serialize, Clone, Debug)]
+pub struct CircuitHourConsumption {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_id: i32,
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Numeric)]
+    pub consumption: Decimal,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ForecastModelError {
+    pub model: String,
+    pub samples: i64,
+    pub missing_forecasts: i64,
+    pub mape: Option<f64>,
+    pub rmse: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EnergyForecastBacktestResponse {
+    pub unit_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub horizon_days: i64,
+    pub current_model: String,
+    pub best_model: Option<String>,
+    /* Horas previstas por todos os modelos, as únicas usadas para escolher best_model */
+    pub comparison_samples: i64,
+    pub models: Vec<ForecastModelError>,
+}
@@ -0,0 +1,55 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Date, Integer, Nullable, Numeric}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetReactiveEnergy {
+    pub unit_id: i32,
+    pub start_date: String,
+    pub end_date: String,
+    pub electric_circuits_ids: Option<Vec<i32>>,
+    /* Tarifa do kvarh excedente; sem ela é usada a tarifa do kWh da unidade */
+    pub tariff_kvarh: Option<Decimal>,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetReactiveEnergyDayResponse {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_reference_id: i32,
+    #[diesel(sql_type = Date)]
+    pub record_date: NaiveDate,
+    #[diesel(sql_type = Numeric)]
+    pub active_consumption: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub reactive_consumption: Decimal,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub average_power_factor: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub min_power_factor: Option<Decimal>,
+    #[diesel(sql_type = Numeric)]
+    pub ufer: Decimal,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub tarifa_kwh: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReactiveEnergyDay {
+    pub electric_circuit_reference_id: i32,
+    pub record_date: NaiveDate,
+    pub active_consumption: Decimal,
+    pub reactive_consumption: Decimal,
+    pub average_power_factor: Option<Decimal>,
+    pub min_power_factor: Option<Decimal>,
+    pub ufer: Decimal,
+    pub estimated_cost: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GetReactiveEnergyResponse {
+    pub days: Vec<ReactiveEnergyDay>,
+    pub total_active_consumption: Decimal,
+    pub total_reactive_consumption: Decimal,
+    pub total_ufer: Decimal,
+    pub total_estimated_cost: Option<Decimal>,
+}
@@ -0,0 +1,37 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Bool, Numeric, Timestamp}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetMonthlyTarget {
+    pub unit_id: i32,
+    /* Qualquer dia do mês da meta */
+    pub month: NaiveDate,
+    pub consumption_target: Decimal,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsDeleteMonthlyTarget {
+    pub unit_id: i32,
+    pub month: NaiveDate,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetMonthlyTargets {
+    pub start_month: Option<NaiveDate>,
+    pub end_month: Option<NaiveDate>,
+}
+
+#[derive(QueryableByName, Serialize, Clone, Debug)]
+pub struct GetMonthlyTargetResponse {
+    #[diesel(sql_type = Timestamp)]
+    pub month: NaiveDateTime,
+    #[diesel(sql_type = Numeric)]
+    pub consumption_target: Decimal,
+    #[diesel(sql_type = Bool)]
+    pub is_manual: bool,
+    /* Meta considerada nas tendências: a manual, quando houver, senão a automática */
+    #[diesel(sql_type = Bool)]
+    pub is_effective: bool,
+}
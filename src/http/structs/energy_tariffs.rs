@@ -0,0 +1,59 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Numeric, Timestamp}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetUnitTariff {
+    pub unit_id: i32,
+    /* CONVENCIONAL, VERDE ou AZUL */
+    pub modality: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    /* Posto de ponta, padrão 18:00 às 21:00 em dias úteis */
+    pub peak_start_time: Option<NaiveTime>,
+    pub peak_end_time: Option<NaiveTime>,
+    pub peak_weekdays_only: Option<bool>,
+    pub peak_energy_price: Decimal,
+    pub off_peak_energy_price: Decimal,
+    pub peak_demand_price: Option<Decimal>,
+    pub off_peak_demand_price: Option<Decimal>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetEnergyCost {
+    pub unit_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+pub struct UnitHourConsumption {
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Numeric)]
+    pub consumption: Decimal,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+pub struct UnitDemand {
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Numeric)]
+    pub demand: Decimal,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EnergyCostSummary {
+    pub time: NaiveDate,
+    pub peak_consumption: Decimal,
+    pub off_peak_consumption: Decimal,
+    pub peak_cost: Decimal,
+    pub off_peak_cost: Decimal,
+    pub peak_demand: Option<Decimal>,
+    pub off_peak_demand: Option<Decimal>,
+    pub demand_cost: Option<Decimal>,
+    pub total_cost: Decimal,
+    /* Horas com consumo mas sem tarifa vigente, que ficaram fora do custo */
+    pub hours_without_tariff: i32,
+}
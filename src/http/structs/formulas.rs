@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsValidateFormulas {
+    /* Registrador -> fórmula, no mesmo formato enviado pelo API Server (ex.: "en_at_tri": "*CMN3/1000") */
+    pub formulas: HashMap<String, String>,
+    /* Telemetrias de exemplo para calcular as fórmulas */
+    pub telemetries: Option<Vec<Value>>,
+    pub is_ieee754_fp: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ValidateFormulasResponse {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub formulas: Vec<FormulaValidation>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FormulaValidation {
+    pub param: String,
+    pub formula: String,
+    pub variables: Vec<String>,
+    pub samples: Vec<FormulaSampleResult>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FormulaSampleResult {
+    pub input: Option<f64>,
+    pub value: Option<f64>,
+    pub missing_input: Option<String>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,46 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Bool, Numeric, Timestamp}, QueryableByName};
+use crate::models::database_models::load_anomalies::{LoadAnomalyRow, UnitDailyLoadProfile};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetOperatingSchedule {
+    pub unit_id: i32,
+    /* Dias da semana sem horário informado ficam sem expediente */
+    pub days: Vec<OperatingScheduleDay>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OperatingScheduleDay {
+    /* 1 = segunda ... 7 = domingo */
+    pub weekday: i32,
+    pub start_time: NaiveTime,
+    /* Menor que start_time quando o expediente atravessa a meia-noite */
+    pub end_time: NaiveTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetLoadAnomalies {
+    pub unit_id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GetLoadAnomaliesResponse {
+    pub anomalies: Vec<LoadAnomalyRow>,
+    pub load_profiles: Vec<UnitDailyLoadProfile>,
+}
+
+#[derive(QueryableByName, Debug, Clone)]
+pub struct UnitHourLoad {
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Numeric)]
+    pub consumption: Decimal,
+    #[diesel(sql_type = Bool)]
+    pub all_valid: bool,
+    #[diesel(sql_type = Bool)]
+    pub all_circuits: bool,
+}
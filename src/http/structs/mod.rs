@@ -4,3 +4,17 @@ pub mod chiller_parameters;
 pub mod script_days;
 pub mod energy_efficiency;
 pub mod energy_demand;
+pub mod dynamo_consumed_capacity;
+pub mod dynamo_tables;
+pub mod formulas;
+pub mod energy_reactive;
+pub mod energy_tariffs;
+pub mod contracted_demand;
+pub mod power_quality;
+pub mod electric_circuits;
+pub mod energy_forecast;
+pub mod energy_targets;
+pub mod energy_baselines;
+pub mod carbon_emissions;
+pub mod calendar;
+pub mod load_anomalies;
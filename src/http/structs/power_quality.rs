@@ -0,0 +1,71 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use diesel::{sql_types::{Integer, Nullable, Numeric, Text, Timestamp}, QueryableByName};
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsGetPowerQuality {
+    pub unit_id: i32,
+    pub electric_circuits_ids: Option<Vec<i32>>,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReqParamsSetNominalVoltage {
+    pub electric_circuit_id: i32,
+    /* Tensão nominal fase-neutro do circuito (V). Se vazio, usa a do configfile */
+    pub nominal_voltage: Option<Decimal>,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetPowerQualityStatsResponse {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_reference_id: i32,
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub min_voltage: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub max_voltage: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub average_voltage: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub average_voltage_unbalance: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub max_voltage_unbalance: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub average_current_unbalance: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub max_current_unbalance: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub min_frequency: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub max_frequency: Option<Decimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub max_frequency_deviation: Option<Decimal>,
+    #[diesel(sql_type = Integer)]
+    pub readings_count: i32,
+    #[diesel(sql_type = Integer)]
+    pub readings_out_of_limits: i32,
+}
+
+#[derive(QueryableByName, Deserialize, Serialize, Clone, Debug)]
+pub struct GetPowerQualityEventResponse {
+    #[diesel(sql_type = Integer)]
+    pub electric_circuit_reference_id: i32,
+    #[diesel(sql_type = Timestamp)]
+    pub record_date: NaiveDateTime,
+    #[diesel(sql_type = Timestamp)]
+    pub end_date: NaiveDateTime,
+    #[diesel(sql_type = Text)]
+    pub event_type: String,
+    #[diesel(sql_type = Text)]
+    pub phase: String,
+    #[diesel(sql_type = Integer)]
+    pub duration_seconds: i32,
+    #[diesel(sql_type = Numeric)]
+    pub extreme_voltage: Decimal,
+    #[diesel(sql_type = Numeric)]
+    pub extreme_voltage_pu: Decimal,
+}
@@ -6,4 +6,6 @@ pub struct ReqParamsScriptDays {
     pub end_date: String,
     pub client_ids: Option<Vec<i32>>,
     pub unit_ids: Option<Vec<i32>>,
+    /* Usa os snapshots de configuração gravados no banco, sem consultar o API Server */
+    pub use_stored_snapshots: Option<bool>,
 }
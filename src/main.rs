@@ -8,17 +8,26 @@ mod compression;
 mod telemetry_payloads;
 mod configs;
 mod http;
+mod energy_billing;
+mod forecasting;
+mod calendar;
 
 use diesel::r2d2::{self, ConnectionManager};
 use std::sync::Arc;
 use actix_web::{web, App, HttpServer};
 use schedules::scheduler::{run_scheduler_many_days, start_scheduler, write_to_log_file_thread};
-use http::routes::{chiller_parameters::chiller_parameters_routes, energy::energy_config_routes, energy_demand::energy_demand_config_routes, energy_efficiency::energy_efficiency_routes, health_check::health_check_route, script_days::scrip_days_route, water::water_config_routes};
+use schedules::dynamo_consumed_capacity::start_consumed_capacity_flush;
+use db::config::table_resolution::start_table_rules_reload;
+use http::routes::{chiller_parameters::chiller_parameters_routes, energy::energy_config_routes, energy_demand::energy_demand_config_routes, energy_efficiency::energy_efficiency_routes, health_check::health_check_route, script_days::scrip_days_route, water::water_config_routes, dynamo_consumed_capacity::dynamo_consumed_capacity_routes, dynamo_tables::dynamo_tables_routes, formulas::formulas_routes, energy_reactive::energy_reactive_routes, energy_tariffs::energy_tariffs_routes, contracted_demand::contracted_demand_routes, power_quality::power_quality_routes, electric_circuits::electric_circuits_routes, energy_forecast::energy_forecast_routes, energy_targets::energy_targets_routes, energy_baselines::energy_baselines_routes, carbon_emissions::carbon_emissions_routes, calendar::calendar_routes, load_anomalies::load_anomalies_routes};
 
 #[derive (Clone)]
 pub struct GlobalVars {
     pub configfile: configs::ConfigFile,
     pub pool: r2d2::Pool<ConnectionManager<diesel::PgConnection>>,
+    pub compilation_cache: Arc<app_history::compilation_cache::CompilationCache>,
+    pub table_resolver: Arc<db::config::table_resolution::TableResolver>,
+    pub http_client: Arc<external_api::http_client::HttpClient>,
+    pub laager_token: Arc<tokio::sync::Mutex<Option<external_api::api_laager::LaagerToken>>>,
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -32,14 +41,26 @@ async fn main() -> std::io::Result<()> {
     let globs = Arc::new(GlobalVars{
         configfile: configfile.clone(),
         pool: db::config::postgres::PostgreSQLDatabaseManager::configure_connection_pool_pg(&configfile.POSTGRES_DATABASE_URL.clone()).unwrap(),
+        compilation_cache: Arc::new(app_history::compilation_cache::CompilationCache::new(configfile.COMPILATION_CACHE_DIR.clone())),
+        table_resolver: Arc::new(db::config::table_resolution::TableResolver::new(&configfile)),
+        http_client: Arc::new(external_api::http_client::HttpClient::new()),
+        laager_token: Arc::new(tokio::sync::Mutex::new(None)),
     });
 
+    if let Err(err) = globs.table_resolver.reload(&globs) {
+        let msg_error = format!("Erro ao carregar regras de tabelas do DynamoDB, usando apenas o configfile: {}", err);
+        write_to_log_file_thread(&msg_error, 0, "ERROR");
+        eprintln!("{}", msg_error);
+    }
+
     let msg_init = format!("Serviço iniciado");
     write_to_log_file_thread(&msg_init, 0, "INFO");
     println!("{}", msg_init);
 
     let globs_for_http_server = globs.clone();
     let globs_clone = globs.clone();
+    let globs_for_flush = globs.clone();
+    let globs_for_rules = globs.clone();
 
     // rodará 3:01 AM em UTC e 00:01 em GMT-3 
     let _ = tokio::spawn(async move { start_scheduler(&globs, 3).await });
@@ -47,6 +68,10 @@ async fn main() -> std::io::Result<()> {
     // // rodará 9:01 AM em UTC e 06:01 em GMT-3 
     let _ = tokio::spawn(async move { start_scheduler(&globs_clone, 9).await });
 
+    let _ = tokio::spawn(async move { start_consumed_capacity_flush(&globs_for_flush).await });
+
+    let _ = tokio::spawn(async move { start_table_rules_reload(&globs_for_rules).await });
+
     let _ = HttpServer::new(move || {
         let globs_for_http_server = globs_for_http_server.clone();
         App::new()
@@ -58,6 +83,20 @@ async fn main() -> std::io::Result<()> {
             .service(scrip_days_route())
             .service(energy_efficiency_routes())
             .service(energy_demand_config_routes())
+            .service(dynamo_consumed_capacity_routes())
+            .service(dynamo_tables_routes())
+            .service(formulas_routes())
+            .service(energy_reactive_routes())
+            .service(energy_tariffs_routes())
+            .service(contracted_demand_routes())
+            .service(power_quality_routes())
+            .service(electric_circuits_routes())
+            .service(energy_forecast_routes())
+            .service(energy_targets_routes())
+            .service(energy_baselines_routes())
+            .service(carbon_emissions_routes())
+            .service(calendar_routes())
+            .service(load_anomalies_routes())
     }).bind(("0.0.0.0", configfile.API_PORT))?.run().await;
 
     Ok(())
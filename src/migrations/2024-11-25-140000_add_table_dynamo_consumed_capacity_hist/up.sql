@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS dynamo_consumed_capacity_hist (
+    table_name TEXT NOT NULL,
+    pipeline TEXT NOT NULL,
+    client_reference_id INT NOT NULL,
+    record_date DATE NOT NULL,
+    consumed_rcu DECIMAL(16,3) NOT NULL,
+    -- Compilações reaproveitadas do cache pelo pipeline e as RCUs que custaram ao pipeline que as compilou
+    cache_hits INT NOT NULL DEFAULT 0,
+    cache_hit_rcu DECIMAL(16,3) NOT NULL DEFAULT 0,
+    PRIMARY KEY(table_name, pipeline, client_reference_id, record_date)
+);
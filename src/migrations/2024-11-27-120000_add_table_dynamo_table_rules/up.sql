@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS dynamo_table_rules (
+    id SERIAL PRIMARY KEY,
+    pattern TEXT NOT NULL UNIQUE,
+    table_template TEXT NOT NULL,
+    partition_key TEXT NOT NULL DEFAULT 'dev_id',
+    sort_key TEXT NOT NULL DEFAULT 'timestamp',
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS api_server_snapshots (
+    snapshot_type TEXT NOT NULL,
+    reference_id INT NOT NULL,
+    record_date DATE NOT NULL,
+    parent_reference_id INT,
+    payload TEXT NOT NULL,
+    changed BOOLEAN NOT NULL DEFAULT TRUE,
+    fetched_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(snapshot_type, reference_id, record_date)
+);
+
+CREATE INDEX IF NOT EXISTS api_server_snapshots_parent_idx ON api_server_snapshots (snapshot_type, parent_reference_id, record_date);
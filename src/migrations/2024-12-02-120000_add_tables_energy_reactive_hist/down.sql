@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS energy_reactive_day_hist;
+DROP TABLE IF EXISTS energy_reactive_hour_hist;
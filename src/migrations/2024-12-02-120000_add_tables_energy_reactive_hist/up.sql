@@ -0,0 +1,28 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS energy_reactive_hour_hist (
+    electric_circuit_id int not null,
+    record_date TIMESTAMP not null,
+    active_consumption DECIMAL(12,3) not null,
+    reactive_consumption DECIMAL(12,3) not null,
+    average_power_factor DECIMAL(5,3),
+    min_power_factor DECIMAL(5,3),
+    ufer DECIMAL(12,3) not null,
+    PRIMARY KEY(electric_circuit_id, record_date),
+
+    CONSTRAINT energy_reactive_hour_hist_fk_electric_circuit_id FOREIGN KEY (electric_circuit_id) REFERENCES electric_circuits (id)
+);
+
+select create_hypertable('energy_reactive_hour_hist', 'record_date');
+
+CREATE TABLE IF NOT EXISTS energy_reactive_day_hist (
+    electric_circuit_id int not null,
+    record_date DATE not null,
+    active_consumption DECIMAL(12,3) not null,
+    reactive_consumption DECIMAL(12,3) not null,
+    average_power_factor DECIMAL(5,3),
+    min_power_factor DECIMAL(5,3),
+    ufer DECIMAL(12,3) not null,
+    PRIMARY KEY(electric_circuit_id, record_date),
+
+    CONSTRAINT energy_reactive_day_hist_fk_electric_circuit_id FOREIGN KEY (electric_circuit_id) REFERENCES electric_circuits (id)
+);
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS unit_tariffs (
+    id SERIAL PRIMARY KEY,
+    unit_id INT NOT NULL,
+    modality TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE,
+    peak_start_time TIME NOT NULL DEFAULT '18:00:00',
+    peak_end_time TIME NOT NULL DEFAULT '21:00:00',
+    peak_weekdays_only BOOLEAN NOT NULL DEFAULT TRUE,
+    peak_energy_price DECIMAL(12, 6) NOT NULL,
+    off_peak_energy_price DECIMAL(12, 6) NOT NULL,
+    peak_demand_price DECIMAL(12, 6),
+    off_peak_demand_price DECIMAL(12, 6),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (unit_id, start_date),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS demand_overrun_events;
+DROP TABLE IF EXISTS contracted_demands;
@@ -0,0 +1,31 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS contracted_demands (
+    id SERIAL PRIMARY KEY,
+    unit_id INT NOT NULL,
+    electric_circuit_id INT,
+    start_date DATE NOT NULL,
+    end_date DATE,
+    peak_demand DECIMAL(12, 3),
+    off_peak_demand DECIMAL(12, 3) NOT NULL,
+    tolerance_percentage DECIMAL(5, 2) NOT NULL DEFAULT 5,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (unit_id) REFERENCES units(id),
+    FOREIGN KEY (electric_circuit_id) REFERENCES electric_circuits(id)
+);
+
+CREATE INDEX IF NOT EXISTS contracted_demands_unit_id_idx ON contracted_demands (unit_id);
+
+CREATE TABLE IF NOT EXISTS demand_overrun_events (
+    id SERIAL PRIMARY KEY,
+    contracted_demand_id INT NOT NULL,
+    event_date DATE NOT NULL,
+    tariff_period TEXT NOT NULL,
+    record_date TIMESTAMP NOT NULL,
+    contracted_demand DECIMAL(12, 3) NOT NULL,
+    tolerance_limit DECIMAL(12, 3) NOT NULL,
+    measured_demand DECIMAL(12, 3) NOT NULL,
+    overrun_demand DECIMAL(12, 3) NOT NULL,
+    estimated_penalty DECIMAL(12, 2),
+    UNIQUE (contracted_demand_id, event_date, tariff_period),
+    FOREIGN KEY (contracted_demand_id) REFERENCES contracted_demands(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS power_quality_events;
+DROP TABLE IF EXISTS power_quality_minutes_hist;
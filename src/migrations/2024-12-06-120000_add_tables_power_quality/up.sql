@@ -0,0 +1,38 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS power_quality_minutes_hist (
+    electric_circuit_id int not null,
+    record_date TIMESTAMP not null,
+    min_voltage DECIMAL(8,2),
+    max_voltage DECIMAL(8,2),
+    average_voltage DECIMAL(8,2),
+    average_voltage_unbalance DECIMAL(6,2),
+    max_voltage_unbalance DECIMAL(6,2),
+    average_current_unbalance DECIMAL(6,2),
+    max_current_unbalance DECIMAL(6,2),
+    min_frequency DECIMAL(6,3),
+    max_frequency DECIMAL(6,3),
+    max_frequency_deviation DECIMAL(6,3),
+    readings_count int not null,
+    readings_out_of_limits int not null,
+    PRIMARY KEY(electric_circuit_id, record_date),
+
+    CONSTRAINT power_quality_minutes_hist_fk_electric_circuit_id FOREIGN KEY (electric_circuit_id) REFERENCES electric_circuits (id)
+);
+
+select create_hypertable('power_quality_minutes_hist', 'record_date');
+
+CREATE TABLE IF NOT EXISTS power_quality_events (
+    electric_circuit_id int not null,
+    record_date TIMESTAMP not null,
+    event_type TEXT not null,
+    phase TEXT not null,
+    end_date TIMESTAMP not null,
+    duration_seconds int not null,
+    extreme_voltage DECIMAL(8,2) not null,
+    extreme_voltage_pu DECIMAL(6,3) not null,
+    PRIMARY KEY(electric_circuit_id, record_date, event_type, phase),
+
+    CONSTRAINT power_quality_events_fk_electric_circuit_id FOREIGN KEY (electric_circuit_id) REFERENCES electric_circuits (id)
+);
+
+select create_hypertable('power_quality_events', 'record_date');
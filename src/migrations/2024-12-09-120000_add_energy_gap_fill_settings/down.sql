@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE electric_circuits DROP COLUMN IF EXISTS max_hour_consumption;
+ALTER TABLE electric_circuits DROP COLUMN IF EXISTS gap_fill_strategy;
+ALTER TABLE energy_hist DROP COLUMN IF EXISTS fill_strategy;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Sem reescrever a hypertable: as linhas anteriores ficam NULL e a origem é deduzida de is_measured_consumption
+-- e is_valid_consumption na leitura (legacy_fill_strategy); o default vale apenas para as novas linhas
+ALTER TABLE energy_hist ADD COLUMN IF NOT EXISTS fill_strategy TEXT;
+ALTER TABLE energy_hist ALTER COLUMN fill_strategy SET DEFAULT 'MEASURED';
+
+ALTER TABLE electric_circuits ADD COLUMN IF NOT EXISTS gap_fill_strategy TEXT;
+ALTER TABLE electric_circuits ADD COLUMN IF NOT EXISTS max_hour_consumption DECIMAL(12,3);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS meter_discontinuity_events;
+ALTER TABLE electric_circuits DROP COLUMN IF EXISTS register_rollover_value;
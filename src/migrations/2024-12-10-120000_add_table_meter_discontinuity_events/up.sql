@@ -0,0 +1,15 @@
+-- Your SQL goes here
+ALTER TABLE electric_circuits ADD COLUMN IF NOT EXISTS register_rollover_value DECIMAL(16,3);
+
+CREATE TABLE IF NOT EXISTS meter_discontinuity_events (
+    electric_circuit_id int not null,
+    record_date TIMESTAMP not null,
+    event_type TEXT not null,
+    previous_value DECIMAL(16,3) not null,
+    new_value DECIMAL(16,3) not null,
+    applied_offset DECIMAL(16,3) not null,
+    estimated_consumption DECIMAL(12,3),
+    PRIMARY KEY(electric_circuit_id, record_date),
+
+    CONSTRAINT meter_discontinuity_events_fk_electric_circuit_id FOREIGN KEY (electric_circuit_id) REFERENCES electric_circuits (id)
+);
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS client_energy_forecast_models (
+    client_id int not null,
+    forecast_model TEXT not null,
+    updated_at TIMESTAMP not null default now(),
+    PRIMARY KEY(client_id),
+
+    CONSTRAINT client_energy_forecast_models_fk_client_id FOREIGN KEY (client_id) REFERENCES clients (id)
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM energy_monthly_consumption_target WHERE is_manual = true;
+ALTER TABLE energy_monthly_consumption_target DROP CONSTRAINT IF EXISTS energy_monthly_consumption_target_pkey;
+ALTER TABLE energy_monthly_consumption_target ADD PRIMARY KEY (unit_id, date_forecast);
+ALTER TABLE energy_monthly_consumption_target DROP COLUMN IF EXISTS is_manual;
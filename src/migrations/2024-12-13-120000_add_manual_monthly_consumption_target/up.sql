@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE energy_monthly_consumption_target ADD COLUMN IF NOT EXISTS is_manual BOOLEAN not null default false;
+
+-- Meta manual e automática do mesmo mês convivem; a manual tem preferência
+ALTER TABLE energy_monthly_consumption_target DROP CONSTRAINT IF EXISTS energy_monthly_consumption_target_pkey;
+ALTER TABLE energy_monthly_consumption_target ADD PRIMARY KEY (unit_id, date_forecast, is_manual);
@@ -0,0 +1,79 @@
+-- Your SQL goes here
+ALTER TABLE electric_circuits ADD COLUMN IF NOT EXISTS parent_id int;
+ALTER TABLE electric_circuits ADD CONSTRAINT electric_circuits_fk_parent_id FOREIGN KEY (parent_id) REFERENCES electric_circuits (id);
+
+-- Totais da unidade consideram apenas os circuitos raiz, para não somar o medidor geral com os submedidores
+drop materialized view IF EXISTS energy_hist_view;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS energy_hist_view
+WITH (timescaledb.continuous) as
+select
+	ec.unit_id AS unit_id,
+	SUM(case when eh.is_valid_consumption then eh.consumption else 0 end) AS consumption,
+	COUNT(CASE WHEN eh.is_valid_consumption = false THEN 1 END) AS invalid_consumption_count,
+    COUNT(CASE WHEN eh.is_measured_consumption = true and eh.is_valid_consumption = true THEN 1 END) AS processed_consumption_count,
+    COUNT(*) as readings_count,
+	time_bucket(INTERVAL '1 day', eh.record_date) AS compilation_record_date
+FROM
+    energy_hist eh
+JOIN
+    electric_circuits ec ON eh.electric_circuit_id  = ec.id
+WHERE
+    ec.parent_id IS NULL
+GROUP by
+	compilation_record_date,
+    ec.unit_id
+WITH NO DATA;
+
+SELECT add_continuous_aggregate_policy('energy_hist_view',
+  start_offset => INTERVAL '1 month',
+  end_offset => INTERVAL '1 hour',
+  schedule_interval => INTERVAL '1 day');
+
+drop materialized view IF EXISTS energy_hist_view_month;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS energy_hist_view_month
+WITH (timescaledb.continuous) as
+select
+	ec.unit_id AS unit_id,
+	SUM(case when eh.is_valid_consumption then eh.consumption else 0 end) AS consumption,
+	COUNT(CASE WHEN eh.is_valid_consumption = false THEN 1 END) AS invalid_consumption_count,
+    COUNT(CASE WHEN eh.is_measured_consumption = true and eh.is_valid_consumption = true THEN 1 END) AS processed_consumption_count,
+    COUNT(*) as readings_count,
+	time_bucket(INTERVAL '1 month', eh.record_date) AS compilation_record_date
+FROM
+    energy_hist eh
+JOIN
+    electric_circuits ec ON eh.electric_circuit_id  = ec.id
+WHERE
+    ec.parent_id IS NULL
+GROUP by
+	compilation_record_date,
+    ec.unit_id
+WITH NO DATA;
+
+SELECT add_continuous_aggregate_policy('energy_hist_view_month',
+  start_offset => INTERVAL '1 year',
+  end_offset => INTERVAL '1 hour',
+  schedule_interval => INTERVAL '1 day');
+
+ALTER MATERIALIZED VIEW energy_hist_view_month set (timescaledb.materialized_only = false);
+
+drop materialized view IF EXISTS energy_consumption_forecast_view;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS energy_consumption_forecast_view
+WITH (timescaledb.continuous) as
+select
+	ec.unit_id AS unit_id,
+	SUM(ecf.consumption_forecast) AS consumption_forecast,
+	time_bucket(INTERVAL '1 day', ecf.record_date) AS compilation_record_date
+FROM
+    energy_consumption_forecast ecf
+JOIN
+    electric_circuits ec ON ecf.electric_circuit_id  = ec.id
+WHERE
+    ec.parent_id IS NULL
+GROUP by
+	compilation_record_date,
+    ec.unit_id
+WITH NO DATA;
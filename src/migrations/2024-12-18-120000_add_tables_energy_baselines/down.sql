@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS energy_baselines;
+DROP TABLE IF EXISTS unit_baseline_variables;
@@ -0,0 +1,32 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS unit_baseline_variables (
+    unit_id INT NOT NULL,
+    day DATE NOT NULL,
+    average_temperature DECIMAL(5, 2),
+    operating_hours DECIMAL(4, 2),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (unit_id, day),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE TABLE IF NOT EXISTS energy_baselines (
+    id SERIAL PRIMARY KEY,
+    unit_id INT NOT NULL,
+    name TEXT NOT NULL,
+    reference_start DATE NOT NULL,
+    reference_end DATE NOT NULL,
+    cooling_base_temperature DECIMAL(5, 2),
+    uses_operating_hours BOOLEAN NOT NULL DEFAULT FALSE,
+    intercept DECIMAL(16, 6) NOT NULL,
+    cooling_degree_days_coefficient DECIMAL(16, 6),
+    operating_hours_coefficient DECIMAL(16, 6),
+    observations INT NOT NULL,
+    r_squared DECIMAL(8, 6) NOT NULL,
+    cv_rmse DECIMAL(10, 6) NOT NULL,
+    rmse DECIMAL(16, 6) NOT NULL,
+    residual_autocorrelation DECIMAL(8, 6) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE INDEX IF NOT EXISTS energy_baselines_unit_id_idx ON energy_baselines (unit_id);
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+-- Fatores de emissão da rede (tCO2e/MWh), com vigência: novos fatores não alteram as emissões já calculadas de meses anteriores
+CREATE TABLE IF NOT EXISTS grid_emission_factors (
+    id SERIAL PRIMARY KEY,
+    grid TEXT NOT NULL DEFAULT 'SIN',
+    start_date DATE NOT NULL,
+    end_date DATE,
+    emission_factor DECIMAL(10, 6) NOT NULL,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (grid, start_date)
+);
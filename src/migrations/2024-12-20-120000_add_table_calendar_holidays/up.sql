@@ -0,0 +1,18 @@
+-- Your SQL goes here
+-- Feriados e dias sem expediente além dos feriados nacionais calculados: nacionais extras, estaduais (units.state_name),
+-- municipais (units.city_name) e do cliente (recessos, fechamentos). end_date permite períodos de vários dias.
+CREATE TABLE IF NOT EXISTS calendar_holidays (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE,
+    recurring BOOLEAN NOT NULL DEFAULT FALSE,
+    state_name VARCHAR(100),
+    city_name VARCHAR(100),
+    client_id INT,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (client_id) REFERENCES clients(id)
+);
+
+CREATE INDEX IF NOT EXISTS calendar_holidays_scope_idx ON calendar_holidays (scope);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS load_anomalies;
+DROP TABLE IF EXISTS unit_daily_load_profiles;
+DROP TABLE IF EXISTS unit_operating_schedules;
@@ -0,0 +1,37 @@
+-- Your SQL goes here
+-- Horário de funcionamento por dia da semana (1 = segunda ... 7 = domingo). Dia sem linha: unidade fechada o dia todo
+CREATE TABLE IF NOT EXISTS unit_operating_schedules (
+    unit_id INT NOT NULL,
+    weekday INT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (unit_id, weekday),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+-- Carga de base e consumo fora do expediente de cada dia, histórico usado na detecção de anomalias
+CREATE TABLE IF NOT EXISTS unit_daily_load_profiles (
+    unit_id INT NOT NULL,
+    day DATE NOT NULL,
+    base_load DECIMAL(12, 3) NOT NULL,
+    after_hours_consumption DECIMAL(12, 3) NOT NULL,
+    after_hours_average_load DECIMAL(12, 3) NOT NULL,
+    after_hours_count INT NOT NULL,
+    operating_hours_consumption DECIMAL(12, 3) NOT NULL,
+    PRIMARY KEY (unit_id, day),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
+
+CREATE TABLE IF NOT EXISTS load_anomalies (
+    id SERIAL PRIMARY KEY,
+    unit_id INT NOT NULL,
+    anomaly_date DATE NOT NULL,
+    anomaly_type TEXT NOT NULL,
+    measured_value DECIMAL(12, 3) NOT NULL,
+    expected_value DECIMAL(12, 3) NOT NULL,
+    threshold DECIMAL(12, 3) NOT NULL,
+    deviation_percentage DECIMAL(10, 2),
+    UNIQUE (unit_id, anomaly_date, anomaly_type),
+    FOREIGN KEY (unit_id) REFERENCES units(id)
+);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS dynamo_table_rules_device_type_pattern_idx;
+ALTER TABLE dynamo_table_rules ADD CONSTRAINT dynamo_table_rules_pattern_key UNIQUE (pattern);
+ALTER TABLE dynamo_table_rules DROP COLUMN IF EXISTS device_type;
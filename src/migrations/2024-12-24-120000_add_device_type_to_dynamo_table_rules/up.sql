@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- Tipo de dispositivo ao qual a regra se aplica (DAC, DUT, DRI, DMA, DMT, DAL, DAM); NULL vale para todos
+ALTER TABLE dynamo_table_rules ADD COLUMN IF NOT EXISTS device_type TEXT;
+
+-- O mesmo padrão pode ter uma regra por tipo de dispositivo
+ALTER TABLE dynamo_table_rules DROP CONSTRAINT IF EXISTS dynamo_table_rules_pattern_key;
+CREATE UNIQUE INDEX IF NOT EXISTS dynamo_table_rules_device_type_pattern_idx ON dynamo_table_rules ((COALESCE(device_type, '')), pattern);
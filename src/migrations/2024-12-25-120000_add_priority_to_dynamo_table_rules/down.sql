@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE dynamo_table_rules DROP COLUMN IF EXISTS priority;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Regras de maior prioridade são avaliadas primeiro; no empate, vale a ordem de cadastro
+ALTER TABLE dynamo_table_rules ADD COLUMN IF NOT EXISTS priority INT NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE api_server_snapshots DROP COLUMN IF EXISTS other_timezone;
+ALTER TABLE api_server_snapshots DROP COLUMN IF EXISTS deleted;
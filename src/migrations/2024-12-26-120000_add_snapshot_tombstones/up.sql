@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- Cliente ou unidade que deixou de vir do API Server a partir de record_date
+ALTER TABLE api_server_snapshots ADD COLUMN IF NOT EXISTS deleted BOOLEAN NOT NULL DEFAULT FALSE;
+-- Grupo de fuso horário em que a unidade foi buscada (UNITS_WITH_OTHERS_TIMEZONES); NULL se não informado
+ALTER TABLE api_server_snapshots ADD COLUMN IF NOT EXISTS other_timezone BOOLEAN;
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE electric_circuits DROP COLUMN IF EXISTS nominal_voltage;
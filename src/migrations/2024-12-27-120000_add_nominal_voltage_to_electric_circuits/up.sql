@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Tensão nominal fase-neutro do circuito (V) usada na qualidade de energia; NULL usa a do configfile
+ALTER TABLE electric_circuits ADD COLUMN IF NOT EXISTS nominal_voltage DECIMAL(8,2);
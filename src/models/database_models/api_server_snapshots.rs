@@ -0,0 +1,17 @@
+use crate::schema::api_server_snapshots;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{Insertable, Queryable};
+
+#[derive(Debug, Queryable, Insertable)]
+#[diesel(table_name = api_server_snapshots)]
+pub struct ApiServerSnapshot {
+    pub snapshot_type: String,
+    pub reference_id: i32,
+    pub record_date: NaiveDate,
+    pub parent_reference_id: Option<i32>,
+    pub payload: String,
+    pub changed: bool,
+    pub fetched_at: NaiveDateTime,
+    pub deleted: bool,
+    pub other_timezone: Option<bool>,
+}
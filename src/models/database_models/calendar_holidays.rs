@@ -0,0 +1,32 @@
+use crate::schema::calendar_holidays;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{Insertable, Queryable, QueryableByName};
+use serde::Serialize;
+
+#[derive(Debug, Queryable, QueryableByName, Serialize, Clone)]
+#[diesel(table_name = calendar_holidays)]
+pub struct CalendarHolidayRow {
+    pub id: i32,
+    pub name: String,
+    pub scope: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub recurring: bool,
+    pub state_name: Option<String>,
+    pub city_name: Option<String>,
+    pub client_id: Option<i32>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = calendar_holidays)]
+pub struct CalendarHoliday {
+    pub name: String,
+    pub scope: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub recurring: bool,
+    pub state_name: Option<String>,
+    pub city_name: Option<String>,
+    pub client_id: Option<i32>,
+}
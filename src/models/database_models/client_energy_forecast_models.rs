@@ -0,0 +1,9 @@
+use crate::schema::client_energy_forecast_models;
+use diesel::{Insertable, Queryable};
+
+#[derive(Debug, Queryable, Insertable, Clone)]
+#[diesel(table_name = client_energy_forecast_models)]
+pub struct ClientEnergyForecastModel {
+    pub client_id: i32,
+    pub forecast_model: String,
+}
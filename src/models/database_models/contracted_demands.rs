@@ -0,0 +1,44 @@
+use crate::schema::{contracted_demands, demand_overrun_events};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct ContractedDemandRow {
+    pub id: i32,
+    pub unit_id: i32,
+    pub electric_circuit_id: Option<i32>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub peak_demand: Option<Decimal>,
+    pub off_peak_demand: Decimal,
+    pub tolerance_percentage: Decimal,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = contracted_demands)]
+pub struct ContractedDemand {
+    pub unit_id: i32,
+    pub electric_circuit_id: Option<i32>,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub peak_demand: Option<Decimal>,
+    pub off_peak_demand: Decimal,
+    pub tolerance_percentage: Decimal,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = demand_overrun_events)]
+pub struct DemandOverrunEvent {
+    pub contracted_demand_id: i32,
+    pub event_date: NaiveDate,
+    pub tariff_period: String,
+    pub record_date: NaiveDateTime,
+    pub contracted_demand: Decimal,
+    pub tolerance_limit: Decimal,
+    pub measured_demand: Decimal,
+    pub overrun_demand: Decimal,
+    pub estimated_penalty: Option<Decimal>,
+}
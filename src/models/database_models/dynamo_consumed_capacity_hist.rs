@@ -0,0 +1,16 @@
+use crate::schema::dynamo_consumed_capacity_hist;
+use chrono::NaiveDate;
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Queryable, Insertable)]
+#[diesel(table_name = dynamo_consumed_capacity_hist)]
+pub struct DynamoConsumedCapacityHist {
+    pub table_name: String,
+    pub pipeline: String,
+    pub client_reference_id: i32,
+    pub record_date: NaiveDate,
+    pub consumed_rcu: Decimal,
+    pub cache_hits: i32,
+    pub cache_hit_rcu: Decimal,
+}
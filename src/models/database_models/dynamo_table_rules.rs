@@ -0,0 +1,27 @@
+use crate::schema::dynamo_table_rules;
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Queryable, Serialize)]
+pub struct DynamoTableRuleRow {
+    pub id: i32,
+    pub pattern: String,
+    pub table_template: String,
+    pub partition_key: String,
+    pub sort_key: String,
+    pub updated_at: NaiveDateTime,
+    pub device_type: Option<String>,
+    pub priority: i32,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = dynamo_table_rules)]
+pub struct DynamoTableRule {
+    pub device_type: Option<String>,
+    pub pattern: String,
+    pub table_template: String,
+    pub partition_key: String,
+    pub sort_key: String,
+    pub priority: i32,
+}
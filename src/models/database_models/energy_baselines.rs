@@ -0,0 +1,62 @@
+use crate::schema::{energy_baselines, unit_baseline_variables};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct EnergyBaselineRow {
+    pub id: i32,
+    pub unit_id: i32,
+    pub name: String,
+    pub reference_start: NaiveDate,
+    pub reference_end: NaiveDate,
+    pub cooling_base_temperature: Option<Decimal>,
+    pub uses_operating_hours: bool,
+    pub intercept: Decimal,
+    pub cooling_degree_days_coefficient: Option<Decimal>,
+    pub operating_hours_coefficient: Option<Decimal>,
+    pub observations: i32,
+    pub r_squared: Decimal,
+    pub cv_rmse: Decimal,
+    pub rmse: Decimal,
+    pub residual_autocorrelation: Decimal,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = energy_baselines)]
+pub struct EnergyBaseline {
+    pub unit_id: i32,
+    pub name: String,
+    pub reference_start: NaiveDate,
+    pub reference_end: NaiveDate,
+    pub cooling_base_temperature: Option<Decimal>,
+    pub uses_operating_hours: bool,
+    pub intercept: Decimal,
+    pub cooling_degree_days_coefficient: Option<Decimal>,
+    pub operating_hours_coefficient: Option<Decimal>,
+    pub observations: i32,
+    pub r_squared: Decimal,
+    pub cv_rmse: Decimal,
+    pub rmse: Decimal,
+    pub residual_autocorrelation: Decimal,
+}
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct UnitBaselineVariablesRow {
+    pub unit_id: i32,
+    pub day: NaiveDate,
+    pub average_temperature: Option<Decimal>,
+    pub operating_hours: Option<Decimal>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = unit_baseline_variables)]
+pub struct UnitBaselineVariables {
+    pub unit_id: i32,
+    pub day: NaiveDate,
+    pub average_temperature: Option<Decimal>,
+    pub operating_hours: Option<Decimal>,
+}
@@ -11,5 +11,6 @@ pub struct EnergyHist {
     pub consumption: Decimal,
     pub record_date: NaiveDateTime,
     pub is_measured_consumption: bool,
-    pub is_valid_consumption: bool
+    pub is_valid_consumption: bool,
+    pub fill_strategy: String,
 }
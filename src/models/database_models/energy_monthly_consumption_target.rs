@@ -9,5 +9,6 @@ use rust_decimal::Decimal;
 pub struct EnergyMonthlyConsumptionTarget {
     pub unit_id: i32,
     pub consumption_target: Decimal,
-    pub date_forecast: NaiveDateTime
+    pub date_forecast: NaiveDateTime,
+    pub is_manual: bool,
 }
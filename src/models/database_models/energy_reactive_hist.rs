@@ -0,0 +1,28 @@
+use crate::schema::{energy_reactive_day_hist, energy_reactive_hour_hist};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Queryable, Insertable, Clone)]
+#[diesel(table_name = energy_reactive_hour_hist)]
+pub struct EnergyReactiveHourHist {
+    pub electric_circuit_id: i32,
+    pub record_date: NaiveDateTime,
+    pub active_consumption: Decimal,
+    pub reactive_consumption: Decimal,
+    pub average_power_factor: Option<Decimal>,
+    pub min_power_factor: Option<Decimal>,
+    pub ufer: Decimal,
+}
+
+#[derive(Debug, Queryable, Insertable, Clone)]
+#[diesel(table_name = energy_reactive_day_hist)]
+pub struct EnergyReactiveDayHist {
+    pub electric_circuit_id: i32,
+    pub record_date: NaiveDate,
+    pub active_consumption: Decimal,
+    pub reactive_consumption: Decimal,
+    pub average_power_factor: Option<Decimal>,
+    pub min_power_factor: Option<Decimal>,
+    pub ufer: Decimal,
+}
@@ -0,0 +1,26 @@
+use crate::schema::grid_emission_factors;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct GridEmissionFactorRow {
+    pub id: i32,
+    pub grid: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub emission_factor: Decimal,
+    pub source: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = grid_emission_factors)]
+pub struct GridEmissionFactor {
+    pub grid: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub emission_factor: Decimal,
+    pub source: Option<String>,
+}
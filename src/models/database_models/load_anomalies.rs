@@ -0,0 +1,59 @@
+use crate::schema::{load_anomalies, unit_daily_load_profiles, unit_operating_schedules};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct UnitOperatingScheduleRow {
+    pub unit_id: i32,
+    pub weekday: i32,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = unit_operating_schedules)]
+pub struct UnitOperatingSchedule {
+    pub unit_id: i32,
+    pub weekday: i32,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+#[derive(Debug, Queryable, Insertable, Serialize, Clone)]
+#[diesel(table_name = unit_daily_load_profiles)]
+pub struct UnitDailyLoadProfile {
+    pub unit_id: i32,
+    pub day: NaiveDate,
+    pub base_load: Decimal,
+    pub after_hours_consumption: Decimal,
+    pub after_hours_average_load: Decimal,
+    pub after_hours_count: i32,
+    pub operating_hours_consumption: Decimal,
+}
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct LoadAnomalyRow {
+    pub id: i32,
+    pub unit_id: i32,
+    pub anomaly_date: NaiveDate,
+    pub anomaly_type: String,
+    pub measured_value: Decimal,
+    pub expected_value: Decimal,
+    pub threshold: Decimal,
+    pub deviation_percentage: Option<Decimal>,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = load_anomalies)]
+pub struct LoadAnomaly {
+    pub unit_id: i32,
+    pub anomaly_date: NaiveDate,
+    pub anomaly_type: String,
+    pub measured_value: Decimal,
+    pub expected_value: Decimal,
+    pub threshold: Decimal,
+    pub deviation_percentage: Option<Decimal>,
+}
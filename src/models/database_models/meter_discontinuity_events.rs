@@ -0,0 +1,16 @@
+use crate::schema::meter_discontinuity_events;
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Queryable, Insertable, Clone)]
+#[diesel(table_name = meter_discontinuity_events)]
+pub struct MeterDiscontinuityEvent {
+    pub electric_circuit_id: i32,
+    pub record_date: NaiveDateTime,
+    pub event_type: String,
+    pub previous_value: Decimal,
+    pub new_value: Decimal,
+    pub applied_offset: Decimal,
+    pub estimated_consumption: Option<Decimal>,
+}
@@ -0,0 +1,36 @@
+use crate::schema::{power_quality_events, power_quality_minutes_hist};
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Queryable, Insertable, Clone)]
+#[diesel(table_name = power_quality_minutes_hist)]
+pub struct PowerQualityMinutesHist {
+    pub electric_circuit_id: i32,
+    pub record_date: NaiveDateTime,
+    pub min_voltage: Option<Decimal>,
+    pub max_voltage: Option<Decimal>,
+    pub average_voltage: Option<Decimal>,
+    pub average_voltage_unbalance: Option<Decimal>,
+    pub max_voltage_unbalance: Option<Decimal>,
+    pub average_current_unbalance: Option<Decimal>,
+    pub max_current_unbalance: Option<Decimal>,
+    pub min_frequency: Option<Decimal>,
+    pub max_frequency: Option<Decimal>,
+    pub max_frequency_deviation: Option<Decimal>,
+    pub readings_count: i32,
+    pub readings_out_of_limits: i32,
+}
+
+#[derive(Debug, Queryable, Insertable, Clone)]
+#[diesel(table_name = power_quality_events)]
+pub struct PowerQualityEvent {
+    pub electric_circuit_id: i32,
+    pub record_date: NaiveDateTime,
+    pub event_type: String,
+    pub phase: String,
+    pub end_date: NaiveDateTime,
+    pub duration_seconds: i32,
+    pub extreme_voltage: Decimal,
+    pub extreme_voltage_pu: Decimal,
+}
@@ -0,0 +1,38 @@
+use crate::schema::unit_tariffs;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::{Insertable, Queryable};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+#[derive(Debug, Queryable, Serialize, Clone)]
+pub struct UnitTariffRow {
+    pub id: i32,
+    pub unit_id: i32,
+    pub modality: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub peak_start_time: NaiveTime,
+    pub peak_end_time: NaiveTime,
+    pub peak_weekdays_only: bool,
+    pub peak_energy_price: Decimal,
+    pub off_peak_energy_price: Decimal,
+    pub peak_demand_price: Option<Decimal>,
+    pub off_peak_demand_price: Option<Decimal>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = unit_tariffs)]
+pub struct UnitTariff {
+    pub unit_id: i32,
+    pub modality: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub peak_start_time: NaiveTime,
+    pub peak_end_time: NaiveTime,
+    pub peak_weekdays_only: bool,
+    pub peak_energy_price: Decimal,
+    pub off_peak_energy_price: Decimal,
+    pub peak_demand_price: Option<Decimal>,
+    pub off_peak_demand_price: Option<Decimal>,
+}
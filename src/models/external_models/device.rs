@@ -130,6 +130,9 @@ pub struct EnergyDevice {
     pub electric_circuit_id: i32,
     #[serde(rename = "ELECTRIC_CIRCUIT_NAME")]
     pub electric_circuit_name: String,
+    /* Circuito que mede a alimentação deste (medidor geral de um submedidor). Vazio para circuitos raiz */
+    #[serde(rename = "PARENT_ELECTRIC_CIRCUIT_ID")]
+    pub parent_electric_circuit_id: Option<i32>,
     pub formulas: Option<HashMap<String, String, RandomState>>,
     #[serde(rename = "DRI_INTERVAL")]
     pub dri_interval: i32,
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::db::entities::contracted_demands::{get_contracted_demands, replace_demand_overrun_events};
+use crate::db::entities::energy_demand_minutes_hist::get_circuit_demand_by_15_minutes;
+use crate::db::entities::unit_tariffs::{get_unit_demand_by_15_minutes, get_unit_tariffs};
+use crate::energy_billing::demand_overrun::{contracted_demand_for_date, detect_demand_overruns};
+use crate::energy_billing::tariffs::tariff_for_date;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+/// Verifica as ultrapassagens de demanda contratada do dia, para a unidade e para cada circuito com demanda contratada.
+pub fn process_demand_overruns(unit_id: i32, day: &str, globs: &Arc<GlobalVars>) {
+    let day_date = match NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => return,
+    };
+
+    let contracted_demands = match get_contracted_demands(unit_id, globs) {
+        Ok(res) => res,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter demandas contratadas da unidade {}: {}", unit_id, err), 0, "ERROR");
+            return;
+        }
+    };
+    if contracted_demands.is_empty() {
+        return;
+    }
+
+    let tariffs = get_unit_tariffs(unit_id, globs).unwrap_or_else(|err| {
+        write_to_log_file_thread(&format!("Erro ao obter tarifas da unidade {}: {}", unit_id, err), 0, "ERROR");
+        Vec::new()
+    });
+    let tariff = tariff_for_date(&tariffs, day_date);
+
+    let mut circuits: Vec<Option<i32>> = contracted_demands.iter().map(|contracted| contracted.electric_circuit_id).collect();
+    circuits.sort();
+    circuits.dedup();
+
+    let mut events = Vec::new();
+    for electric_circuit_id in circuits {
+        let contracted = match contracted_demand_for_date(&contracted_demands, electric_circuit_id, day_date) {
+            Some(contracted) => contracted,
+            None => continue,
+        };
+
+        let demand = match electric_circuit_id {
+            Some(circuit_id) => get_circuit_demand_by_15_minutes(circuit_id, day, day, globs),
+            None => get_unit_demand_by_15_minutes(unit_id, day, day, globs),
+        };
+        let demand = match demand {
+            Ok(res) => res,
+            Err(err) => {
+                write_to_log_file_thread(&format!("Erro ao obter demanda para verificar ultrapassagem, unidade {}: {}", unit_id, err), 0, "ERROR");
+                continue;
+            }
+        };
+
+        events.extend(detect_demand_overruns(contracted, tariff, &demand, day_date));
+    }
+
+    let contracted_demand_ids: Vec<i32> = contracted_demands.iter().map(|contracted| contracted.id).collect();
+    if let Err(err) = replace_demand_overrun_events(&contracted_demand_ids, day_date, &events, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar ultrapassagens de demanda da unidade {}: {}", unit_id, err), 0, "ERROR");
+    }
+}
@@ -12,7 +12,7 @@ use super::chiller::chiller_xa_parameters::{calculate_group_averages_xa_hvar, co
 use super::device_disponibility::insert_device_disponibility_hist;
 use super::last_device_telemetry_time::{insert_last_device_telemetry, very_last_device_telemetry_dac};
 use super::waters::{compile_dma_data, compile_laager_data, insert_data_dma_per_hour, insert_data_laager_per_hour, normalize_laager_consumption};
-use super::devices_l1_totalization::{insert_device_l1_totalization, verify_insert_update_asset};
+use super::devices_l1_totalization::{day_programming_intervals, insert_device_l1_totalization, verify_insert_update_asset};
 
 use crate::{app_history::{compiler_queues::{task_queue_manager, CompilationRequest}, dac_hist::{parse_parameters_dac, DacHist}, dal_hist::{parse_parameters_dal, DalHist}, dam_hist::{parse_parameters_dam, DamHist}, dma_hist::{parse_parameters, DmaCompiledData}, dmt_hist::{parse_parameters_dmt, DmtHist}, dri_hist::{DriHist, DriHistParams}, dut_hist::{parse_parameters_dut, DutHist}}, external_api::api_laager::LaagerApi, models::external_models::{device::{DacDevice, DalDevice, DamDevice, DmaDevice, DmtDevice, DriDevice, DutDevice, LaagerDevice, WaterConsumptionHistory}, unit}, schedules::scheduler::write_to_log_file_thread, telemetry_payloads::dri_telemetry::{DriChillerCarrierHXTelemetry, DriChillerCarrierXATelemetry}, GlobalVars};
 use super::chiller::{chiller_hx_parameters::{calculate_group_averages_hx, group_telemetries_by_10_minutes_hx, insert_chiller_hx_parameters}, chiller_xa_parameters::{calculate_group_averages_xa, group_telemetries_by_10_minutes_xa, insert_chiller_xa_parameters}};
@@ -69,8 +69,9 @@ pub async fn process_duts_devices(unit_id: i32, day: &str, devices: &Vec<DutDevi
                             continue;
                         }
                     }
+                    let intervals = day_programming_intervals(unit_id, day, intervals, globs);
                     let (total_on, total_off, total_on_outside_programming, seconds_must_be_off, percentual_outside_programming) = calculate_l1_states(&response_data.lcmp, intervals.clone());
-                    let programming = concatenate_intervals(intervals.clone());
+                    let programming = concatenate_intervals(intervals);
                     let percentual_outside_programming_converted = Decimal::from_f64(percentual_outside_programming).unwrap_or(Decimal::new(0, 0));
                     insert_device_l1_totalization(dut_device.asset_id, dut_device.machine_id.unwrap(), &dut_device.device_code, total_on, total_off, total_on_outside_programming, seconds_must_be_off, percentual_outside_programming_converted, &programming, day, &globs);       
                 } else {
@@ -192,8 +193,9 @@ pub async fn process_dac_common(
     if let Some(ref intervals) = dac_device.machine_autom_intervals {
         if !intervals.is_empty() {
             if let Ok(asset_id) = verify_insert_update_asset(unit_id, dac_device.asset_id.unwrap(), &dac_device.device_code, &dac_device.asset_name.clone().unwrap(), dac_device.machine_id.unwrap(), &globs) {
+                let intervals = day_programming_intervals(unit_id, day, intervals, globs);
                 let (total_on, total_off, total_on_outside_programming, seconds_must_be_off, percentual_outside_programming) = calculate_l1_states(&response_data.lcmp, intervals.clone());
-                let programming = concatenate_intervals(intervals.clone());
+                let programming = concatenate_intervals(intervals);
                 let percentual_outside_programming_converted = Decimal::from_f64(percentual_outside_programming).unwrap_or(Decimal::new(0, 0));
                 insert_device_l1_totalization(dac_device.asset_id, dac_device.machine_id.unwrap(), &dac_device.device_code, total_on, total_off, total_on_outside_programming, seconds_must_be_off, percentual_outside_programming_converted, &programming, day, &globs);
             } else {
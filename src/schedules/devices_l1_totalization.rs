@@ -5,6 +5,9 @@ use rust_decimal::{prelude::FromPrimitive, Decimal};
 
 use crate::{db::entities::{devices_l1_totalization_hist::insert_data_device_l1_totalization_hist, assets::{insert_data_asset, update_asset, get_asset}}, models::{database_models::{devices_l1_totalization_hist::DevicesL1TotalizationHist, assets::Assets}, external_models::device::{DacDevice, DutDevice}},GlobalVars};
 
+use crate::calendar::unit_calendar::unit_non_working_days;
+use crate::models::external_models::device::MachineAutomInterval;
+
 use super::devices::{process_dacs_devices, process_duts_devices};
 
 pub async fn process_l1_totalization_dacs(unit_id: i32, day: &str, dacs_devices: &Option<Vec<DacDevice>>, client_minutes_to_check_offline: Option<i32>,  globs: &Arc<GlobalVars>) {
@@ -19,6 +22,17 @@ pub async fn process_l1_totalization_duts(unit_id: i32, day: &str, duts_devices:
     }
 }
 
+/// Programação considerada no dia: em feriados e dias sem expediente da unidade a máquina deve ficar desligada o dia todo
+pub fn day_programming_intervals(unit_id: i32, day: &str, intervals: &[MachineAutomInterval], globs: &Arc<GlobalVars>) -> Vec<MachineAutomInterval> {
+    let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") else { return intervals.to_vec() };
+
+    if unit_non_working_days(unit_id, date, date, globs).contains(&date) {
+        return vec![MachineAutomInterval { seconds_start: 0, seconds_end: 86400, must_be_on: false }];
+    }
+
+    intervals.to_vec()
+}
+
 pub fn insert_device_l1_totalization(
     asset_reference_id: Option<i32>,
     machine_reference_id: i32,
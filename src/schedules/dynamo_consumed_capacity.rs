@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+use crate::{db::{config::consumed_capacity::{restore_consumed_capacity, take_consumed_capacity}, entities::dynamo_consumed_capacity_hist::insert_data_dynamo_consumed_capacity}, models::database_models::dynamo_consumed_capacity_hist::DynamoConsumedCapacityHist, schedules::scheduler::write_to_log_file_thread, GlobalVars};
+
+// Intervalo entre gravações dos totais acumulados, para que reprocessamentos via HTTP não percam leituras num reinício
+const FLUSH_INTERVAL_SECONDS: u64 = 300;
+
+pub async fn start_consumed_capacity_flush(globs: &Arc<GlobalVars>) {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(FLUSH_INTERVAL_SECONDS)).await;
+        flush_dynamo_consumed_capacity(globs);
+    }
+}
+
+pub fn flush_dynamo_consumed_capacity(globs: &Arc<GlobalVars>) {
+    for (key, capacity) in take_consumed_capacity() {
+        let history = DynamoConsumedCapacityHist {
+            table_name: key.table_name.clone(),
+            pipeline: key.pipeline.clone(),
+            client_reference_id: key.client_reference_id,
+            record_date: key.record_date,
+            consumed_rcu: Decimal::from_f64(capacity.consumed_rcu).unwrap_or(Decimal::new(0, 0)).round_dp(3),
+            cache_hits: capacity.cache_hits,
+            cache_hit_rcu: Decimal::from_f64(capacity.cache_hit_rcu).unwrap_or(Decimal::new(0, 0)).round_dp(3),
+        };
+
+        // Os totais voltam para o acumulador e são gravados no próximo flush
+        if let Err(err) = insert_data_dynamo_consumed_capacity(history, globs) {
+            write_to_log_file_thread(&format!("Erro ao gravar capacidade consumida do DynamoDB, {:?}: {}", key, err), 0, "ERROR");
+            restore_consumed_capacity(key, capacity);
+        }
+    }
+}
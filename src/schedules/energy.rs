@@ -11,14 +11,24 @@ use serde_json;
 use crate::db::entities::energy_consumption_forecast::{get_energy_consumption_target, insert_data_energy_consumption_forecast, process_energy_forecast_view, GetEnergyTarget};
 use crate::compression::common_func::check_amount_minutes_offline;
 use crate::schedules::device_disponibility::insert_device_disponibility_hist;
+use crate::schedules::energy_reactive::process_reactive_energy;
+use crate::schedules::demand_overrun::process_demand_overruns;
+use crate::schedules::load_anomalies::process_load_anomalies;
+use crate::schedules::power_quality::{process_power_quality, POWER_QUALITY_PARAMS};
+use crate::schedules::energy_gap_fill::{CircuitEnergySettings, GapFillStrategy, FILL_STRATEGY_MEASURED};
+use crate::schedules::meter_discontinuities::{rebase_energy_counter, save_meter_discontinuities};
 use crate::db::entities::energy_demand_minutes_hist::insert_data_demand;
-use crate::db::entities::energy_hist::{get_energy_consumption_average, get_last_valid_consumption, get_total_days_unit_with_consumption, GetEnergyAverage};
+use crate::db::entities::energy_hist::{get_circuit_hour_consumption, get_last_valid_consumption, get_total_days_unit_with_consumption};
+use crate::db::entities::client_energy_forecast_models::get_circuit_forecast_model;
+use crate::forecasting::energy_consumption::{ForecastModel, FORECAST_HISTORY_WEEKS, FORECAST_WEEKS_AHEAD};
+use crate::calendar::unit_calendar::circuit_non_working_days;
+use crate::schedules::virtual_circuits::process_virtual_circuits;
 use crate::db::entities::energy_monthly_consumption_target::{insert_data_energy_monthly_consumption_target, monthly_target_exists_for_unit};
 use crate::models::database_models::energy_monthly_consumption_target;
 use crate::schedules::scheduler::write_to_log_file_thread;
 use crate::telemetry_payloads::energy::padronized::EnergyDemandTelemetry;
 use crate::http::structs::energy::{GetDayEnergyConsumptionResponse, GetHourEnergyConsumptionResponse, GetLastValidConsumption, ParamsGetTotalDaysConsumptionUnit};
-use crate::{app_history::{compiler_queues::{task_queue_manager, CompilationRequest}, energy_hist::{CompiledEnergyData, EnergyDataStruct, EnergyHist, EnergyHistParams, HoursCompiledEnergyData}}, db::entities::{electric_circuits::{get_electric_circuit, insert_data_electric_circuits, update_electric_circuit}, energy_hist::insert_data_energy}, http::structs::energy::GetEnergyConsumptionResponse, models::{database_models::electric_circuits::ElectricCircuit, external_models::device::EnergyDevice}, telemetry_payloads::energy::padronized::PadronizedEnergyTelemetry, GlobalVars};
+use crate::{app_history::{compiler_queues::{task_queue_manager, CompilationRequest}, energy_hist::{CompiledEnergyData, EnergyDataStruct, EnergyHist, EnergyHistParams, HoursCompiledEnergyData}}, db::entities::{electric_circuits::{get_electric_circuit, get_electric_circuit_unit_id, insert_data_electric_circuits, update_electric_circuit, update_electric_circuit_parent}, energy_hist::insert_data_energy}, http::structs::energy::GetEnergyConsumptionResponse, models::{database_models::electric_circuits::ElectricCircuit, external_models::device::EnergyDevice}, telemetry_payloads::energy::padronized::PadronizedEnergyTelemetry, GlobalVars};
 use crate::models::database_models::{energy_consumption_forecast, energy_demand_minutes_hist, energy_hist};
 
 #[derive(Debug, Serialize)]
@@ -44,7 +54,12 @@ pub async fn process_energy_devices(unit_id: i32, energy_devices: &Option<Vec<En
     if let Some(devices) = energy_devices {
 
         for energy_device in devices {
-            let params = EnergyHistParams::parse_parameters(energy_device, day);
+            let mut params = EnergyHistParams::parse_parameters(energy_device, day);
+            if globs.configfile.POWER_QUALITY.is_some() {
+                if let Some(params_list) = params.params.as_mut() {
+                    params_list.extend(POWER_QUALITY_PARAMS.iter().map(|param| param.to_string()));
+                }
+            }
             let mut params_clone = params.clone();
             
             let response = match task_queue_manager(CompilationRequest::EnergyQuery(params), globs).await {
@@ -68,8 +83,17 @@ pub async fn process_energy_devices(unit_id: i32, energy_devices: &Option<Vec<En
             match verify_insert_update_electric_circuits(unit_id, energy_device, globs) {
                 Ok(electric_circuit_id) => {
                     if !only_demand.unwrap_or(false) {
-                        let compiled_energy_data = compile_energy_data(&response_data.clone(), day);
+                        let energy_settings = CircuitEnergySettings::load(electric_circuit_id, day, globs);
+                        let mut rebased_data = response_data.clone();
+                        let discontinuities = rebase_energy_counter(&mut rebased_data.data, &energy_settings);
+                        save_meter_discontinuities(electric_circuit_id, day, &discontinuities, globs);
+
+                        let compiled_energy_data = compile_energy_data(&rebased_data, day, &energy_settings);
                         insert_data_energy_per_hour(electric_circuit_id, &compiled_energy_data, &mut params_clone, unit_id, energy_device, globs).await;
+                        process_reactive_energy(electric_circuit_id, &rebased_data.data, day, globs);
+                        if let Some(power_quality_config) = &globs.configfile.POWER_QUALITY {
+                            process_power_quality(electric_circuit_id, &response_data.data, day, power_quality_config, globs);
+                        }
                     }
 
                     let grouped_telemetries_demand = group_telemetries_by_15_minutes(response_data.data.clone(), day);
@@ -81,11 +105,15 @@ pub async fn process_energy_devices(unit_id: i32, energy_devices: &Option<Vec<En
                 }
             };
         }
+        sync_electric_circuits_hierarchy(devices, globs);
+        process_virtual_circuits(unit_id, day, globs);
         calc_consumption_monthly_target(unit_id, day, devices.to_vec(), globs);
+        process_demand_overruns(unit_id, day, globs);
+        process_load_anomalies(unit_id, day, globs);
     }
 }
 
-fn compile_energy_data(energy_hist: &EnergyHist, day: &str) -> CompiledEnergyData {
+fn compile_energy_data(energy_hist: &EnergyHist, day: &str, settings: &CircuitEnergySettings) -> CompiledEnergyData {
     let start_time = NaiveDateTime::parse_from_str(&format!("{}T00:00:00", day), "%Y-%m-%dT%H:%M:%S").unwrap();
     let end_time = start_time + Duration::days(1);
     let sample_with_params = energy_hist.data.iter().find(|&x| {
@@ -98,7 +126,7 @@ fn compile_energy_data(energy_hist: &EnergyHist, day: &str) -> CompiledEnergyDat
 
     let mut data_struct = generate_data_struct(day, sample_with_params);
 
-    format_data(&mut data_struct, energy_hist)
+    format_data(&mut data_struct, energy_hist, settings)
 }
 
 fn generate_data_struct(day: &str, sample: Option<&PadronizedEnergyTelemetry>) -> EnergyDataStruct {
@@ -115,11 +143,15 @@ fn generate_data_struct(day: &str, sample: Option<&PadronizedEnergyTelemetry>) -
     result
 }
 
-fn format_data(data_struct: &mut EnergyDataStruct, energy_hist: &EnergyHist) -> CompiledEnergyData {
+fn format_data(data_struct: &mut EnergyDataStruct, energy_hist: &EnergyHist, settings: &CircuitEnergySettings) -> CompiledEnergyData {
     let data = &energy_hist.data;
 
     for hist in data {
-        if hist.en_at_tri.is_some() {
+        if hist.en_at_tri_invalid {
+            if let Some(timestamp) = hist.timestamp {
+                data_struct.invalid_hours.insert(format!("{:02}", timestamp.time().hour()));
+            }
+        } else if hist.en_at_tri.is_some() {
             let (hour) = {
                 let timestamp = hist.timestamp.unwrap();
                 let hour = timestamp.time().hour();
@@ -164,14 +196,14 @@ fn format_data(data_struct: &mut EnergyDataStruct, energy_hist: &EnergyHist) ->
             None
         };
 
-        let hour_data_vec = if hour_data_vec.contains(&-1.0) {
+        let hour_data_vec = if data_struct.invalid_hours.contains(*hour) {
             vec![]
         } else {
             hour_data_vec.clone()
         };
         
         let next_hour_data_vec = match next_hour_data_vec {
-            Some(vec) => if vec.contains(&-1.0) {
+            Some(vec) => if data_struct.invalid_hours.contains(&format!("{:02}", hour_index + 1)) {
                 Some(vec![])
             } else {
                 Some(vec)
@@ -205,7 +237,8 @@ fn format_data(data_struct: &mut EnergyDataStruct, energy_hist: &EnergyHist) ->
             last_en_at_tri_hour: last_en_at_tri,
             first_en_at_tri_hour: hour_data_vec.first().copied().unwrap_or(0.0),
             is_measured_consumption: false,
-            is_valid_consumption: verify_is_valid_consumption(total_measured, hour_data_vec, next_hour_data_vec),
+            is_valid_consumption: verify_is_valid_consumption(total_measured, hour_data_vec, next_hour_data_vec, settings),
+            fill_strategy: FILL_STRATEGY_MEASURED.to_string(),
         });
 
         let mut total_measured = 0.0;
@@ -237,12 +270,15 @@ fn format_data(data_struct: &mut EnergyDataStruct, energy_hist: &EnergyHist) ->
                     let next_first_en_at_tri = obj.hours[currentPosition].first_en_at_tri_hour;
 
                     let total_consumption = next_first_en_at_tri - last_en_at_tri;
-                    let calculated_total_measured = total_consumption / (zeroPositions.len() as f64);
-
-                    for &i in &zeroPositions {
-                        formatedEnergyData.hours[i].total_measured = calculated_total_measured;
-                        formatedEnergyData.hours[i].is_measured_consumption = true;
-                        formatedEnergyData.hours[i].is_valid_consumption = calculated_total_measured >= 0.0 && calculated_total_measured < 1000.0;
+                    let gap_hours: Vec<u32> = zeroPositions.iter().map(|&i| obj.hours[i].hour.parse().unwrap_or(0)).collect();
+
+                    if let Some((filled_values, strategy)) = settings.fill_gap(total_consumption, &gap_hours) {
+                        for (&i, calculated_total_measured) in zeroPositions.iter().zip(filled_values) {
+                            formatedEnergyData.hours[i].total_measured = calculated_total_measured;
+                            formatedEnergyData.hours[i].is_measured_consumption = true;
+                            formatedEnergyData.hours[i].is_valid_consumption = settings.is_plausible_consumption(calculated_total_measured);
+                            formatedEnergyData.hours[i].fill_strategy = strategy.as_str().to_string();
+                        }
                     }
     
                     zeroPositions.clear();
@@ -253,11 +289,20 @@ fn format_data(data_struct: &mut EnergyDataStruct, energy_hist: &EnergyHist) ->
         }
     }
 
+    // Horas inválidas que não foram preenchidas ficam registradas como não preenchidas
+    for hour in formatedEnergyData.hours.iter_mut() {
+        if !hour.is_valid_consumption && !hour.is_measured_consumption {
+            hour.fill_strategy = GapFillStrategy::LeaveEmpty.as_str().to_string();
+        }
+    }
+
     formatedEnergyData
 }
 
 async fn insert_data_energy_per_hour(electric_circuit_id: i32, energy_data: &CompiledEnergyData, parameters: &mut EnergyHistParams, unit_id: i32, energy_device: &EnergyDevice, globs: &Arc<GlobalVars>) {
     let mut first_non_zero_history: Option<energy_hist::EnergyHist> = None;
+    let day = NaiveDate::parse_from_str(&energy_data.day, "%Y-%m-%d").unwrap_or_default();
+    let forecast_settings = CircuitForecastSettings::load(electric_circuit_id, day, day, globs);
 
     for hour in &energy_data.hours {
         let total_cons = format!("{:.2}", &hour.total_measured);
@@ -268,6 +313,7 @@ async fn insert_data_energy_per_hour(electric_circuit_id: i32, energy_data: &Com
             record_date: NaiveDateTime::parse_from_str(&format!("{} {}:00:00", energy_data.day, hour.hour), "%Y-%m-%d %H:%M:%S").unwrap_or_default(),
             is_measured_consumption: hour.is_measured_consumption,
             is_valid_consumption: hour.is_valid_consumption,
+            fill_strategy: hour.fill_strategy.clone(),
         };
 
         if history.is_valid_consumption && !history.is_measured_consumption && first_non_zero_history.is_none() {
@@ -276,7 +322,7 @@ async fn insert_data_energy_per_hour(electric_circuit_id: i32, energy_data: &Com
         
         insert_data_energy(history.clone(), globs);
 
-        calc_energy_consumption_forecast(history.electric_circuit_id.clone(), history.record_date.clone(), globs)
+        calc_energy_consumption_forecast(history.electric_circuit_id.clone(), history.record_date.clone(), &forecast_settings, globs)
     }
 
     if first_non_zero_history.is_some() {
@@ -287,8 +333,8 @@ async fn insert_data_energy_per_hour(electric_circuit_id: i32, energy_data: &Com
     }
 }
 
-fn verify_is_valid_consumption(consumption: f64, hour_data_vec: Vec<f64>, next_hour_data_vec: Option<Vec<f64>>) -> bool {
-    if consumption < 0.0 || consumption > 1000.0 {
+fn verify_is_valid_consumption(consumption: f64, hour_data_vec: Vec<f64>, next_hour_data_vec: Option<Vec<f64>>, settings: &CircuitEnergySettings) -> bool {
+    if !settings.is_plausible_consumption(consumption) {
         false
     } else if hour_data_vec.is_empty() || (hour_data_vec.len() == 1 && next_hour_data_vec.unwrap_or([].to_vec()).is_empty()) {
         false
@@ -323,6 +369,60 @@ fn verify_insert_update_electric_circuits(unit_id: i32, energy_device: &EnergyDe
     }
 }
 
+/// Atualiza o circuito pai de cada circuito conforme os medidores da unidade. O pai precisa ser outro circuito
+/// da mesma unidade e sem formar ciclo; caso contrário o circuito fica como raiz
+fn sync_electric_circuits_hierarchy(energy_devices: &[EnergyDevice], globs: &Arc<GlobalVars>) {
+    let unit_circuits: HashSet<i32> = energy_devices.iter().map(|device| device.electric_circuit_id).collect();
+    let parents: HashMap<i32, i32> = energy_devices.iter()
+        .filter_map(|device| Some((device.electric_circuit_id, device.parent_electric_circuit_id?)))
+        .collect();
+
+    for energy_device in energy_devices {
+        let Ok(Some((electric_circuit_id, _))) = get_electric_circuit(energy_device.electric_circuit_id, globs) else {
+            continue;
+        };
+
+        let parent_id = match energy_device.parent_electric_circuit_id {
+            Some(parent_reference_id) if is_valid_parent_circuit(energy_device.electric_circuit_id, parent_reference_id, &unit_circuits, &parents) => {
+                match get_electric_circuit(parent_reference_id, globs) {
+                    Ok(parent) => parent.map(|(id, _)| id),
+                    // Sem conseguir ler o pai, o circuito mantém o pai atual em vez de virar raiz
+                    Err(err) => {
+                        write_to_log_file_thread(&format!("Erro ao obter circuito pai {} do circuito {}: {}", parent_reference_id, energy_device.electric_circuit_id, err), 0, "ERROR");
+                        continue;
+                    }
+                }
+            },
+            Some(parent_reference_id) => {
+                write_to_log_file_thread(&format!("Circuito pai {} inválido para o circuito {}, mantido como raiz", parent_reference_id, energy_device.electric_circuit_id), 0, "ERROR");
+                None
+            },
+            None => None,
+        };
+
+        if let Err(err) = update_electric_circuit_parent(electric_circuit_id, parent_id, globs) {
+            write_to_log_file_thread(&format!("Erro ao atualizar circuito pai do circuito {}: {}", energy_device.electric_circuit_id, err), 0, "ERROR");
+        }
+    }
+}
+
+fn is_valid_parent_circuit(electric_circuit_id: i32, parent_id: i32, unit_circuits: &HashSet<i32>, parents: &HashMap<i32, i32>) -> bool {
+    if parent_id == electric_circuit_id || !unit_circuits.contains(&parent_id) {
+        return false;
+    }
+
+    let mut visited = HashSet::from([electric_circuit_id]);
+    let mut current = parent_id;
+    while let Some(next) = parents.get(&current) {
+        if !visited.insert(current) || *next == electric_circuit_id {
+            return false;
+        }
+        current = *next;
+    }
+
+    true
+}
+
 pub fn fill_consumption_by_day(consumption_day_list: Vec<GetDayEnergyConsumptionResponse>, start_date: NaiveDate, end_date: NaiveDate, isDielUser: bool) -> Vec<EnergyConsumptionPerDay> {
     let mut existing_dates_by_circuit: HashMap<i32, HashSet<NaiveDate>> = HashMap::new();
 
@@ -492,13 +592,13 @@ async fn verify_update_energy_consumption_aux(parameters: &mut EnergyHistParams,
 
 
     let last_valid_consumption_telemetries = verify_telemetries(parameters, globs).await?;
-    let last_valid_consumption_telemetries_data_filtered: Vec<PadronizedEnergyTelemetry> = last_valid_consumption_telemetries.data.into_iter().filter(|t| t.en_at_tri.unwrap_or(-1.0) >= 0.0).collect();
+    let last_valid_consumption_telemetries_data_filtered: Vec<PadronizedEnergyTelemetry> = last_valid_consumption_telemetries.data.into_iter().filter(|t| t.en_at_tri.is_some()).collect();
 
     let actual_telemetries = verify_telemetries(&mut parameters_clone, globs).await?;
-    let actual_telemetries_data_filtered: Vec<PadronizedEnergyTelemetry> = actual_telemetries.data.into_iter().filter(|t| t.en_at_tri.unwrap_or(-1.0) >= 0.0).collect();
+    let actual_telemetries_data_filtered: Vec<PadronizedEnergyTelemetry> = actual_telemetries.data.into_iter().filter(|t| t.en_at_tri.is_some()).collect();
     
-    // inicializando como -1, para casos onde após os novos filtros, não encontre telemetrias válidas
-    let mut difference_consumption = -1.0;
+    // None para casos onde após os novos filtros, não encontre telemetrias válidas
+    let mut difference_consumption = None;
 
     // casos em que as telemetrias foram consideradas válidas antes das novas validações,
     // ou seja, após os filtros aplicados nessas telemetrias, 
@@ -506,27 +606,37 @@ async fn verify_update_energy_consumption_aux(parameters: &mut EnergyHistParams,
     if !actual_telemetries_data_filtered.is_empty() && !last_valid_consumption_telemetries_data_filtered.is_empty() {
         let actual_consumption = actual_telemetries_data_filtered[0].en_at_tri.unwrap_or(0.0);
         let last_valid_consumption = last_valid_consumption_telemetries_data_filtered[last_valid_consumption_telemetries_data_filtered.len() - 1].en_at_tri.unwrap_or(0.0);
-        difference_consumption = actual_consumption - last_valid_consumption;
+        difference_consumption = Some(actual_consumption - last_valid_consumption);
 
     }
     
     let hours_without_consumption = (actual_history.record_date - history_clone.record_date).num_hours() - 1;
-    let formatted_consumption = difference_consumption.to_f64().unwrap_or(0.0) / hours_without_consumption as f64;
+    let gap_dates: Vec<NaiveDateTime> = (1..hours_without_consumption + 1).map(|i| history_clone.record_date + Duration::hours(i)).collect();
+    let gap_hours: Vec<u32> = gap_dates.iter().map(|date_time| date_time.hour()).collect();
 
-    for i in 1..hours_without_consumption + 1 {
-        let date_time = history_clone.record_date + Duration::hours(i.into());
-        let is_valid_consumption = formatted_consumption >= 0.0 && formatted_consumption < 1000.0;
+    let settings = CircuitEnergySettings::load(electric_circuit_id, &actual_history.record_date.date().to_string(), globs);
+    let filled_gap = difference_consumption.and_then(|difference| settings.fill_gap(difference, &gap_hours));
+    let forecast_settings = CircuitForecastSettings::load(electric_circuit_id, history_clone.record_date.date(), actual_history.record_date.date(), globs);
+
+    for (i, date_time) in gap_dates.into_iter().enumerate() {
+        let (formatted_consumption, fill_strategy) = match &filled_gap {
+            Some((values, strategy)) => (Some(values[i]), *strategy),
+            None => (None, GapFillStrategy::LeaveEmpty),
+        };
+        let consumption = formatted_consumption.filter(|value| settings.is_plausible_consumption(*value));
+        let is_valid_consumption = consumption.is_some();
         let history = energy_hist::EnergyHist {
             electric_circuit_id,
-            consumption: if is_valid_consumption { Decimal::from_f64_retain(formatted_consumption).unwrap_or(Decimal::from(0)).round_dp(3) } else { Decimal::from(0) },
+            consumption: consumption.map(|value| Decimal::from_f64_retain(value).unwrap_or(Decimal::from(0)).round_dp(3)).unwrap_or(Decimal::from(0)),
             record_date: date_time,
-            is_measured_consumption: true,
+            is_measured_consumption: filled_gap.is_some(),
             is_valid_consumption,
+            fill_strategy: fill_strategy.as_str().to_string(),
         };
 
         insert_data_energy(history.clone(), globs);
 
-        calc_energy_consumption_forecast(history.electric_circuit_id.clone(), history.record_date.clone(), globs)
+        calc_energy_consumption_forecast(history.electric_circuit_id.clone(), history.record_date.clone(), &forecast_settings, globs)
     }
 
     Ok(())
@@ -662,7 +772,7 @@ async fn verify_telemetries_saved_data(parameters: &mut EnergyHistParams, start_
         },
     };
 
-    let response_data_filtered: Vec<PadronizedEnergyTelemetry> = response_data.data.into_iter().filter(|t| t.en_at_tri.unwrap_or(-1.0) >= 0.0).collect();
+    let response_data_filtered: Vec<PadronizedEnergyTelemetry> = response_data.data.into_iter().filter(|t| t.en_at_tri.is_some()).collect();
 
     if response_data_filtered.is_empty() {
         return Err("Não foi possível encontrar telemetrias válidas".into());
@@ -677,6 +787,12 @@ async fn verify_telemetries_saved_data(parameters: &mut EnergyHistParams, start_
         date_array.push(current_date.format("%Y-%m-%d").to_string());
         current_date += Duration::days(1);
     }
+
+    // Os circuitos virtuais da unidade dependem do consumo regravado e precisam ser recalculados junto
+    let unit_id = get_electric_circuit_unit_id(electric_circuit_id, globs).unwrap_or_else(|err| {
+        write_to_log_file_thread(&format!("Erro ao obter unidade do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+        None
+    });
     
     for day in date_array {
         let mut response_data_clone = response_data.clone();
@@ -697,7 +813,11 @@ async fn verify_telemetries_saved_data(parameters: &mut EnergyHistParams, start_
         let grouped_averages = calculate_group_telemetries(&grouped_telemetries_demand);
         insert_demand_hist(grouped_averages, electric_circuit_id, globs);
         
-        let compiled_energy_data = compile_energy_data(&response_data_clone, &day);
+        let energy_settings = CircuitEnergySettings::load(electric_circuit_id, &day, globs);
+        let discontinuities = rebase_energy_counter(&mut response_data_clone.data, &energy_settings);
+        save_meter_discontinuities(electric_circuit_id, &day, &discontinuities, globs);
+        let compiled_energy_data = compile_energy_data(&response_data_clone, &day, &energy_settings);
+        let forecast_settings = CircuitForecastSettings::load(electric_circuit_id, start_of_day.date(), start_of_day.date(), globs);
         for hour_data in compiled_energy_data.hours {
             let total_cons = format!("{:.2}", &hour_data.total_measured);
 
@@ -707,6 +827,7 @@ async fn verify_telemetries_saved_data(parameters: &mut EnergyHistParams, start_
                 record_date: NaiveDateTime::parse_from_str(&format!("{} {}:00:00", day, hour_data.hour), "%Y-%m-%d %H:%M:%S").unwrap_or_default(),
                 is_measured_consumption: hour_data.is_measured_consumption,
                 is_valid_consumption: hour_data.is_valid_consumption,
+                fill_strategy: hour_data.fill_strategy.clone(),
             };
 
             if history.is_valid_consumption && !history.is_measured_consumption && first_non_zero_history.is_none() {
@@ -715,7 +836,11 @@ async fn verify_telemetries_saved_data(parameters: &mut EnergyHistParams, start_
 
             insert_data_energy(history.clone(), globs);
 
-            calc_energy_consumption_forecast(history.electric_circuit_id.clone(), history.record_date.clone(), globs)
+            calc_energy_consumption_forecast(history.electric_circuit_id.clone(), history.record_date.clone(), &forecast_settings, globs)
+        }
+
+        if let Some(unit_id) = unit_id {
+            process_virtual_circuits(unit_id, &day, globs);
         }
 
         // caso tenha buracos entre os dados de saved_data
@@ -763,38 +888,56 @@ fn verify_hours_online(energy_hist: EnergyHist, client_minutes_to_check_offline:
     insert_device_disponibility_hist(unit_id, Decimal::from_f64_retain(hours_online).unwrap_or(Decimal::new(0,0)), day, device_code, globs);
 }
 
-fn calc_energy_consumption_forecast(electric_circuit_id: i32, date: NaiveDateTime, globs: &Arc<GlobalVars>) {
-    let consumption_forecast = get_energy_consumption_average(GetEnergyAverage {
-        date: date.clone().format("%Y-%m-%d %H:%M:%S").to_string(),
-        electCircuitId: electric_circuit_id.clone()
-    }, globs);
-
-    let mut date_forecast = date.clone();
-
-    date_forecast = date_forecast + Duration::days(7);
-
-    if consumption_forecast.is_ok() {
-        let consumption = consumption_forecast.unwrap().consumption_average.clone();
-        for _ in 0..5 {
-            let _ = insert_data_energy_consumption_forecast(energy_consumption_forecast::EnergyConsumptionForecast { 
-                electric_circuit_id: electric_circuit_id.clone(), 
-                consumption_forecast: consumption, 
-                record_date: date_forecast.clone()
-            }, globs);
+/// Modelo de previsão e dias não úteis do circuito, resolvidos uma vez para todas as horas gravadas entre `first_day` e `last_day`
+pub struct CircuitForecastSettings {
+    model: ForecastModel,
+    holidays: HashSet<NaiveDate>,
+}
 
-            date_forecast = date_forecast + Duration::days(7);
+impl CircuitForecastSettings {
+    pub fn load(electric_circuit_id: i32, first_day: NaiveDate, last_day: NaiveDate, globs: &Arc<GlobalVars>) -> Self {
+        let client_model = get_circuit_forecast_model(electric_circuit_id, globs).unwrap_or_else(|err| {
+            write_to_log_file_thread(&format!("Erro ao obter modelo de previsão do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+            None
+        });
 
+        // Cobre o histórico usado pela primeira hora e as semanas previstas a partir da última
+        CircuitForecastSettings {
+            model: ForecastModel::resolve(client_model.as_deref(), globs.configfile.ENERGY_FORECAST_MODEL.as_deref()),
+            holidays: circuit_non_working_days(
+                electric_circuit_id,
+                first_day - Duration::weeks(FORECAST_HISTORY_WEEKS),
+                last_day + Duration::weeks(FORECAST_WEEKS_AHEAD),
+                globs,
+            ),
         }
-    } else {
-        for _ in 0..5 {
-            let _ = insert_data_energy_consumption_forecast(energy_consumption_forecast::EnergyConsumptionForecast { 
-                electric_circuit_id: electric_circuit_id.clone(), 
-                consumption_forecast: Decimal::new(0, 0), 
-                record_date: date_forecast.clone()
-            }, globs);
-
-            date_forecast = date_forecast + Duration::days(7);
-        }
+    }
+}
+
+pub fn calc_energy_consumption_forecast(electric_circuit_id: i32, date: NaiveDateTime, settings: &CircuitForecastSettings, globs: &Arc<GlobalVars>) {
+    let history_start = date - Duration::weeks(FORECAST_HISTORY_WEEKS);
+    let history = get_circuit_hour_consumption(
+        electric_circuit_id,
+        &history_start.format("%Y-%m-%d %H:%M:%S").to_string(),
+        &date.format("%Y-%m-%d %H:%M:%S").to_string(),
+        Some(date.hour() as i32),
+        globs,
+    ).unwrap_or_default();
+
+    let forecaster = settings.model.forecaster();
+
+    for week in 1..=FORECAST_WEEKS_AHEAD {
+        let date_forecast = date + Duration::weeks(week);
+        let consumption = forecaster.forecast(&history, &settings.holidays, date_forecast)
+            .and_then(Decimal::from_f64_retain)
+            .map(|consumption| consumption.round_dp(2))
+            .unwrap_or(Decimal::new(0, 0));
+
+        let _ = insert_data_energy_consumption_forecast(energy_consumption_forecast::EnergyConsumptionForecast { 
+            electric_circuit_id, 
+            consumption_forecast: consumption, 
+            record_date: date_forecast
+        }, globs);
     }
 }
 
@@ -874,6 +1017,7 @@ fn calculate_and_insert_monthly_target(
                         unit_id,
                         consumption_target: consumption_average.consumption_target,
                         date_forecast: next_day.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+                        is_manual: false,
                     },
                     globs,
                 );
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::db::entities::electric_circuits::get_electric_circuit_energy_settings;
+use crate::db::entities::energy_hist::get_circuit_hour_profile;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+/// Origem do consumo de uma hora medida diretamente pelo medidor
+pub const FILL_STRATEGY_MEASURED: &str = "MEASURED";
+
+/// Consumo máximo plausível em uma hora (kWh), usado quando o circuito não tem limite próprio
+pub const DEFAULT_MAX_HOUR_CONSUMPTION: f64 = 1000.0;
+
+/// Origem das horas gravadas antes da coluna fill_strategy (NULL): as horas estimadas eram sempre
+/// preenchidas dividindo o consumo igualmente e as inválidas ficavam vazias
+pub fn legacy_fill_strategy(is_measured_consumption: bool, is_valid_consumption: bool) -> &'static str {
+    if is_measured_consumption {
+        GapFillStrategy::EvenSplit.as_str()
+    } else if !is_valid_consumption {
+        GapFillStrategy::LeaveEmpty.as_str()
+    } else {
+        FILL_STRATEGY_MEASURED
+    }
+}
+
+/// Como distribuir o consumo de um intervalo sem leituras válidas entre as horas do intervalo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GapFillStrategy {
+    /// Divide a diferença do medidor igualmente entre as horas
+    EvenSplit,
+    /// Distribui a diferença proporcionalmente ao dia típico do circuito
+    ProfileWeighted,
+    /// Não preenche: as horas ficam inválidas
+    LeaveEmpty,
+}
+
+impl GapFillStrategy {
+    pub fn parse(strategy: &str) -> Option<Self> {
+        match strategy.to_uppercase().as_str() {
+            "EVEN_SPLIT" => Some(GapFillStrategy::EvenSplit),
+            "PROFILE_WEIGHTED" => Some(GapFillStrategy::ProfileWeighted),
+            "LEAVE_EMPTY" => Some(GapFillStrategy::LeaveEmpty),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GapFillStrategy::EvenSplit => "EVEN_SPLIT",
+            GapFillStrategy::ProfileWeighted => "PROFILE_WEIGHTED",
+            GapFillStrategy::LeaveEmpty => "LEAVE_EMPTY",
+        }
+    }
+}
+
+/// Configuração de consumo horário do circuito: estratégia de preenchimento, limite de plausibilidade,
+/// valor de virada do registrador e, para a estratégia por perfil, o consumo médio de cada hora do dia.
+#[derive(Debug, Clone)]
+pub struct CircuitEnergySettings {
+    pub gap_fill_strategy: GapFillStrategy,
+    pub max_hour_consumption: f64,
+    pub hour_profile: Option<[f64; 24]>,
+    pub register_rollover_value: Option<f64>,
+}
+
+impl Default for CircuitEnergySettings {
+    fn default() -> Self {
+        CircuitEnergySettings {
+            gap_fill_strategy: GapFillStrategy::EvenSplit,
+            max_hour_consumption: DEFAULT_MAX_HOUR_CONSUMPTION,
+            hour_profile: None,
+            register_rollover_value: None,
+        }
+    }
+}
+
+impl CircuitEnergySettings {
+    /// Carrega a configuração do circuito; sem estratégia no circuito, usa ENERGY_GAP_FILL_STRATEGY do configfile
+    pub fn load(electric_circuit_id: i32, day: &str, globs: &Arc<GlobalVars>) -> Self {
+        let mut settings = CircuitEnergySettings::default();
+
+        if let Some(strategy) = globs.configfile.ENERGY_GAP_FILL_STRATEGY.as_deref().and_then(GapFillStrategy::parse) {
+            settings.gap_fill_strategy = strategy;
+        }
+
+        match get_electric_circuit_energy_settings(electric_circuit_id, globs) {
+            Ok(Some((strategy, max_hour_consumption, register_rollover_value))) => {
+                if let Some(strategy) = strategy.as_deref().and_then(GapFillStrategy::parse) {
+                    settings.gap_fill_strategy = strategy;
+                }
+                if let Some(max_hour_consumption) = max_hour_consumption.and_then(|value| value.to_f64()) {
+                    settings.max_hour_consumption = max_hour_consumption;
+                }
+                settings.register_rollover_value = register_rollover_value.and_then(|value| value.to_f64());
+            },
+            Ok(None) => {},
+            Err(err) => {
+                write_to_log_file_thread(&format!("Erro ao obter configuração de consumo do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+            }
+        }
+
+        if settings.gap_fill_strategy == GapFillStrategy::ProfileWeighted {
+            settings.hour_profile = match get_circuit_hour_profile(electric_circuit_id, day, globs) {
+                Ok(rows) if !rows.is_empty() => {
+                    let mut profile = [0.0; 24];
+                    for row in rows {
+                        if let Some(slot) = profile.get_mut(row.hour as usize) {
+                            *slot = row.consumption_average.to_f64().unwrap_or(0.0).max(0.0);
+                        }
+                    }
+                    Some(profile)
+                },
+                Ok(_) => None,
+                Err(err) => {
+                    write_to_log_file_thread(&format!("Erro ao obter perfil horário do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+                    None
+                }
+            };
+        }
+
+        settings
+    }
+
+    /// Consumo negativo ou acima do limite do circuito é descartado; o próprio limite ainda é plausível
+    pub fn is_plausible_consumption(&self, consumption: f64) -> bool {
+        !(consumption < 0.0 || consumption > self.max_hour_consumption)
+    }
+
+    /// Distribui `total_consumption` entre as horas do intervalo (hora do dia de cada uma).
+    /// Retorna o consumo de cada hora e a estratégia efetivamente usada: sem perfil do circuito,
+    /// ou com perfil zerado nas horas do intervalo, a distribuição por perfil cai para a divisão igual.
+    pub fn fill_gap(&self, total_consumption: f64, gap_hours: &[u32]) -> Option<(Vec<f64>, GapFillStrategy)> {
+        if gap_hours.is_empty() {
+            return None;
+        }
+
+        match self.gap_fill_strategy {
+            GapFillStrategy::LeaveEmpty => None,
+            GapFillStrategy::ProfileWeighted => {
+                let weights: Vec<f64> = match &self.hour_profile {
+                    Some(profile) => gap_hours.iter().map(|hour| profile[*hour as usize % 24]).collect(),
+                    None => Vec::new(),
+                };
+                let weights_sum: f64 = weights.iter().sum();
+
+                if weights_sum > 0.0 {
+                    Some((weights.iter().map(|weight| total_consumption * weight / weights_sum).collect(), GapFillStrategy::ProfileWeighted))
+                } else {
+                    Some((even_split(total_consumption, gap_hours.len()), GapFillStrategy::EvenSplit))
+                }
+            },
+            GapFillStrategy::EvenSplit => Some((even_split(total_consumption, gap_hours.len()), GapFillStrategy::EvenSplit)),
+        }
+    }
+}
+
+fn even_split(total_consumption: f64, hours: usize) -> Vec<f64> {
+    vec![total_consumption / hours as f64; hours]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(gap_fill_strategy: GapFillStrategy, hour_profile: Option<[f64; 24]>) -> CircuitEnergySettings {
+        CircuitEnergySettings {
+            gap_fill_strategy,
+            hour_profile,
+            ..CircuitEnergySettings::default()
+        }
+    }
+
+    #[test]
+    fn plausible_consumption_includes_zero_and_the_circuit_limit() {
+        let settings = CircuitEnergySettings { max_hour_consumption: 50.0, ..CircuitEnergySettings::default() };
+        assert!(settings.is_plausible_consumption(0.0));
+        assert!(settings.is_plausible_consumption(50.0));
+        assert!(!settings.is_plausible_consumption(50.01));
+        assert!(!settings.is_plausible_consumption(-0.01));
+    }
+
+    #[test]
+    fn even_split_divides_the_gap_equally() {
+        let (hours, strategy) = settings(GapFillStrategy::EvenSplit, None).fill_gap(12.0, &[1, 2, 3]).unwrap();
+        assert_eq!(hours, vec![4.0, 4.0, 4.0]);
+        assert_eq!(strategy, GapFillStrategy::EvenSplit);
+    }
+
+    #[test]
+    fn leave_empty_and_empty_gaps_are_not_filled() {
+        assert!(settings(GapFillStrategy::LeaveEmpty, None).fill_gap(12.0, &[1, 2]).is_none());
+        assert!(settings(GapFillStrategy::EvenSplit, None).fill_gap(12.0, &[]).is_none());
+    }
+
+    #[test]
+    fn profile_weighted_follows_the_circuit_hour_profile() {
+        let mut profile = [1.0; 24];
+        profile[9] = 3.0;
+        profile[10] = 1.0;
+        let (hours, strategy) = settings(GapFillStrategy::ProfileWeighted, Some(profile)).fill_gap(8.0, &[9, 10]).unwrap();
+        assert_eq!(hours, vec![6.0, 2.0]);
+        assert_eq!(strategy, GapFillStrategy::ProfileWeighted);
+    }
+
+    #[test]
+    fn profile_weighted_falls_back_to_even_split_without_profile() {
+        let (hours, strategy) = settings(GapFillStrategy::ProfileWeighted, None).fill_gap(8.0, &[9, 10]).unwrap();
+        assert_eq!(hours, vec![4.0, 4.0]);
+        assert_eq!(strategy, GapFillStrategy::EvenSplit);
+
+        let (hours, strategy) = settings(GapFillStrategy::ProfileWeighted, Some([0.0; 24])).fill_gap(8.0, &[9, 10]).unwrap();
+        assert_eq!(hours, vec![4.0, 4.0]);
+        assert_eq!(strategy, GapFillStrategy::EvenSplit);
+    }
+
+    #[test]
+    fn legacy_rows_map_to_the_strategy_used_when_they_were_written() {
+        assert_eq!(legacy_fill_strategy(true, true), "EVEN_SPLIT");
+        assert_eq!(legacy_fill_strategy(false, false), "LEAVE_EMPTY");
+        assert_eq!(legacy_fill_strategy(false, true), FILL_STRATEGY_MEASURED);
+    }
+}
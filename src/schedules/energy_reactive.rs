@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+
+use crate::db::entities::energy_reactive_hist::{insert_data_reactive_day, insert_data_reactive_hour};
+use crate::models::database_models::energy_reactive_hist::{EnergyReactiveDayHist, EnergyReactiveHourHist};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::telemetry_payloads::energy::padronized::PadronizedEnergyTelemetry;
+use crate::GlobalVars;
+
+// Fator de potência de referência da ANEEL: abaixo dele o consumo reativo excedente é faturado (UFER)
+pub const UFER_REFERENCE_POWER_FACTOR: f64 = 0.92;
+
+#[derive(Debug, Default, Clone)]
+struct ReactiveHourValues {
+    active_consumption: f64,
+    reactive_consumption: f64,
+    power_factors: Vec<f64>,
+    ufer: f64,
+}
+
+/// Calcula o consumo ativo/reativo, o fator de potência médio e mínimo e a UFER de cada hora do dia
+/// e salva os históricos por hora e por dia do circuito.
+pub fn process_reactive_energy(electric_circuit_id: i32, telemetries: &[PadronizedEnergyTelemetry], day: &str, globs: &Arc<GlobalVars>) {
+    let day_date = match NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Data inválida ao calcular energia reativa: {}, {}", day, err), 0, "ERROR");
+            return;
+        }
+    };
+    let start_time = day_date.and_hms_opt(0, 0, 0).unwrap();
+
+    let mut hour_rows = Vec::new();
+    let mut day_values = ReactiveHourValues::default();
+
+    for hour in 0..24 {
+        let hour_start = start_time + Duration::hours(hour);
+        let values = match calculate_hour_values(telemetries, hour_start) {
+            Some(values) => values,
+            None => continue,
+        };
+
+        day_values.active_consumption += values.active_consumption;
+        day_values.reactive_consumption += values.reactive_consumption;
+        day_values.power_factors.extend(values.power_factors.iter());
+        day_values.ufer += values.ufer;
+
+        let (average_power_factor, min_power_factor) = power_factor_stats(&values.power_factors);
+        hour_rows.push(EnergyReactiveHourHist {
+            electric_circuit_id,
+            record_date: hour_start,
+            active_consumption: to_decimal(values.active_consumption, 3),
+            reactive_consumption: to_decimal(values.reactive_consumption, 3),
+            average_power_factor,
+            min_power_factor,
+            ufer: to_decimal(values.ufer, 3),
+        });
+    }
+
+    if hour_rows.is_empty() {
+        return;
+    }
+
+    if let Err(err) = insert_data_reactive_hour(hour_rows, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar energia reativa por hora do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+    }
+
+    let (average_power_factor, min_power_factor) = power_factor_stats(&day_values.power_factors);
+    let day_row = EnergyReactiveDayHist {
+        electric_circuit_id,
+        record_date: day_date,
+        active_consumption: to_decimal(day_values.active_consumption, 3),
+        reactive_consumption: to_decimal(day_values.reactive_consumption, 3),
+        average_power_factor,
+        min_power_factor,
+        ufer: to_decimal(day_values.ufer, 3),
+    };
+
+    if let Err(err) = insert_data_reactive_day(day_row, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar energia reativa por dia do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+    }
+}
+
+// Os medidores enviam energia acumulada: o consumo da hora é a diferença entre a primeira leitura da hora
+// e a primeira leitura da hora seguinte (ou a última leitura da própria hora, se a seguinte não existir)
+fn calculate_hour_values(telemetries: &[PadronizedEnergyTelemetry], hour_start: NaiveDateTime) -> Option<ReactiveHourValues> {
+    let hour_end = hour_start + Duration::hours(1);
+    let hour_tels: Vec<&PadronizedEnergyTelemetry> = telemetries.iter()
+        .filter(|tel| tel.timestamp.map(|ts| ts >= hour_start && ts < hour_end).unwrap_or(false))
+        .collect();
+    let next_hour_tels: Vec<&PadronizedEnergyTelemetry> = telemetries.iter()
+        .filter(|tel| tel.timestamp.map(|ts| ts >= hour_end && ts < hour_end + Duration::hours(1)).unwrap_or(false))
+        .collect();
+
+    let active_consumption = counter_difference(&hour_tels, &next_hour_tels, |tel| tel.en_at_tri)?;
+    let reactive_consumption = counter_difference(&hour_tels, &next_hour_tels, |tel| tel.en_re_tri)?;
+
+    let power_factors: Vec<f64> = hour_tels.iter()
+        .filter_map(|tel| tel.fp)
+        .map(f64::abs)
+        .filter(|fp| *fp > 0.0 && *fp <= 1.0)
+        .collect();
+
+    Some(ReactiveHourValues {
+        active_consumption,
+        reactive_consumption,
+        power_factors,
+        ufer: calculate_ufer(active_consumption, reactive_consumption),
+    })
+}
+
+fn counter_difference(hour_tels: &[&PadronizedEnergyTelemetry], next_hour_tels: &[&PadronizedEnergyTelemetry], field: fn(&PadronizedEnergyTelemetry) -> Option<f64>) -> Option<f64> {
+    let is_valid = |value: &f64| *value >= 0.0;
+    let first = hour_tels.iter().filter_map(|tel| field(tel)).find(is_valid)?;
+    let last = next_hour_tels.iter().filter_map(|tel| field(tel)).find(is_valid)
+        .or_else(|| hour_tels.iter().rev().filter_map(|tel| field(tel)).find(is_valid))?;
+
+    // Reset ou troca de medidor: a hora é descartada
+    if last < first {
+        return None;
+    }
+
+    Some(last - first)
+}
+
+/// UFER (kvarh) da hora: energia reativa excedente em relação ao fator de potência de referência de 0,92.
+/// UFER = EA * (0,92 / FP - 1), com FP = EA / sqrt(EA² + ER²), quando FP < 0,92.
+pub fn calculate_ufer(active_consumption: f64, reactive_consumption: f64) -> f64 {
+    if active_consumption <= 0.0 {
+        return 0.0;
+    }
+
+    let power_factor = active_consumption / (active_consumption.powi(2) + reactive_consumption.powi(2)).sqrt();
+    if power_factor >= UFER_REFERENCE_POWER_FACTOR {
+        return 0.0;
+    }
+
+    active_consumption * (UFER_REFERENCE_POWER_FACTOR / power_factor - 1.0)
+}
+
+fn power_factor_stats(power_factors: &[f64]) -> (Option<Decimal>, Option<Decimal>) {
+    if power_factors.is_empty() {
+        return (None, None);
+    }
+
+    let average = power_factors.iter().sum::<f64>() / power_factors.len() as f64;
+    let min = power_factors.iter().cloned().fold(f64::MAX, f64::min);
+
+    (Some(to_decimal(average, 3)), Some(to_decimal(min, 3)))
+}
+
+fn to_decimal(value: f64, scale: u32) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default().round_dp(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ufer_is_zero_without_active_consumption() {
+        assert_eq!(calculate_ufer(0.0, 50.0), 0.0);
+        assert_eq!(calculate_ufer(-1.0, 50.0), 0.0);
+    }
+
+    #[test]
+    fn ufer_is_zero_at_or_above_reference_power_factor() {
+        assert_eq!(calculate_ufer(100.0, 0.0), 0.0);
+        // FP = 100 / sqrt(100² + 42²) ≈ 0,922
+        assert_eq!(calculate_ufer(100.0, 42.0), 0.0);
+    }
+
+    #[test]
+    fn ufer_charges_reactive_excess_below_reference_power_factor() {
+        // FP = 1 / sqrt(2): UFER = 100 * (0,92 * sqrt(2) - 1)
+        let ufer = calculate_ufer(100.0, 100.0);
+        assert!((ufer - 100.0 * (UFER_REFERENCE_POWER_FACTOR * 2f64.sqrt() - 1.0)).abs() < 1e-9);
+        assert!((ufer - 30.108).abs() < 1e-3);
+    }
+
+    #[test]
+    fn ufer_is_the_same_for_capacitive_reactive_energy() {
+        assert_eq!(calculate_ufer(100.0, -100.0), calculate_ufer(100.0, 100.0));
+    }
+}
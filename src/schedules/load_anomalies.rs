@@ -0,0 +1,385 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use crate::calendar::unit_calendar::unit_non_working_days;
+use crate::db::entities::load_anomalies::{get_unit_daily_load_profiles, get_unit_hourly_load, get_unit_operating_schedule, replace_load_anomalies, upsert_unit_daily_load_profile};
+use crate::http::structs::load_anomalies::UnitHourLoad;
+use crate::models::database_models::load_anomalies::{LoadAnomaly, UnitDailyLoadProfile, UnitOperatingScheduleRow};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::GlobalVars;
+
+pub const ANOMALY_AFTER_HOURS_CONSUMPTION: &str = "AFTER_HOURS_CONSUMPTION";
+pub const ANOMALY_BASE_LOAD_INCREASE: &str = "BASE_LOAD_INCREASE";
+
+// Horas da madrugada (00:00 às 04:59) usadas no cálculo da carga de base
+const NIGHT_HOURS: std::ops::Range<u32> = 0..5;
+const BASE_LOAD_PERCENTILE: f64 = 10.0;
+// Histórico da própria unidade comparado com o dia processado
+const HISTORY_DAYS: i64 = 35;
+// A carga de base dos últimos dias (incluindo o processado) é comparada com a dos dias anteriores a eles
+const RECENT_DAYS: i64 = 7;
+const MIN_AFTER_HOURS_HISTORY_DAYS: usize = 5;
+const MIN_BASE_LOAD_HISTORY_DAYS: usize = 14;
+// Fora do expediente, a carga média precisa superar a mediana em 25% e em 3 desvios absolutos medianos
+const AFTER_HOURS_MIN_INCREASE: f64 = 0.25;
+const AFTER_HOURS_MAD_MULTIPLIER: f64 = 3.0;
+// Fator que converte o desvio absoluto mediano em desvio padrão numa distribuição normal
+const MAD_TO_STD: f64 = 1.4826;
+const BASE_LOAD_MAX_INCREASE: f64 = 0.15;
+// Horário assumido para unidades sem horário de funcionamento cadastrado: segunda a sexta, das 8h às 18h
+const DEFAULT_OPERATING_WEEKDAYS: std::ops::RangeInclusive<i32> = 1..=5;
+const DEFAULT_OPERATING_START_HOUR: u32 = 8;
+const DEFAULT_OPERATING_END_HOUR: u32 = 18;
+
+/// Calcula a carga de base e o consumo fora do expediente do dia e os compara com o histórico da própria unidade:
+/// consumo fora do expediente acima do habitual (ex.: ar-condicionado ligado à noite) e carga de base subindo.
+pub fn process_load_anomalies(unit_id: i32, day: &str, globs: &Arc<GlobalVars>) {
+    let day_date = match NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => return,
+    };
+
+    let hours = match get_unit_hourly_load(unit_id, day_date, globs) {
+        Ok(res) => res,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter consumo horário da unidade {}: {}", unit_id, err), 0, "ERROR");
+            return;
+        }
+    };
+
+    let schedule = get_unit_operating_schedule(unit_id, globs).unwrap_or_else(|err| {
+        write_to_log_file_thread(&format!("Erro ao obter horário de funcionamento da unidade {}: {}", unit_id, err), 0, "ERROR");
+        Vec::new()
+    });
+    let non_working_days = unit_non_working_days(unit_id, day_date - Duration::days(1), day_date, globs);
+
+    let profile = match build_daily_load_profile(unit_id, day_date, &hours, &schedule, &non_working_days) {
+        Some(profile) => profile,
+        None => return,
+    };
+    if let Err(err) = upsert_unit_daily_load_profile(&profile, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar perfil de carga da unidade {}: {}", unit_id, err), 0, "ERROR");
+        return;
+    }
+
+    // Um dia a mais para avaliar o aumento da carga de base do dia anterior com a mesma janela
+    let history = match get_unit_daily_load_profiles(unit_id, day_date - Duration::days(HISTORY_DAYS + 1), day_date - Duration::days(1), globs) {
+        Ok(res) => res,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter histórico de perfil de carga da unidade {}: {}", unit_id, err), 0, "ERROR");
+            return;
+        }
+    };
+
+    let anomalies = detect_load_anomalies(&profile, &history);
+    if let Err(err) = replace_load_anomalies(unit_id, day_date, &anomalies, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar anomalias de consumo da unidade {}: {}", unit_id, err), 0, "ERROR");
+    }
+}
+
+/// Perfil de carga do dia, None se o dia não tiver as 24 horas válidas e gravadas por todos os circuitos raiz.
+/// A carga de base é o percentil 10 do consumo horário da madrugada (kWh em uma hora = kW médio).
+pub fn build_daily_load_profile(unit_id: i32, day: NaiveDate, hours: &[UnitHourLoad], schedule: &[UnitOperatingScheduleRow], non_working_days: &HashSet<NaiveDate>) -> Option<UnitDailyLoadProfile> {
+    if hours.len() != 24 || hours.iter().any(|hour| !hour.all_valid || !hour.all_circuits) {
+        return None;
+    }
+
+    let mut night_load = Vec::new();
+    let mut after_hours_consumption = 0.0;
+    let mut after_hours_count = 0;
+    let mut operating_hours_consumption = 0.0;
+    for hour in hours {
+        let consumption = hour.consumption.to_f64().unwrap_or_default();
+        let time = hour.record_date.time();
+        if NIGHT_HOURS.contains(&time.hour()) {
+            night_load.push(consumption);
+        }
+        if is_operating_time(day, time, schedule, non_working_days) {
+            operating_hours_consumption += consumption;
+        } else {
+            after_hours_consumption += consumption;
+            after_hours_count += 1;
+        }
+    }
+
+    let base_load = percentile(&mut night_load, BASE_LOAD_PERCENTILE)?;
+    let after_hours_average_load = if after_hours_count > 0 { after_hours_consumption / after_hours_count as f64 } else { 0.0 };
+
+    Some(UnitDailyLoadProfile {
+        unit_id,
+        day,
+        base_load: to_decimal(base_load),
+        after_hours_consumption: to_decimal(after_hours_consumption),
+        after_hours_average_load: to_decimal(after_hours_average_load),
+        after_hours_count,
+        operating_hours_consumption: to_decimal(operating_hours_consumption),
+    })
+}
+
+/// Compara o dia com o histórico da unidade (dias anteriores, sem o próprio dia).
+/// O consumo fora do expediente só é comparado com dias de mesma quantidade de horas fora do expediente,
+/// para que fins de semana e feriados não sejam comparados com dias úteis.
+/// O aumento da carga de base só é registrado no primeiro dia em que aparece, e não enquanto a média recente continuar alta.
+pub fn detect_load_anomalies(profile: &UnitDailyLoadProfile, history: &[UnitDailyLoadProfile]) -> Vec<LoadAnomaly> {
+    let mut anomalies = Vec::new();
+
+    let mut after_hours_history: Vec<f64> = history_window(profile.day, history)
+        .filter(|past| past.after_hours_count == profile.after_hours_count)
+        .map(|past| past.after_hours_average_load.to_f64().unwrap_or_default())
+        .collect();
+    if profile.after_hours_count > 0 && after_hours_history.len() >= MIN_AFTER_HOURS_HISTORY_DAYS {
+        let measured = profile.after_hours_average_load.to_f64().unwrap_or_default();
+        let expected = median(&mut after_hours_history).unwrap_or_default();
+        let mut deviations: Vec<f64> = after_hours_history.iter().map(|value| (value - expected).abs()).collect();
+        let mad = median(&mut deviations).unwrap_or_default();
+        let threshold = (expected * (1.0 + AFTER_HOURS_MIN_INCREASE)).max(expected + AFTER_HOURS_MAD_MULTIPLIER * MAD_TO_STD * mad);
+        if measured > threshold {
+            anomalies.push(load_anomaly(profile, ANOMALY_AFTER_HOURS_CONSUMPTION, measured, expected, threshold));
+        }
+    }
+
+    let previous_day_increase = history.iter()
+        .find(|past| past.day == profile.day - Duration::days(1))
+        .and_then(|previous| base_load_increase(previous, history));
+    if let (Some((measured, expected, threshold)), None) = (base_load_increase(profile, history), previous_day_increase) {
+        anomalies.push(load_anomaly(profile, ANOMALY_BASE_LOAD_INCREASE, measured, expected, threshold));
+    }
+
+    anomalies
+}
+
+// Média da carga de base dos últimos RECENT_DAYS dias, mediana de referência e limite, se a média passar do limite
+fn base_load_increase(profile: &UnitDailyLoadProfile, history: &[UnitDailyLoadProfile]) -> Option<(f64, f64, f64)> {
+    let recent_start = profile.day - Duration::days(RECENT_DAYS - 1);
+    let mut reference_base_load: Vec<f64> = history_window(profile.day, history)
+        .filter(|past| past.day < recent_start)
+        .map(|past| past.base_load.to_f64().unwrap_or_default())
+        .collect();
+    let recent_base_load: Vec<f64> = history_window(profile.day, history)
+        .filter(|past| past.day >= recent_start)
+        .chain(std::iter::once(profile))
+        .map(|past| past.base_load.to_f64().unwrap_or_default())
+        .collect();
+    if reference_base_load.len() < MIN_BASE_LOAD_HISTORY_DAYS {
+        return None;
+    }
+
+    let measured = recent_base_load.iter().sum::<f64>() / recent_base_load.len() as f64;
+    let expected = median(&mut reference_base_load).unwrap_or_default();
+    let threshold = expected * (1.0 + BASE_LOAD_MAX_INCREASE);
+    (expected > 0.0 && measured > threshold).then_some((measured, expected, threshold))
+}
+
+// Dias do histórico nos HISTORY_DAYS anteriores ao dia
+fn history_window(day: NaiveDate, history: &[UnitDailyLoadProfile]) -> impl Iterator<Item = &UnitDailyLoadProfile> {
+    let start = day - Duration::days(HISTORY_DAYS);
+    history.iter().filter(move |past| past.day >= start && past.day < day)
+}
+
+// A hora pertence ao expediente pelo horário do próprio dia ou pela parte após a meia-noite do expediente do dia anterior
+fn is_operating_time(day: NaiveDate, time: NaiveTime, schedule: &[UnitOperatingScheduleRow], non_working_days: &HashSet<NaiveDate>) -> bool {
+    let previous_day = day - Duration::days(1);
+    let today = operating_interval(day, schedule, non_working_days);
+    let yesterday = operating_interval(previous_day, schedule, non_working_days);
+
+    let in_today = today.map(|(start, end)| if start < end { time >= start && time < end } else { time >= start }).unwrap_or(false);
+    let in_yesterday_overnight = yesterday.map(|(start, end)| start >= end && time < end).unwrap_or(false);
+
+    in_today || in_yesterday_overnight
+}
+
+// Início e fim do expediente na data, None se não houver expediente. Início igual ao fim é expediente de 24 horas.
+fn operating_interval(date: NaiveDate, schedule: &[UnitOperatingScheduleRow], non_working_days: &HashSet<NaiveDate>) -> Option<(NaiveTime, NaiveTime)> {
+    if non_working_days.contains(&date) {
+        return None;
+    }
+
+    let weekday = date.weekday().number_from_monday() as i32;
+    if schedule.is_empty() {
+        return DEFAULT_OPERATING_WEEKDAYS.contains(&weekday).then(|| (
+            NaiveTime::from_hms_opt(DEFAULT_OPERATING_START_HOUR, 0, 0).unwrap_or_default(),
+            NaiveTime::from_hms_opt(DEFAULT_OPERATING_END_HOUR, 0, 0).unwrap_or_default(),
+        ));
+    }
+
+    schedule.iter()
+        .find(|row| row.weekday == weekday)
+        .map(|row| (row.start_time, row.end_time))
+}
+
+fn load_anomaly(profile: &UnitDailyLoadProfile, anomaly_type: &str, measured: f64, expected: f64, threshold: f64) -> LoadAnomaly {
+    LoadAnomaly {
+        unit_id: profile.unit_id,
+        anomaly_date: profile.day,
+        anomaly_type: anomaly_type.to_owned(),
+        measured_value: to_decimal(measured),
+        expected_value: to_decimal(expected),
+        threshold: to_decimal(threshold),
+        deviation_percentage: (expected > 0.0).then(|| Decimal::from_f64((measured - expected) / expected * 100.0).unwrap_or_default().round_dp(2)),
+    }
+}
+
+// Percentil com interpolação linear entre as amostras ordenadas
+fn percentile(values: &mut [f64], percentile: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let position = percentile / 100.0 * (values.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    Some(values[lower] + (values[upper] - values[lower]) * (position - lower as f64))
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    percentile(values, 50.0)
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default().round_dp(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    // Quarta-feira
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, 4).unwrap()
+    }
+
+    // Madrugada crescente de 10 a 18 kWh, 20 kWh fora do expediente padrão e 50 kWh no expediente
+    fn hours(day: NaiveDate) -> Vec<UnitHourLoad> {
+        (0..24).map(|hour| UnitHourLoad {
+            record_date: day.and_hms_opt(hour, 0, 0).unwrap(),
+            consumption: match hour {
+                0..=4 => Decimal::from(10 + 2 * hour),
+                8..=17 => dec!(50),
+                _ => dec!(20),
+            },
+            all_valid: true,
+            all_circuits: true,
+        }).collect()
+    }
+
+    fn schedule(weekday: i32, start_hour: u32, end_hour: u32) -> UnitOperatingScheduleRow {
+        UnitOperatingScheduleRow {
+            unit_id: 1,
+            weekday,
+            start_time: NaiveTime::from_hms_opt(start_hour, 0, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(end_hour, 0, 0).unwrap(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn profile(offset: i64, base_load: Decimal, after_hours_average_load: Decimal) -> UnitDailyLoadProfile {
+        UnitDailyLoadProfile {
+            unit_id: 1,
+            day: day() + Duration::days(offset),
+            base_load,
+            after_hours_consumption: after_hours_average_load * Decimal::from(14),
+            after_hours_average_load,
+            after_hours_count: 14,
+            operating_hours_consumption: dec!(500),
+        }
+    }
+
+    fn anomaly_types(anomalies: &[LoadAnomaly]) -> Vec<&str> {
+        anomalies.iter().map(|anomaly| anomaly.anomaly_type.as_str()).collect()
+    }
+
+    #[test]
+    fn profile_uses_night_percentile_and_default_operating_hours() {
+        let profile = build_daily_load_profile(1, day(), &hours(day()), &[], &HashSet::new()).unwrap();
+
+        // Percentil 10 de [10, 12, 14, 16, 18]
+        assert_eq!(profile.base_load, dec!(10.8));
+        assert_eq!(profile.after_hours_count, 14);
+        assert_eq!(profile.after_hours_consumption, dec!(250));
+        assert_eq!(profile.after_hours_average_load, dec!(17.857));
+        assert_eq!(profile.operating_hours_consumption, dec!(500));
+    }
+
+    #[test]
+    fn profile_requires_every_hour_valid_and_from_every_root_circuit() {
+        let mut missing_hour = hours(day());
+        missing_hour.pop();
+        assert!(build_daily_load_profile(1, day(), &missing_hour, &[], &HashSet::new()).is_none());
+
+        let mut invalid = hours(day());
+        invalid[12].all_valid = false;
+        assert!(build_daily_load_profile(1, day(), &invalid, &[], &HashSet::new()).is_none());
+
+        let mut missing_circuit = hours(day());
+        missing_circuit[3].all_circuits = false;
+        assert!(build_daily_load_profile(1, day(), &missing_circuit, &[], &HashSet::new()).is_none());
+    }
+
+    #[test]
+    fn profile_counts_overnight_shift_from_previous_day_and_non_working_days() {
+        // Terça e quarta das 22h às 6h: 0h–5h (turno de terça) e 22h–23h (turno de quarta)
+        let overnight = [schedule(2, 22, 6), schedule(3, 22, 6)];
+        let profile = build_daily_load_profile(1, day(), &hours(day()), &overnight, &HashSet::new()).unwrap();
+        assert_eq!(profile.after_hours_count, 16);
+
+        let holiday = HashSet::from([day()]);
+        let profile = build_daily_load_profile(1, day(), &hours(day()), &[], &holiday).unwrap();
+        assert_eq!(profile.after_hours_count, 24);
+        assert_eq!(profile.operating_hours_consumption, Decimal::ZERO);
+    }
+
+    #[test]
+    fn after_hours_consumption_above_history_is_flagged() {
+        let history: Vec<UnitDailyLoadProfile> = (1..=5).map(|offset| profile(-offset, dec!(10), dec!(10))).collect();
+
+        let anomalies = detect_load_anomalies(&profile(0, dec!(10), dec!(20)), &history);
+        assert_eq!(anomaly_types(&anomalies), vec![ANOMALY_AFTER_HOURS_CONSUMPTION]);
+        assert_eq!(anomalies[0].expected_value, dec!(10));
+        assert_eq!(anomalies[0].threshold, dec!(12.5));
+        assert_eq!(anomalies[0].deviation_percentage, Some(dec!(100)));
+
+        assert!(detect_load_anomalies(&profile(0, dec!(10), dec!(12)), &history).is_empty());
+        assert!(detect_load_anomalies(&profile(0, dec!(10), dec!(20)), &history[..4]).is_empty());
+    }
+
+    #[test]
+    fn after_hours_consumption_is_only_compared_with_days_of_same_after_hours_count() {
+        let history: Vec<UnitDailyLoadProfile> = (1..=5).map(|offset| {
+            let mut past = profile(-offset, dec!(10), dec!(10));
+            past.after_hours_count = 24;
+            past
+        }).collect();
+        assert!(detect_load_anomalies(&profile(0, dec!(10), dec!(20)), &history).is_empty());
+    }
+
+    #[test]
+    fn base_load_increase_is_flagged_on_the_first_day_only() {
+        let mut history: Vec<UnitDailyLoadProfile> = (2..=36).map(|offset| profile(-offset, dec!(10), dec!(10))).collect();
+
+        // Ontem ainda normal: a média dos últimos 7 dias passa do limite só hoje
+        history.push(profile(-1, dec!(10), dec!(10)));
+        let anomalies = detect_load_anomalies(&profile(0, dec!(30), dec!(10)), &history);
+        assert_eq!(anomaly_types(&anomalies), vec![ANOMALY_BASE_LOAD_INCREASE]);
+        assert_eq!(anomalies[0].expected_value, dec!(10));
+        assert_eq!(anomalies[0].threshold, dec!(11.5));
+
+        // Ontem o aumento já tinha aparecido: não é registrado de novo
+        history.pop();
+        history.push(profile(-1, dec!(30), dec!(10)));
+        assert!(detect_load_anomalies(&profile(0, dec!(30), dec!(10)), &history).is_empty());
+    }
+
+    #[test]
+    fn base_load_needs_enough_reference_days() {
+        let history: Vec<UnitDailyLoadProfile> = (1..=19).map(|offset| profile(-offset, dec!(10), dec!(10))).collect();
+        assert!(detect_load_anomalies(&profile(0, dec!(30), dec!(10)), &history).is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+
+use crate::db::entities::meter_discontinuity_events::replace_meter_discontinuity_events;
+use crate::models::database_models::meter_discontinuity_events::MeterDiscontinuityEvent;
+use crate::schedules::energy_gap_fill::CircuitEnergySettings;
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::telemetry_payloads::energy::padronized::PadronizedEnergyTelemetry;
+use crate::GlobalVars;
+
+/// O registrador passou do valor máximo e voltou a contar do zero
+pub const EVENT_TYPE_ROLLOVER: &str = "ROLLOVER";
+/// O registrador foi zerado
+pub const EVENT_TYPE_RESET: &str = "RESET";
+/// O medidor foi trocado: o registrador passou a outro valor sem relação com o anterior
+pub const EVENT_TYPE_REPLACEMENT: &str = "REPLACEMENT";
+
+// Uma virada só é reconhecida com a leitura anterior nos últimos 10% do registrador e a nova nos primeiros 10%
+const ROLLOVER_MARGIN: f64 = 0.1;
+
+#[derive(Debug, Clone)]
+pub struct MeterDiscontinuity {
+    pub record_date: NaiveDateTime,
+    pub event_type: &'static str,
+    pub previous_value: f64,
+    pub new_value: f64,
+    /// Valor somado às leituras a partir da descontinuidade, acumulado no dia
+    pub applied_offset: f64,
+    pub estimated_consumption: Option<f64>,
+}
+
+/// Identifica viradas, zeramentos e trocas do medidor na série de `en_at_tri` e ajusta as leituras seguintes
+/// para que a série continue a partir do valor anterior. Uma leitura isolada fora da série (a seguinte volta
+/// a acompanhar a anterior, ou não há leitura seguinte) é descartada, sem gerar evento.
+pub fn rebase_energy_counter(telemetries: &mut [PadronizedEnergyTelemetry], settings: &CircuitEnergySettings) -> Vec<MeterDiscontinuity> {
+    let mut order: Vec<usize> = (0..telemetries.len())
+        .filter(|&i| telemetries[i].timestamp.is_some() && telemetries[i].en_at_tri.map(|value| value >= 0.0).unwrap_or(false))
+        .collect();
+    order.sort_by_key(|&i| telemetries[i].timestamp);
+
+    let mut discontinuities = Vec::new();
+    let mut offset = 0.0;
+    let mut previous: Option<(NaiveDateTime, f64)> = None;
+
+    for (position, &index) in order.iter().enumerate() {
+        let timestamp = telemetries[index].timestamp.unwrap();
+        let raw = telemetries[index].en_at_tri.unwrap();
+
+        let (previous_timestamp, previous_raw) = match previous {
+            Some(previous) => previous,
+            None => {
+                previous = Some((timestamp, raw));
+                telemetries[index].en_at_tri = Some(raw + offset);
+                continue;
+            }
+        };
+
+        let delta = raw - previous_raw;
+        if is_plausible_delta(delta, previous_timestamp, timestamp, settings) {
+            previous = Some((timestamp, raw));
+            telemetries[index].en_at_tri = Some(raw + offset);
+            continue;
+        }
+
+        // Sem leitura seguinte não há como confirmar a descontinuidade: a última leitura é descartada como isolada
+        let next = order.get(position + 1).map(|&next_index| (telemetries[next_index].timestamp.unwrap(), telemetries[next_index].en_at_tri.unwrap()));
+        let isolated = match next {
+            Some((next_timestamp, next_raw)) => is_plausible_delta(next_raw - previous_raw, previous_timestamp, next_timestamp, settings),
+            None => true,
+        };
+        if isolated {
+            telemetries[index].en_at_tri = None;
+            continue;
+        }
+
+        let (event_type, estimated_consumption) = classify_discontinuity(previous_raw, raw, settings);
+        offset += match event_type {
+            EVENT_TYPE_ROLLOVER => settings.register_rollover_value.unwrap_or(0.0),
+            EVENT_TYPE_RESET => previous_raw,
+            _ => previous_raw - raw,
+        };
+
+        discontinuities.push(MeterDiscontinuity {
+            record_date: timestamp,
+            event_type,
+            previous_value: previous_raw,
+            new_value: raw,
+            applied_offset: offset,
+            estimated_consumption,
+        });
+
+        previous = Some((timestamp, raw));
+        telemetries[index].en_at_tri = Some(raw + offset);
+    }
+
+    discontinuities
+}
+
+fn is_plausible_delta(delta: f64, from: NaiveDateTime, to: NaiveDateTime, settings: &CircuitEnergySettings) -> bool {
+    let elapsed_hours = ((to - from).num_seconds() as f64 / 3600.0).max(1.0);
+    delta >= 0.0 && delta <= settings.max_hour_consumption * elapsed_hours
+}
+
+fn classify_discontinuity(previous_raw: f64, raw: f64, settings: &CircuitEnergySettings) -> (&'static str, Option<f64>) {
+    if raw < previous_raw {
+        if let Some(rollover_value) = settings.register_rollover_value {
+            if previous_raw >= rollover_value * (1.0 - ROLLOVER_MARGIN) && raw <= rollover_value * ROLLOVER_MARGIN {
+                return (EVENT_TYPE_ROLLOVER, Some(rollover_value - previous_raw + raw));
+            }
+        }
+
+        // Registrador voltou para perto do zero: o consumo após o zeramento é a própria leitura
+        if raw <= settings.max_hour_consumption {
+            return (EVENT_TYPE_RESET, Some(raw));
+        }
+    }
+
+    (EVENT_TYPE_REPLACEMENT, None)
+}
+
+/// Salva as descontinuidades do dia do circuito, substituindo as de um processamento anterior
+pub fn save_meter_discontinuities(electric_circuit_id: i32, day: &str, discontinuities: &[MeterDiscontinuity], globs: &Arc<GlobalVars>) {
+    let start_time = match NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        Ok(date) => date.and_hms_opt(0, 0, 0).unwrap(),
+        Err(_) => return,
+    };
+    let end_time = start_time + Duration::days(1);
+
+    let events: Vec<MeterDiscontinuityEvent> = discontinuities.iter()
+        .filter(|discontinuity| discontinuity.record_date >= start_time && discontinuity.record_date < end_time)
+        .map(|discontinuity| MeterDiscontinuityEvent {
+            electric_circuit_id,
+            record_date: discontinuity.record_date,
+            event_type: discontinuity.event_type.to_owned(),
+            previous_value: to_decimal(discontinuity.previous_value),
+            new_value: to_decimal(discontinuity.new_value),
+            applied_offset: to_decimal(discontinuity.applied_offset),
+            estimated_consumption: discontinuity.estimated_consumption.map(to_decimal),
+        })
+        .collect();
+
+    for event in &events {
+        let msg = format!("Descontinuidade no medidor do circuito {}: {} em {} ({} -> {})", electric_circuit_id, event.event_type, event.record_date, event.previous_value, event.new_value);
+        write_to_log_file_thread(&msg, 0, "WARN");
+    }
+
+    if let Err(err) = replace_meter_discontinuity_events(electric_circuit_id, start_time, end_time, &events, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar descontinuidades do medidor do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+    }
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default().round_dp(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> CircuitEnergySettings {
+        CircuitEnergySettings {
+            max_hour_consumption: 10.0,
+            register_rollover_value: Some(1000.0),
+            ..CircuitEnergySettings::default()
+        }
+    }
+
+    // Uma leitura por hora a partir da meia-noite
+    fn readings(values: &[f64]) -> Vec<PadronizedEnergyTelemetry> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        values.iter().enumerate().map(|(hour, &value)| PadronizedEnergyTelemetry {
+            timestamp: Some(start + Duration::hours(hour as i64)),
+            en_at_tri: Some(value),
+            ..PadronizedEnergyTelemetry::default()
+        }).collect()
+    }
+
+    fn series(telemetries: &[PadronizedEnergyTelemetry]) -> Vec<Option<f64>> {
+        telemetries.iter().map(|telemetry| telemetry.en_at_tri).collect()
+    }
+
+    #[test]
+    fn rollover_continues_the_series_past_the_register_limit() {
+        let mut telemetries = readings(&[990.0, 995.0, 3.0, 8.0]);
+        let discontinuities = rebase_energy_counter(&mut telemetries, &settings());
+
+        assert_eq!(discontinuities.len(), 1);
+        assert_eq!(discontinuities[0].event_type, EVENT_TYPE_ROLLOVER);
+        assert_eq!(discontinuities[0].record_date, telemetries[2].timestamp.unwrap());
+        assert_eq!(discontinuities[0].applied_offset, 1000.0);
+        assert_eq!(discontinuities[0].estimated_consumption, Some(8.0));
+        assert_eq!(series(&telemetries), vec![Some(990.0), Some(995.0), Some(1003.0), Some(1008.0)]);
+    }
+
+    #[test]
+    fn reset_continues_the_series_from_the_previous_reading() {
+        let mut telemetries = readings(&[500.0, 505.0, 2.0, 6.0]);
+        let discontinuities = rebase_energy_counter(&mut telemetries, &settings());
+
+        assert_eq!(discontinuities.len(), 1);
+        assert_eq!(discontinuities[0].event_type, EVENT_TYPE_RESET);
+        assert_eq!(discontinuities[0].applied_offset, 505.0);
+        assert_eq!(discontinuities[0].estimated_consumption, Some(2.0));
+        assert_eq!(series(&telemetries), vec![Some(500.0), Some(505.0), Some(507.0), Some(511.0)]);
+    }
+
+    #[test]
+    fn replacement_joins_the_new_register_without_estimating_consumption() {
+        let mut telemetries = readings(&[500.0, 505.0, 300.0, 305.0]);
+        let discontinuities = rebase_energy_counter(&mut telemetries, &settings());
+
+        assert_eq!(discontinuities.len(), 1);
+        assert_eq!(discontinuities[0].event_type, EVENT_TYPE_REPLACEMENT);
+        assert_eq!(discontinuities[0].applied_offset, 205.0);
+        assert_eq!(discontinuities[0].estimated_consumption, None);
+        assert_eq!(series(&telemetries), vec![Some(500.0), Some(505.0), Some(505.0), Some(510.0)]);
+    }
+
+    #[test]
+    fn isolated_spike_is_discarded_without_event() {
+        let mut telemetries = readings(&[500.0, 505.0, 9000.0, 510.0]);
+        let discontinuities = rebase_energy_counter(&mut telemetries, &settings());
+
+        assert!(discontinuities.is_empty());
+        assert_eq!(series(&telemetries), vec![Some(500.0), Some(505.0), None, Some(510.0)]);
+    }
+
+    #[test]
+    fn spike_on_the_last_reading_is_discarded_without_event() {
+        let mut telemetries = readings(&[500.0, 505.0, 9000.0]);
+        let discontinuities = rebase_energy_counter(&mut telemetries, &settings());
+
+        assert!(discontinuities.is_empty());
+        assert_eq!(series(&telemetries), vec![Some(500.0), Some(505.0), None]);
+    }
+}
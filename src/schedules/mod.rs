@@ -8,3 +8,13 @@ pub mod device_disponibility;
 pub mod chiller;
 pub mod devices_l1_totalization;
 pub mod last_device_telemetry_time;
+pub mod dynamo_consumed_capacity;
+pub mod energy_reactive;
+pub mod demand_overrun;
+pub mod power_quality;
+pub mod energy_gap_fill;
+pub mod meter_discontinuities;
+pub mod virtual_circuits;
+pub mod load_anomalies;
+#[cfg(test)]
+mod scheduler_tests;
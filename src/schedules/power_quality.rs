@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+
+use crate::db::entities::electric_circuits::get_electric_circuit_nominal_voltage;
+use crate::db::entities::power_quality::{insert_data_power_quality, replace_power_quality_events};
+use crate::models::database_models::power_quality::{PowerQualityEvent, PowerQualityMinutesHist};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::telemetry_payloads::energy::padronized::PadronizedEnergyTelemetry;
+use crate::GlobalVars;
+
+pub const EVENT_TYPE_SAG: &str = "SAG";
+pub const EVENT_TYPE_SWELL: &str = "SWELL";
+
+/// Registradores usados pela análise de qualidade de energia, somados aos parâmetros da consulta de energia
+pub const POWER_QUALITY_PARAMS: [&str; 10] = ["v_a", "v_b", "v_c", "v_ab", "v_bc", "v_ca", "i_a", "i_b", "i_c", "freq"];
+
+/// Limites da análise de qualidade de energia, configurados em POWER_QUALITY.
+/// Tensões em pu da tensão nominal fase-neutro, que pode ser sobrescrita por circuito.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PowerQualityConfig {
+    #[serde(deserialize_with = "deserialize_nominal_voltage")]
+    pub nominal_voltage: f64,
+    #[serde(default = "default_nominal_frequency")]
+    pub nominal_frequency: f64,
+    #[serde(default = "default_voltage_lower_limit")]
+    pub voltage_lower_limit: f64,
+    #[serde(default = "default_voltage_upper_limit")]
+    pub voltage_upper_limit: f64,
+    #[serde(default = "default_sag_threshold")]
+    pub sag_threshold: f64,
+    #[serde(default = "default_swell_threshold")]
+    pub swell_threshold: f64,
+}
+
+fn default_nominal_frequency() -> f64 { 60.0 }
+fn default_voltage_lower_limit() -> f64 { 0.92 }
+fn default_voltage_upper_limit() -> f64 { 1.05 }
+fn default_sag_threshold() -> f64 { 0.9 }
+fn default_swell_threshold() -> f64 { 1.1 }
+
+// Todas as tensões em pu são divididas pela tensão nominal, então ela precisa ser positiva já ao carregar a configuração
+fn deserialize_nominal_voltage<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let nominal_voltage = f64::deserialize(deserializer)?;
+    if !nominal_voltage.is_finite() || nominal_voltage <= 0.0 {
+        return Err(D::Error::custom(format!("POWER_QUALITY.nominal_voltage deve ser maior que zero, recebido {}", nominal_voltage)));
+    }
+    Ok(nominal_voltage)
+}
+
+impl PowerQualityConfig {
+    /// Configuração com a tensão nominal do circuito, quando cadastrada (ex.: circuitos de 220 V ou 380 V)
+    pub fn for_circuit(&self, electric_circuit_id: i32, globs: &Arc<GlobalVars>) -> PowerQualityConfig {
+        let mut config = self.clone();
+
+        match get_electric_circuit_nominal_voltage(electric_circuit_id, globs) {
+            Ok(Some(nominal_voltage)) => {
+                if let Some(nominal_voltage) = nominal_voltage.to_f64().filter(|value| *value > 0.0) {
+                    config.nominal_voltage = nominal_voltage;
+                }
+            },
+            Ok(None) => {},
+            Err(err) => {
+                write_to_log_file_thread(&format!("Erro ao obter tensão nominal do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+            }
+        }
+
+        config
+    }
+}
+
+/// Calcula as estatísticas de qualidade de energia por 15 minutos e os eventos de afundamento/elevação de tensão do dia.
+pub fn process_power_quality(electric_circuit_id: i32, telemetries: &[PadronizedEnergyTelemetry], day: &str, config: &PowerQualityConfig, globs: &Arc<GlobalVars>) {
+    let start_time = match NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        Ok(date) => date.and_hms_opt(0, 0, 0).unwrap(),
+        Err(_) => return,
+    };
+    let end_time = start_time + Duration::days(1);
+
+    let mut day_tels: Vec<&PadronizedEnergyTelemetry> = telemetries.iter()
+        .filter(|tel| tel.timestamp.map(|ts| ts >= start_time && ts < end_time).unwrap_or(false))
+        .collect();
+    day_tels.sort_by_key(|tel| tel.timestamp);
+
+    let config = &config.for_circuit(electric_circuit_id, globs);
+    let stats = calculate_minutes_stats(electric_circuit_id, &day_tels, config);
+    if !stats.is_empty() {
+        if let Err(err) = insert_data_power_quality(stats, globs) {
+            write_to_log_file_thread(&format!("Erro ao salvar qualidade de energia do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+        }
+    }
+
+    let events = detect_voltage_events(electric_circuit_id, &day_tels, config);
+    if let Err(err) = replace_power_quality_events(electric_circuit_id, start_time, end_time, &events, globs) {
+        write_to_log_file_thread(&format!("Erro ao salvar eventos de qualidade de energia do circuito {}: {}", electric_circuit_id, err), 0, "ERROR");
+    }
+}
+
+// Tensões fase-neutro; sem elas, usa as tensões de linha convertidas para fase-neutro
+fn phase_voltages(tel: &PadronizedEnergyTelemetry) -> Vec<(&'static str, f64)> {
+    let line_neutral: Vec<(&'static str, f64)> = [("A", tel.v_a), ("B", tel.v_b), ("C", tel.v_c)].into_iter()
+        .filter_map(|(phase, value)| value.filter(|v| *v > 0.0).map(|v| (phase, v)))
+        .collect();
+    if !line_neutral.is_empty() {
+        return line_neutral;
+    }
+
+    [("AB", tel.v_ab), ("BC", tel.v_bc), ("CA", tel.v_ca)].into_iter()
+        .filter_map(|(phase, value)| value.filter(|v| *v > 0.0).map(|v| (phase, v / 3f64.sqrt())))
+        .collect()
+}
+
+/// Desequilíbrio (NEMA): maior desvio em relação à média das fases, em % da média
+fn unbalance(values: &[f64]) -> Option<f64> {
+    if values.len() < 3 {
+        return None;
+    }
+    let average = values.iter().sum::<f64>() / values.len() as f64;
+    if average <= 0.0 {
+        return None;
+    }
+    let max_deviation = values.iter().map(|v| (v - average).abs()).fold(0.0, f64::max);
+
+    Some(max_deviation / average * 100.0)
+}
+
+#[derive(Default)]
+struct MinutesAccumulator {
+    voltages: Vec<f64>,
+    voltage_unbalances: Vec<f64>,
+    current_unbalances: Vec<f64>,
+    frequencies: Vec<f64>,
+    readings_count: i32,
+    readings_out_of_limits: i32,
+}
+
+fn calculate_minutes_stats(electric_circuit_id: i32, tels: &[&PadronizedEnergyTelemetry], config: &PowerQualityConfig) -> Vec<PowerQualityMinutesHist> {
+    let mut groups: BTreeMap<NaiveDateTime, MinutesAccumulator> = BTreeMap::new();
+
+    for tel in tels {
+        let timestamp = tel.timestamp.unwrap();
+        let slot = timestamp.date().and_hms_opt(timestamp.hour(), timestamp.minute() / 15 * 15, 0).unwrap();
+        let acc = groups.entry(slot).or_default();
+
+        let voltages: Vec<f64> = phase_voltages(tel).into_iter().map(|(_, v)| v).collect();
+        let currents: Vec<f64> = [tel.i_a, tel.i_b, tel.i_c].into_iter().flatten().collect();
+        if voltages.is_empty() && tel.freq.is_none() {
+            continue;
+        }
+
+        acc.readings_count += 1;
+        let out_of_limits = voltages.iter().any(|v| {
+            let pu = v / config.nominal_voltage;
+            pu < config.voltage_lower_limit || pu > config.voltage_upper_limit
+        });
+        if out_of_limits {
+            acc.readings_out_of_limits += 1;
+        }
+
+        acc.voltage_unbalances.extend(unbalance(&voltages));
+        acc.current_unbalances.extend(unbalance(&currents));
+        acc.voltages.extend(voltages);
+        acc.frequencies.extend(tel.freq.filter(|f| *f > 0.0));
+    }
+
+    groups.into_iter()
+        .filter(|(_, acc)| acc.readings_count > 0)
+        .map(|(record_date, acc)| PowerQualityMinutesHist {
+            electric_circuit_id,
+            record_date,
+            min_voltage: min(&acc.voltages).and_then(|v| to_decimal(v, 2)),
+            max_voltage: max(&acc.voltages).and_then(|v| to_decimal(v, 2)),
+            average_voltage: average(&acc.voltages).and_then(|v| to_decimal(v, 2)),
+            average_voltage_unbalance: average(&acc.voltage_unbalances).and_then(|v| to_decimal(v, 2)),
+            max_voltage_unbalance: max(&acc.voltage_unbalances).and_then(|v| to_decimal(v, 2)),
+            average_current_unbalance: average(&acc.current_unbalances).and_then(|v| to_decimal(v, 2)),
+            max_current_unbalance: max(&acc.current_unbalances).and_then(|v| to_decimal(v, 2)),
+            min_frequency: min(&acc.frequencies).and_then(|v| to_decimal(v, 3)),
+            max_frequency: max(&acc.frequencies).and_then(|v| to_decimal(v, 3)),
+            max_frequency_deviation: max(&acc.frequencies.iter().map(|f| (f - config.nominal_frequency).abs()).collect::<Vec<f64>>()).and_then(|v| to_decimal(v, 3)),
+            readings_count: acc.readings_count,
+            readings_out_of_limits: acc.readings_out_of_limits,
+        })
+        .collect()
+}
+
+struct OpenEvent {
+    event_type: &'static str,
+    start: NaiveDateTime,
+    last: NaiveDateTime,
+    extreme_voltage: f64,
+}
+
+/// Afundamento: tensão da fase abaixo de `sag_threshold` pu; elevação: acima de `swell_threshold` pu.
+/// Leituras consecutivas fora do limiar formam um único evento, encerrado na primeira leitura normal
+/// ou quando a leitura passa a trazer as tensões do outro grupo de fases (fase-neutro ou de linha).
+fn detect_voltage_events(electric_circuit_id: i32, tels: &[&PadronizedEnergyTelemetry], config: &PowerQualityConfig) -> Vec<PowerQualityEvent> {
+    let mut open_events: BTreeMap<&'static str, OpenEvent> = BTreeMap::new();
+    let mut events = Vec::new();
+
+    let close_event = |phase: &str, event: OpenEvent, end: NaiveDateTime, events: &mut Vec<PowerQualityEvent>| {
+        let extreme_voltage_pu = event.extreme_voltage / config.nominal_voltage;
+        events.push(PowerQualityEvent {
+            electric_circuit_id,
+            record_date: event.start,
+            event_type: event.event_type.to_owned(),
+            phase: phase.to_owned(),
+            end_date: end,
+            duration_seconds: (end - event.start).num_seconds() as i32,
+            extreme_voltage: to_decimal(event.extreme_voltage, 2).unwrap_or_default(),
+            extreme_voltage_pu: to_decimal(extreme_voltage_pu, 3).unwrap_or_default(),
+        });
+    };
+
+    for tel in tels {
+        let timestamp = tel.timestamp.unwrap();
+        let voltages = phase_voltages(tel);
+
+        // As fases A, B e C deixam de ser acompanhadas quando a leitura cai para as tensões de linha, e vice-versa
+        if let Some((reading_phase, _)) = voltages.first() {
+            let other_group: Vec<&'static str> = open_events.keys().copied().filter(|phase| phase.len() != reading_phase.len()).collect();
+            for phase in other_group {
+                if let Some(event) = open_events.remove(phase) {
+                    close_event(phase, event, timestamp, &mut events);
+                }
+            }
+        }
+
+        for (phase, voltage) in voltages {
+            let pu = voltage / config.nominal_voltage;
+            let event_type = if pu < config.sag_threshold {
+                Some(EVENT_TYPE_SAG)
+            } else if pu > config.swell_threshold {
+                Some(EVENT_TYPE_SWELL)
+            } else {
+                None
+            };
+
+            match (open_events.remove(phase), event_type) {
+                (Some(mut event), Some(event_type)) if event.event_type == event_type => {
+                    event.last = timestamp;
+                    event.extreme_voltage = if event_type == EVENT_TYPE_SAG { event.extreme_voltage.min(voltage) } else { event.extreme_voltage.max(voltage) };
+                    open_events.insert(phase, event);
+                },
+                (open_event, event_type) => {
+                    if let Some(event) = open_event {
+                        close_event(phase, event, timestamp, &mut events);
+                    }
+                    if let Some(event_type) = event_type {
+                        open_events.insert(phase, OpenEvent { event_type, start: timestamp, last: timestamp, extreme_voltage: voltage });
+                    }
+                },
+            }
+        }
+    }
+
+    // Eventos ainda abertos no fim do dia terminam na última leitura fora do limiar
+    for (phase, event) in open_events {
+        let end = event.last;
+        close_event(phase, event, end, &mut events);
+    }
+
+    events
+}
+
+fn min(values: &[f64]) -> Option<f64> {
+    values.iter().cloned().reduce(f64::min)
+}
+
+fn max(values: &[f64]) -> Option<f64> {
+    values.iter().cloned().reduce(f64::max)
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn to_decimal(value: f64, scale: u32) -> Option<Decimal> {
+    Decimal::from_f64(value).map(|v| v.round_dp(scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PowerQualityConfig {
+        json5::from_str("{ nominal_voltage: 220 }").unwrap()
+    }
+
+    // Leitura no minuto `minute` com a tensão fase-neutro da fase A ou, sem ela, a tensão de linha AB
+    fn reading(minute: i64, v_a: Option<f64>, v_ab: Option<f64>) -> PadronizedEnergyTelemetry {
+        PadronizedEnergyTelemetry {
+            timestamp: Some(start() + Duration::minutes(minute)),
+            v_a,
+            v_ab,
+            ..PadronizedEnergyTelemetry::default()
+        }
+    }
+
+    fn start() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+    }
+
+    fn detect(readings: &[PadronizedEnergyTelemetry]) -> Vec<PowerQualityEvent> {
+        let tels: Vec<&PadronizedEnergyTelemetry> = readings.iter().collect();
+        detect_voltage_events(1, &tels, &config())
+    }
+
+    #[test]
+    fn config_rejects_non_positive_nominal_voltage() {
+        assert_eq!(config().nominal_voltage, 220.0);
+        assert_eq!(config().sag_threshold, 0.9);
+        assert!(json5::from_str::<PowerQualityConfig>("{ nominal_voltage: 0 }").is_err());
+        assert!(json5::from_str::<PowerQualityConfig>("{ nominal_voltage: -127 }").is_err());
+    }
+
+    #[test]
+    fn unbalance_is_the_largest_deviation_from_the_average() {
+        assert_eq!(unbalance(&[220.0, 220.0, 220.0]), Some(0.0));
+        // Média 220, maior desvio 11
+        let value = unbalance(&[209.0, 220.0, 231.0]).unwrap();
+        assert!((value - 5.0).abs() < 1e-9);
+        assert_eq!(unbalance(&[220.0, 230.0]), None);
+        assert_eq!(unbalance(&[0.0, 0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn consecutive_readings_below_the_threshold_form_a_single_event() {
+        let events = detect(&[
+            reading(0, Some(220.0), None),
+            reading(1, Some(190.0), None),
+            reading(2, Some(180.0), None),
+            reading(3, Some(220.0), None),
+        ]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EVENT_TYPE_SAG);
+        assert_eq!(events[0].phase, "A");
+        assert_eq!(events[0].record_date, start() + Duration::minutes(1));
+        assert_eq!(events[0].end_date, start() + Duration::minutes(3));
+        assert_eq!(events[0].duration_seconds, 120);
+        assert_eq!(events[0].extreme_voltage, Decimal::from(180));
+        assert_eq!(events[0].extreme_voltage_pu, Decimal::new(818, 3));
+    }
+
+    #[test]
+    fn switching_voltage_group_closes_the_events_of_the_other_group() {
+        let events = detect(&[
+            reading(0, Some(190.0), None),
+            // Só a tensão de linha: 330 / √3 ≈ 190,5 V, afundamento na fase AB
+            reading(1, None, Some(330.0)),
+            reading(2, None, Some(380.0)),
+        ]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].phase.as_str(), events[0].record_date, events[0].end_date), ("A", start(), start() + Duration::minutes(1)));
+        assert_eq!(events[1].phase, "AB");
+        assert_eq!(events[1].event_type, EVENT_TYPE_SAG);
+        assert_eq!((events[1].record_date, events[1].end_date), (start() + Duration::minutes(1), start() + Duration::minutes(2)));
+    }
+
+    #[test]
+    fn events_still_open_at_the_end_of_the_day_end_at_the_last_reading_outside_the_threshold() {
+        let events = detect(&[
+            reading(0, Some(220.0), None),
+            reading(1, Some(250.0), None),
+            reading(2, Some(260.0), None),
+        ]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EVENT_TYPE_SWELL);
+        assert_eq!(events[0].record_date, start() + Duration::minutes(1));
+        assert_eq!(events[0].end_date, start() + Duration::minutes(2));
+        assert_eq!(events[0].duration_seconds, 60);
+        assert_eq!(events[0].extreme_voltage, Decimal::from(260));
+    }
+}
@@ -7,7 +7,7 @@ use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc
 use tokio::task;
 use crate::db::entities::clients::{get_client, insert_data_client};
 use crate::db::entities::units::{get_unit, insert_data_unit, update_unit};
-use crate::external_api::api_server::ApiServer;
+use crate::external_api::config_snapshots::ConfigSource;
 use crate::models::database_models::clients::Clients;
 use crate::models::database_models::units::Units;
 use crate::models::external_models::client::ClientInfo;
@@ -23,13 +23,16 @@ use super::waters::process_waters_devices;
 use super::energy_efficiency::{process_energy_efficiency_dacs, process_energy_efficiency_duts};
 use super::disponibility::{process_disponibility_dacs_devices, process_disponibility_dals_devices, process_disponibility_dams_devices, process_disponibility_dmts_devices, process_disponibility_dris_devices, process_disponibility_duts_devices};
 use super::devices_l1_totalization::{process_l1_totalization_dacs, process_l1_totalization_duts};
+use super::dynamo_consumed_capacity::flush_dynamo_consumed_capacity;
+use crate::db::config::consumed_capacity::{with_client, with_pipeline};
 
-pub async fn run_nightly_tasks(globs: &Arc<GlobalVars>, day: &str, units_with_others_timezones: Option<bool>, script_type: &str, client_ids: Option<Vec<i32>>, unit_ids: Option<Vec<i32>>) {
+pub async fn run_nightly_tasks(globs: &Arc<GlobalVars>, day: &str, units_with_others_timezones: Option<bool>, script_type: &str, client_ids: Option<Vec<i32>>, unit_ids: Option<Vec<i32>>, config_source: ConfigSource) {
     let msg = "Começando Processamento";
     write_to_log_file_thread(msg, 0, "SCHEDULER");
     println!("{}", msg);
 
-    let clients_result = ApiServer::get_clients(client_ids, globs).await;
+    globs.compilation_cache.begin_day(day);
+    let clients_result = config_source.get_clients(client_ids, day, globs).await;
     match clients_result {
         Ok(clients) => {
             let clients_queue: VecDeque<_> = clients.into_iter().collect();
@@ -58,7 +61,7 @@ pub async fn run_nightly_tasks(globs: &Arc<GlobalVars>, day: &str, units_with_ot
                             client = clients.pop_front().unwrap();
                         }
                         
-                        process_clients_range(client, &globs_clone, index, &day_clone, units_with_others_timezones, &script_type, unit_ids_clone.clone()).await;
+                        process_clients_range(client, &globs_clone, index, &day_clone, units_with_others_timezones, &script_type, unit_ids_clone.clone(), config_source).await;
                     }
                 });
                 handles.push(handle);
@@ -79,6 +82,8 @@ pub async fn run_nightly_tasks(globs: &Arc<GlobalVars>, day: &str, units_with_ot
     }
 
     reprocess_energy_forecast_view(day, globs);
+    globs.compilation_cache.end_day(day);
+    flush_dynamo_consumed_capacity(globs);
 }
 
 pub async fn run_scheduler_many_days(start_date: &str, end_date: &str, client_ids: Option<Vec<i32>>, unit_ids: Option<Vec<i32>>, script_type: &str, globs: &Arc<GlobalVars>) {
@@ -96,7 +101,7 @@ pub async fn run_scheduler_many_days(start_date: &str, end_date: &str, client_id
 
     let start = Instant::now();
     for day in days {
-        run_nightly_tasks(globs, &day, None, script_type, client_ids.clone(), unit_ids.clone()).await;
+        run_nightly_tasks(globs, &day, None, script_type, client_ids.clone(), unit_ids.clone(), ConfigSource::ApiServer).await;
     }
 
     let duration = start.elapsed();
@@ -125,7 +130,7 @@ pub async fn start_scheduler(globs: &Arc<GlobalVars>, start_hour: u32) {
         let start = Instant::now();
 
         let day: String = (Utc::now() - chrono::Duration::days(1)).format("%Y-%m-%d").to_string();
-        run_nightly_tasks(globs, &day, Some(units_with_others_timezones), "all", None, None).await;
+        run_nightly_tasks(globs, &day, Some(units_with_others_timezones), "all", None, None, ConfigSource::ApiServer).await;
 
         let duration = start.elapsed();
 
@@ -136,14 +141,14 @@ pub async fn start_scheduler(globs: &Arc<GlobalVars>, start_hour: u32) {
     }
 }
 
-async fn process_clients_range(client: ClientInfo, globs: &Arc<GlobalVars>, thread: usize, day: &str, units_with_others_timezones: Option<bool>, script_type: &str, unit_ids: Option<Vec<i32>>) {
-    process_client_units(&client, day, globs, thread, units_with_others_timezones, unit_ids, script_type).await;
+async fn process_clients_range(client: ClientInfo, globs: &Arc<GlobalVars>, thread: usize, day: &str, units_with_others_timezones: Option<bool>, script_type: &str, unit_ids: Option<Vec<i32>>, config_source: ConfigSource) {
+    with_client(client.client_id, process_client_units(&client, day, globs, thread, units_with_others_timezones, unit_ids, script_type, config_source)).await;
 }
 
-async fn process_client_units(client_info: &ClientInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize, units_with_others_timezones: Option<bool>, unit_ids: Option<Vec<i32>>, script_type: &str) {
+async fn process_client_units(client_info: &ClientInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize, units_with_others_timezones: Option<bool>, unit_ids: Option<Vec<i32>>, script_type: &str, config_source: ConfigSource) {
     match verify_insert_client(client_info, globs) {
      Ok(client_db) => {
-         let units_result = ApiServer::get_all_units_by_client(&client_info.client_id, units_with_others_timezones, unit_ids, day, globs).await;
+         let units_result = config_source.get_all_units_by_client(&client_info.client_id, units_with_others_timezones, unit_ids, day, globs).await;
          match units_result {
              Ok(units) => {
                  let start_message = format!("Começou cliente: {:?}-{:?}", client_info.client_id, &client_info.client_name);
@@ -152,42 +157,42 @@ async fn process_client_units(client_info: &ClientInfo, day: &str, globs: &Arc<G
                  match script_type {
                     "energy" => {
                         for unit in units {
-                            process_unit_energy_devices(client_db.0, unit, client_db.1, day, globs, thread, Some(false)).await;
+                            process_unit_energy_devices(client_db.0, unit, client_db.1, day, globs, thread, Some(false), config_source).await;
                         }
                     },
                     "chiller" => {
                         for unit in units {
-                            process_unit_chiller_devices(client_db.0, unit, client_db.1, day, globs, thread).await;
+                            process_unit_chiller_devices(client_db.0, unit, client_db.1, day, globs, thread, config_source).await;
                         }
                     },
                     "water" => {
                         for unit in units {
-                            process_unit_water_devices(client_db.0, client_db.1, unit, day, globs, thread).await;
+                            process_unit_water_devices(client_db.0, client_db.1, unit, day, globs, thread, config_source).await;
                         }
                     },
                     "without_energy" => {
                         for unit in units {
-                            process_unit_devices_script(client_db.0, unit, client_db.1, day, globs, thread).await;
+                            process_unit_devices_script(client_db.0, unit, client_db.1, day, globs, thread, config_source).await;
                         } 
                     },
                     "energy_demand" => {
                         for unit in units {
-                            process_unit_energy_devices(client_db.0, unit, client_db.1, day, globs, thread, Some(true)).await;
+                            process_unit_energy_devices(client_db.0, unit, client_db.1, day, globs, thread, Some(true), config_source).await;
                         }
                     },
                     "energy_efficiency" => {
                         for unit in units {
-                            process_unit_energy_efficiency_devices(client_db.0, client_db.1, unit, day, globs, thread).await;
+                            process_unit_energy_efficiency_devices(client_db.0, client_db.1, unit, day, globs, thread, config_source).await;
                         } 
                     },
                     "process_unit_on_outside_programming" => {
                         for unit in units {
-                            process_unit_on_outside_programming_devices(client_db.0, client_db.1, unit, day, globs, thread).await;
+                            process_unit_on_outside_programming_devices(client_db.0, client_db.1, unit, day, globs, thread, config_source).await;
                         } 
                     },
                     _ => {
                         for unit in units {
-                            process_unit_devices(client_db.0, client_db.1, unit, day, globs, thread).await;
+                            process_unit_devices(client_db.0, client_db.1, unit, day, globs, thread, config_source).await;
                         } 
                     }
                 };
@@ -236,25 +241,25 @@ fn verify_insert_client(client_info: &ClientInfo, globs: &Arc<GlobalVars>) -> Re
     }
 }
 
-async fn process_unit_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize) {
+async fn process_unit_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
                     tokio::join!(
-                        process_waters_devices(unit_id, devices_config.devices.laager_device, devices_config.devices.dma_device, day, client_minutes_to_check_offline, globs),
-                        process_energy_devices(unit_id, &devices_config.devices.energy_devices, day, Some(false), client_minutes_to_check_offline, globs),
-                        process_l1_totalization_dacs(unit_id, day, &devices_config.devices.dacs_to_l1_automation, client_minutes_to_check_offline, globs),
-                        process_l1_totalization_duts(unit_id, day, &devices_config.devices.duts_to_l1_automation, client_minutes_to_check_offline, globs),
-                        process_energy_efficiency_dacs(unit_id, day, &devices_config.devices.dacs_devices, client_minutes_to_check_offline, globs),
-                        process_energy_efficiency_duts(unit_id, day, &devices_config.devices.duts_devices, client_minutes_to_check_offline, globs),
-                        process_disponibility_duts_devices(unit_id, day, &devices_config.devices.duts_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dacs_devices(unit_id, day, &devices_config.devices.dacs_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dris_devices(unit_id, day, &devices_config.devices.dris_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dmts_devices(unit_id, day, &devices_config.devices.dmts_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dals_devices(unit_id, day, &devices_config.devices.dals_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dams_devices(unit_id, day, &devices_config.devices.dams_to_disponibility, client_minutes_to_check_offline, globs)
+                        with_pipeline("water", process_waters_devices(unit_id, devices_config.devices.laager_device, devices_config.devices.dma_device, day, client_minutes_to_check_offline, config_source, globs)),
+                        with_pipeline("energy", process_energy_devices(unit_id, &devices_config.devices.energy_devices, day, Some(false), client_minutes_to_check_offline, globs)),
+                        with_pipeline("l1_totalization", process_l1_totalization_dacs(unit_id, day, &devices_config.devices.dacs_to_l1_automation, client_minutes_to_check_offline, globs)),
+                        with_pipeline("l1_totalization", process_l1_totalization_duts(unit_id, day, &devices_config.devices.duts_to_l1_automation, client_minutes_to_check_offline, globs)),
+                        with_pipeline("energy_efficiency", process_energy_efficiency_dacs(unit_id, day, &devices_config.devices.dacs_devices, client_minutes_to_check_offline, globs)),
+                        with_pipeline("energy_efficiency", process_energy_efficiency_duts(unit_id, day, &devices_config.devices.duts_devices, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_duts_devices(unit_id, day, &devices_config.devices.duts_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dacs_devices(unit_id, day, &devices_config.devices.dacs_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dris_devices(unit_id, day, &devices_config.devices.dris_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dmts_devices(unit_id, day, &devices_config.devices.dmts_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dals_devices(unit_id, day, &devices_config.devices.dals_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dams_devices(unit_id, day, &devices_config.devices.dams_to_disponibility, client_minutes_to_check_offline, globs))
                     );
                 }
                 Err(err) => {
@@ -272,25 +277,25 @@ async fn process_unit_devices(client_id: i32, client_minutes_to_check_offline: O
     }
 }
 
-async fn process_unit_devices_script(client_id: i32, unit_info: UnitInfo, client_minutes_to_check_offline: Option<i32>, day: &str, globs: &Arc<GlobalVars>, thread: usize) {
+async fn process_unit_devices_script(client_id: i32, unit_info: UnitInfo, client_minutes_to_check_offline: Option<i32>, day: &str, globs: &Arc<GlobalVars>, thread: usize, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
                     // sem medidor de energia
                     tokio::join!(
-                        process_waters_devices(unit_id, devices_config.devices.laager_device, devices_config.devices.dma_device, day, client_minutes_to_check_offline, globs),
-                        process_l1_totalization_dacs(unit_id, day, &devices_config.devices.dacs_to_l1_automation, client_minutes_to_check_offline, globs),
-                        process_l1_totalization_duts(unit_id, day, &devices_config.devices.duts_to_l1_automation, client_minutes_to_check_offline, globs),
-                        process_energy_efficiency_dacs(unit_id, day, &devices_config.devices.dacs_devices, client_minutes_to_check_offline, globs),
-                        process_energy_efficiency_duts(unit_id, day, &devices_config.devices.duts_devices, client_minutes_to_check_offline, globs),
-                        process_disponibility_duts_devices(unit_id, day, &devices_config.devices.duts_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dacs_devices(unit_id, day, &devices_config.devices.dacs_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dris_devices(unit_id, day, &devices_config.devices.dris_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dmts_devices(unit_id, day, &devices_config.devices.dmts_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dals_devices(unit_id, day, &devices_config.devices.dals_to_disponibility, client_minutes_to_check_offline, globs),
-                        process_disponibility_dams_devices(unit_id, day, &devices_config.devices.dams_to_disponibility, client_minutes_to_check_offline, globs)
+                        with_pipeline("water", process_waters_devices(unit_id, devices_config.devices.laager_device, devices_config.devices.dma_device, day, client_minutes_to_check_offline, config_source, globs)),
+                        with_pipeline("l1_totalization", process_l1_totalization_dacs(unit_id, day, &devices_config.devices.dacs_to_l1_automation, client_minutes_to_check_offline, globs)),
+                        with_pipeline("l1_totalization", process_l1_totalization_duts(unit_id, day, &devices_config.devices.duts_to_l1_automation, client_minutes_to_check_offline, globs)),
+                        with_pipeline("energy_efficiency", process_energy_efficiency_dacs(unit_id, day, &devices_config.devices.dacs_devices, client_minutes_to_check_offline, globs)),
+                        with_pipeline("energy_efficiency", process_energy_efficiency_duts(unit_id, day, &devices_config.devices.duts_devices, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_duts_devices(unit_id, day, &devices_config.devices.duts_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dacs_devices(unit_id, day, &devices_config.devices.dacs_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dris_devices(unit_id, day, &devices_config.devices.dris_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dmts_devices(unit_id, day, &devices_config.devices.dmts_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dals_devices(unit_id, day, &devices_config.devices.dals_to_disponibility, client_minutes_to_check_offline, globs)),
+                        with_pipeline("disponibility", process_disponibility_dams_devices(unit_id, day, &devices_config.devices.dams_to_disponibility, client_minutes_to_check_offline, globs))
                     );
                 }
                 Err(err) => {
@@ -308,13 +313,13 @@ async fn process_unit_devices_script(client_id: i32, unit_info: UnitInfo, client
     }
 }
 
-async fn process_unit_energy_devices(client_id: i32, unit_info: UnitInfo, client_minutes_to_check_offline: Option<i32>, day: &str, globs: &Arc<GlobalVars>, thread: usize, only_demand: Option<bool>) {
+async fn process_unit_energy_devices(client_id: i32, unit_info: UnitInfo, client_minutes_to_check_offline: Option<i32>, day: &str, globs: &Arc<GlobalVars>, thread: usize, only_demand: Option<bool>, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
-                    process_energy_devices(unit_id, &devices_config.devices.energy_devices, day, only_demand, client_minutes_to_check_offline, globs).await;
+                    with_pipeline("energy", process_energy_devices(unit_id, &devices_config.devices.energy_devices, day, only_demand, client_minutes_to_check_offline, globs)).await;
                 }
                 Err(err) => {
                     let error_msg = format!("SCRIPT Energia - Erro ao obter os dispositivos da unidade: {}, no dia {}, {}", unit_info.unit_id, &day, err);
@@ -331,16 +336,16 @@ async fn process_unit_energy_devices(client_id: i32, unit_info: UnitInfo, client
     }
 }
 
-async fn process_unit_chiller_devices(client_id: i32, unit_info: UnitInfo, client_minutes_to_check_offline: Option<i32>, day: &str, globs: &Arc<GlobalVars>, thread: usize) {
+async fn process_unit_chiller_devices(client_id: i32, unit_info: UnitInfo, client_minutes_to_check_offline: Option<i32>, day: &str, globs: &Arc<GlobalVars>, thread: usize, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
                     tokio::join!(
-                        process_chiller_hx_devices(unit_id, &devices_config.devices.dris_to_disponibility, day, client_minutes_to_check_offline, globs),
-                        process_chiller_xa_devices(unit_id, &devices_config.devices.dris_to_disponibility, day, client_minutes_to_check_offline, globs),
-                        process_chiller_xa_hvar_devices(unit_id, &devices_config.devices.dris_to_disponibility, day, client_minutes_to_check_offline, globs),
+                        with_pipeline("chiller", process_chiller_hx_devices(unit_id, &devices_config.devices.dris_to_disponibility, day, client_minutes_to_check_offline, globs)),
+                        with_pipeline("chiller", process_chiller_xa_devices(unit_id, &devices_config.devices.dris_to_disponibility, day, client_minutes_to_check_offline, globs)),
+                        with_pipeline("chiller", process_chiller_xa_hvar_devices(unit_id, &devices_config.devices.dris_to_disponibility, day, client_minutes_to_check_offline, globs)),
                     );
                 }
                 Err(err) => {
@@ -358,13 +363,13 @@ async fn process_unit_chiller_devices(client_id: i32, unit_info: UnitInfo, clien
     }
 }
 
-async fn process_unit_water_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize) {
+async fn process_unit_water_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
-                    process_waters_devices(unit_id, devices_config.devices.laager_device, devices_config.devices.dma_device, day, client_minutes_to_check_offline, globs).await;
+                    with_pipeline("water", process_waters_devices(unit_id, devices_config.devices.laager_device, devices_config.devices.dma_device, day, client_minutes_to_check_offline, config_source, globs)).await;
                 }
                 Err(err) => {
                     let error_msg = format!("SCRIPT Água - Erro ao obter os dispositivos da unidade: {}, no dia {}, {}", unit_info.unit_id, &day, err);
@@ -382,15 +387,15 @@ async fn process_unit_water_devices(client_id: i32, client_minutes_to_check_offl
     
 }
 
-async fn process_unit_energy_efficiency_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize) {
+async fn process_unit_energy_efficiency_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
                     tokio::join!(
-                        process_energy_efficiency_dacs(unit_id, day, &devices_config.devices.dacs_devices, client_minutes_to_check_offline, globs),
-                        process_energy_efficiency_duts(unit_id, day, &devices_config.devices.duts_devices, client_minutes_to_check_offline, globs),
+                        with_pipeline("energy_efficiency", process_energy_efficiency_dacs(unit_id, day, &devices_config.devices.dacs_devices, client_minutes_to_check_offline, globs)),
+                        with_pipeline("energy_efficiency", process_energy_efficiency_duts(unit_id, day, &devices_config.devices.duts_devices, client_minutes_to_check_offline, globs)),
                     );                }
                 Err(err) => {
                     let error_msg = format!("SCRIPT Eficiência Energética - Erro ao obter os dispositivos da unidade: {}, no dia {}, {}", unit_info.unit_id, &day, err);
@@ -408,15 +413,15 @@ async fn process_unit_energy_efficiency_devices(client_id: i32, client_minutes_t
     
 }
 
-async fn process_unit_on_outside_programming_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize) {
+async fn process_unit_on_outside_programming_devices(client_id: i32, client_minutes_to_check_offline: Option<i32>, unit_info: UnitInfo, day: &str, globs: &Arc<GlobalVars>, thread: usize, config_source: ConfigSource) {
     match verify_insert_update_units(client_id, &unit_info, globs) {
         Ok(unit_id) => {
-            let devices_result = ApiServer::get_config_devices(&unit_info.unit_id, day, globs).await;
+            let devices_result = config_source.get_config_devices(&unit_info.unit_id, day, globs).await;
             match devices_result {
                 Ok(devices_config) => {
                     tokio::join!(
-                        process_l1_totalization_dacs(unit_id, day, &devices_config.devices.dacs_to_l1_automation, client_minutes_to_check_offline, globs),
-                        process_l1_totalization_duts(unit_id, day, &devices_config.devices.duts_to_l1_automation, client_minutes_to_check_offline, globs),
+                        with_pipeline("l1_totalization", process_l1_totalization_dacs(unit_id, day, &devices_config.devices.dacs_to_l1_automation, client_minutes_to_check_offline, globs)),
+                        with_pipeline("l1_totalization", process_l1_totalization_duts(unit_id, day, &devices_config.devices.duts_to_l1_automation, client_minutes_to_check_offline, globs)),
                     );                }
                 Err(err) => {
                     let error_msg = format!("SCRIPT Tempo Fora da Programação - Erro ao obter os dispositivos da unidade: {}, no dia {}, {}", unit_info.unit_id, &day, err);
@@ -0,0 +1,124 @@
+// Roda o processamento noturno de ponta a ponta contra o mock do API Server e da Laager.
+// Precisa de um Postgres com as migrations aplicadas: TEST_DATABASE_URL=postgres://... cargo test -- --ignored
+
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::external_api::config_snapshots::ConfigSource;
+use crate::schedules::scheduler::run_nightly_tasks;
+use crate::schema::{api_server_snapshots, clients, units, water_hist};
+use crate::GlobalVars;
+
+#[path = "../bin/mock_upstream/server.rs"]
+mod mock_upstream_server;
+
+const TEST_DAY: &str = "2024-11-20";
+const MOCK_CLIENT_ID: i32 = 1;
+const MOCK_CLIENT_NAME: &str = "Cliente Teste";
+const MOCK_UNIT_ID: i32 = 10;
+
+// Os futures do processamento de uma unidade não cabem na pilha padrão das threads do tokio em builds de debug
+const TEST_THREAD_STACK_SIZE: usize = 16 * 1024 * 1024;
+
+#[test]
+#[ignore = "precisa de um Postgres com as migrations aplicadas em TEST_DATABASE_URL"]
+fn run_nightly_tasks_against_mock_upstream() {
+    tokio::runtime::Builder::new_multi_thread()
+        .thread_stack_size(TEST_THREAD_STACK_SIZE)
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(nightly_tasks_against_mock_upstream());
+}
+
+async fn nightly_tasks_against_mock_upstream() {
+    let database_url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL não definida");
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let mock_url = format!("http://{}", listener.local_addr().unwrap());
+    let fixtures_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("devops/mock_upstream/fixtures");
+    let server = mock_upstream_server::mock_upstream_server(fixtures_dir, listener).unwrap();
+    let server_handle = server.handle();
+    tokio::spawn(server);
+
+    let mut configfile = crate::configs::load_configfile(format!("{}/configfile_example.json5", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    configfile.POSTGRES_DATABASE_URL = database_url.clone();
+    configfile.APISERVER_URL = mock_url.clone();
+    configfile.APILAAGER_URL = mock_url;
+
+    let globs = Arc::new(GlobalVars {
+        configfile: configfile.clone(),
+        pool: crate::db::config::postgres::PostgreSQLDatabaseManager::configure_connection_pool_pg(&database_url).unwrap(),
+        compilation_cache: Arc::new(crate::app_history::compilation_cache::CompilationCache::new(None)),
+        table_resolver: Arc::new(crate::db::config::table_resolution::TableResolver::new(&configfile)),
+        http_client: Arc::new(crate::external_api::http_client::HttpClient::new()),
+        laager_token: Arc::new(tokio::sync::Mutex::new(None)),
+    });
+
+    let day = NaiveDate::parse_from_str(TEST_DAY, "%Y-%m-%d").unwrap();
+    clear_mock_rows(day, &globs);
+
+    run_nightly_tasks(&globs, TEST_DAY, None, "all", None, None, ConfigSource::ApiServer).await;
+    server_handle.stop(true).await;
+
+    let mut conn = globs.pool.get().unwrap();
+
+    let client_id: i32 = clients::table
+        .filter(clients::client_name.eq(MOCK_CLIENT_NAME))
+        .select(clients::id)
+        .first(&mut conn)
+        .expect("cliente do mock não foi inserido");
+
+    let unit_id: i32 = units::table
+        .filter(units::reference_id.eq(MOCK_UNIT_ID))
+        .filter(units::client_id.eq(client_id))
+        .select(units::id)
+        .first(&mut conn)
+        .expect("unidade do mock não foi inserida");
+
+    let mut snapshot_types: Vec<String> = api_server_snapshots::table
+        .filter(api_server_snapshots::record_date.eq(day))
+        .filter(api_server_snapshots::reference_id.eq_any([MOCK_CLIENT_ID, MOCK_UNIT_ID]))
+        .select(api_server_snapshots::snapshot_type)
+        .load(&mut conn)
+        .unwrap();
+    snapshot_types.sort();
+    assert_eq!(snapshot_types, vec!["client", "config_devices", "unit"]);
+
+    let water_rows: Vec<(String, Decimal)> = water_hist::table
+        .filter(water_hist::unit_id.eq(unit_id))
+        .filter(water_hist::record_date.ge(day.and_hms_opt(0, 0, 0).unwrap()))
+        .filter(water_hist::record_date.lt(day.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap()))
+        .select((water_hist::device_code, water_hist::consumption))
+        .load(&mut conn)
+        .unwrap();
+    assert!(!water_rows.is_empty(), "consumo de água do medidor Laager não foi gravado");
+    assert!(water_rows.iter().all(|(device_code, _)| device_code == "LAAGER0001"));
+    let total: Decimal = water_rows.iter().map(|(_, consumption)| *consumption).sum();
+    assert!(total > Decimal::ZERO, "consumo de água gravado zerado: {:?}", water_rows);
+}
+
+// Remove o que uma execução anterior gravou, já que os snapshots só são salvos quando a configuração muda
+fn clear_mock_rows(day: NaiveDate, globs: &Arc<GlobalVars>) {
+    let mut conn = globs.pool.get().unwrap();
+
+    diesel::delete(api_server_snapshots::table.filter(api_server_snapshots::reference_id.eq_any([MOCK_CLIENT_ID, MOCK_UNIT_ID])))
+        .execute(&mut conn)
+        .unwrap();
+
+    let unit_ids: Vec<i32> = units::table
+        .filter(units::reference_id.eq(MOCK_UNIT_ID))
+        .select(units::id)
+        .load(&mut conn)
+        .unwrap();
+    diesel::delete(water_hist::table
+        .filter(water_hist::unit_id.eq_any(unit_ids))
+        .filter(water_hist::record_date.ge(day.and_hms_opt(0, 0, 0).unwrap())))
+        .execute(&mut conn)
+        .unwrap();
+}
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use crate::db::entities::electric_circuits::{get_unit_measured_circuits, get_unit_virtual_circuits};
+use crate::db::entities::energy_demand_minutes_hist::{get_circuits_demand_minutes_hist, insert_data_demand};
+use crate::db::entities::energy_hist::{get_circuits_energy_hist, insert_data_energy};
+use crate::models::database_models::energy_demand_minutes_hist::EnergyDemandMinutesHist;
+use crate::models::database_models::energy_hist::EnergyHist;
+use crate::schedules::energy::{calc_energy_consumption_forecast, CircuitForecastSettings};
+use crate::schedules::energy_gap_fill::{GapFillStrategy, FILL_STRATEGY_MEASURED};
+use crate::schedules::scheduler::write_to_log_file_thread;
+use crate::telemetry_payloads::formulas::extract_variables;
+use crate::GlobalVars;
+
+// Variáveis da fórmula referenciam circuitos pelo id de referência: C12 é o circuito 12
+const CIRCUIT_VARIABLE_PREFIX: &str = "C";
+
+/// Expressão aritmética de um circuito virtual sobre circuitos medidos da unidade (ex.: "C1 + C2 - C7")
+#[derive(Debug, Clone)]
+pub struct VirtualCircuitFormula {
+    expr: meval::Expr,
+    variables: Vec<(String, i32)>,
+}
+
+impl VirtualCircuitFormula {
+    pub fn compile(formula: &str) -> Result<Self, String> {
+        let expr: meval::Expr = formula.parse().map_err(|err: meval::Error| format!("Fórmula inválida ({}): {}", formula, err))?;
+
+        let mut variables = Vec::new();
+        for variable in extract_variables(formula) {
+            let reference_id = variable.strip_prefix(CIRCUIT_VARIABLE_PREFIX)
+                .and_then(|id| id.parse::<i32>().ok())
+                .ok_or_else(|| format!("Variável {} inválida na fórmula ({}), use C<id do circuito>", variable, formula))?;
+            variables.push((variable, reference_id));
+        }
+
+        if variables.is_empty() {
+            return Err(format!("Fórmula ({}) não referencia nenhum circuito", formula));
+        }
+
+        Ok(VirtualCircuitFormula { expr, variables })
+    }
+
+    /// Ids de referência dos circuitos usados na fórmula
+    pub fn circuits(&self) -> Vec<i32> {
+        self.variables.iter().map(|(_, reference_id)| *reference_id).collect()
+    }
+
+    /// Calcula a fórmula com o valor de cada circuito (por id de referência). None se faltar algum circuito
+    pub fn evaluate(&self, values: &HashMap<i32, f64>) -> Option<f64> {
+        let mut ctx = meval::Context::new();
+        for (variable, reference_id) in &self.variables {
+            ctx.var(variable.clone(), *values.get(reference_id)?);
+        }
+
+        self.expr.eval_with_context(ctx).ok().filter(|value| value.is_finite())
+    }
+}
+
+/// Calcula o consumo horário e a demanda de 15 minutos dos circuitos virtuais da unidade no dia,
+/// a partir do que já foi gravado para os circuitos medidos
+pub fn process_virtual_circuits(unit_id: i32, day: &str, globs: &Arc<GlobalVars>) {
+    let virtual_circuits = match get_unit_virtual_circuits(unit_id, globs) {
+        Ok(circuits) => circuits,
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter circuitos virtuais da unidade {}: {}", unit_id, err), 0, "ERROR");
+            return;
+        }
+    };
+
+    if virtual_circuits.is_empty() {
+        return;
+    }
+
+    let Ok(day_date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") else { return };
+    let start = day_date.and_time(NaiveTime::MIN);
+    let end = start + Duration::days(1);
+
+    let measured_circuits: HashMap<i32, i32> = match get_unit_measured_circuits(unit_id, globs) {
+        Ok(circuits) => circuits.into_iter().map(|(id, reference_id)| (reference_id, id)).collect(),
+        Err(err) => {
+            write_to_log_file_thread(&format!("Erro ao obter circuitos da unidade {}: {}", unit_id, err), 0, "ERROR");
+            return;
+        }
+    };
+
+    let reference_ids: HashMap<i32, i32> = measured_circuits.iter().map(|(reference_id, id)| (*id, *reference_id)).collect();
+
+    for (electric_circuit_id, reference_id, formula) in virtual_circuits {
+        let formula = match VirtualCircuitFormula::compile(&formula) {
+            Ok(formula) => formula,
+            Err(err) => {
+                write_to_log_file_thread(&format!("Circuito virtual {}: {}", reference_id, err), 0, "ERROR");
+                continue;
+            }
+        };
+
+        let input_ids: Option<Vec<i32>> = formula.circuits().iter().map(|circuit| measured_circuits.get(circuit).copied()).collect();
+        let Some(input_ids) = input_ids else {
+            write_to_log_file_thread(&format!("Circuito virtual {} referencia circuito que não pertence à unidade {}", reference_id, unit_id), 0, "ERROR");
+            continue;
+        };
+
+        match get_circuits_energy_hist(&input_ids, start, end, globs) {
+            Ok(hours) => {
+                let forecast_settings = CircuitForecastSettings::load(electric_circuit_id, day_date, day_date, globs);
+                for history in compute_virtual_energy(electric_circuit_id, &formula, &hours, &reference_ids, start) {
+                    let record_date = history.record_date;
+                    let _ = insert_data_energy(history, globs);
+                    calc_energy_consumption_forecast(electric_circuit_id, record_date, &forecast_settings, globs);
+                }
+            },
+            Err(err) => write_to_log_file_thread(&format!("Erro ao obter consumo dos circuitos do circuito virtual {}: {}", reference_id, err), 0, "ERROR"),
+        }
+
+        match get_circuits_demand_minutes_hist(&input_ids, start, end, globs) {
+            Ok(demands) => {
+                for demand in compute_virtual_demand(electric_circuit_id, &formula, &demands, &reference_ids) {
+                    let _ = insert_data_demand(demand, globs);
+                }
+            },
+            Err(err) => write_to_log_file_thread(&format!("Erro ao obter demanda dos circuitos do circuito virtual {}: {}", reference_id, err), 0, "ERROR"),
+        }
+    }
+}
+
+/// Uma linha por hora do dia. A hora só é válida se todos os circuitos da fórmula tiverem consumo válido
+/// e o resultado não for negativo; horas preenchidas em algum circuito herdam a estratégia de preenchimento.
+fn compute_virtual_energy(electric_circuit_id: i32, formula: &VirtualCircuitFormula, hours: &[EnergyHist], reference_ids: &HashMap<i32, i32>, start: NaiveDateTime) -> Vec<EnergyHist> {
+    let mut by_hour: HashMap<NaiveDateTime, Vec<&EnergyHist>> = HashMap::new();
+    for hour in hours {
+        by_hour.entry(hour.record_date).or_default().push(hour);
+    }
+
+    (0..24).map(|hour| {
+        let record_date = start + Duration::hours(hour);
+        let inputs = by_hour.get(&record_date).map(Vec::as_slice).unwrap_or_default();
+
+        let values: HashMap<i32, f64> = inputs.iter()
+            .filter(|input| input.is_valid_consumption)
+            .filter_map(|input| Some((*reference_ids.get(&input.electric_circuit_id)?, input.consumption.to_f64()?)))
+            .collect();
+        let consumption = formula.evaluate(&values).filter(|value| *value >= 0.0);
+
+        let fill_strategy = match consumption {
+            Some(_) => inputs.iter()
+                .map(|input| input.fill_strategy.as_str())
+                .find(|strategy| *strategy != FILL_STRATEGY_MEASURED)
+                .unwrap_or(FILL_STRATEGY_MEASURED),
+            None => GapFillStrategy::LeaveEmpty.as_str(),
+        };
+
+        EnergyHist {
+            electric_circuit_id,
+            consumption: consumption.and_then(Decimal::from_f64).map(|value| value.round_dp(3)).unwrap_or(Decimal::from(0)),
+            record_date,
+            is_measured_consumption: inputs.iter().any(|input| input.is_measured_consumption),
+            is_valid_consumption: consumption.is_some(),
+            fill_strategy: fill_strategy.to_owned(),
+        }
+    }).collect()
+}
+
+/// Demanda de cada intervalo em que todos os circuitos da fórmula têm medição. Média, máxima e mínima
+/// são calculadas separadamente pela fórmula, então máxima e mínima são aproximações quando há subtração.
+fn compute_virtual_demand(electric_circuit_id: i32, formula: &VirtualCircuitFormula, demands: &[EnergyDemandMinutesHist], reference_ids: &HashMap<i32, i32>) -> Vec<EnergyDemandMinutesHist> {
+    let mut by_interval: BTreeMap<NaiveDateTime, Vec<&EnergyDemandMinutesHist>> = BTreeMap::new();
+    for demand in demands {
+        by_interval.entry(demand.record_date).or_default().push(demand);
+    }
+
+    let evaluate = |inputs: &[&EnergyDemandMinutesHist], field: fn(&EnergyDemandMinutesHist) -> Decimal| -> Option<Decimal> {
+        let values: HashMap<i32, f64> = inputs.iter()
+            .filter_map(|input| Some((*reference_ids.get(&input.electric_circuit_id)?, field(input).to_f64()?)))
+            .collect();
+        formula.evaluate(&values).map(|value| value.max(0.0)).and_then(Decimal::from_f64).map(|value| value.round_dp(3))
+    };
+
+    by_interval.into_iter().filter_map(|(record_date, inputs)| {
+        Some(EnergyDemandMinutesHist {
+            electric_circuit_id,
+            average_demand: evaluate(&inputs, |demand| demand.average_demand)?,
+            max_demand: evaluate(&inputs, |demand| demand.max_demand)?,
+            min_demand: evaluate(&inputs, |demand| demand.min_demand)?,
+            record_date,
+        })
+    }).collect()
+}
@@ -3,6 +3,8 @@ use std::sync::{Arc, Mutex};
 use std::error::Error;
 use crate::app_history::dma_hist::{CompiledDmaData, DmaCompiledData, DmaDataStruct, HoursCompiledDmaData, PulseData};
 use crate::app_history::laager_hist::{CompiledLaagerData, HoursCompiledLaagerData, LaagerConsumptionHistoryPerHour, LaagerDataStruct, ReadingPerDayLaager};
+use crate::calendar::unit_calendar::unit_non_working_days;
+use crate::external_api::config_snapshots::ConfigSource;
 use crate::db::entities::water_consumption_forecast::insert_update_water_consumption_forecast;
 use crate::models::database_models::water_consumption_forecast::WaterConsumptionForecast;
 use crate::schedules::scheduler::write_to_log_file_thread;
@@ -19,9 +21,14 @@ use crate::schedules::devices::{process_dmas_devices_per_hour, process_laager_de
 
 use crate::db::entities::waters_hist::{get_last_valid_consumption, get_water_consumption_in_dates, insert_data_water, insert_data_waters};
 
-pub async fn process_waters_devices(unit_id: i32, laager_device: Option<LaagerDevice>, dma_device: Option<DmaDevice>, day: &str, client_minutes_to_check_offline: Option<i32>, globs: &Arc<GlobalVars>) {
+pub async fn process_waters_devices(unit_id: i32, laager_device: Option<LaagerDevice>, dma_device: Option<DmaDevice>, day: &str, client_minutes_to_check_offline: Option<i32>, config_source: ConfigSource, globs: &Arc<GlobalVars>) {
   if let Some(device) = laager_device {
-    process_laager_devices_per_hour(unit_id, day, &device, globs).await;
+    // O consumo dos medidores Laager vem do API Server e da API da Laager, então não há como reprocessá-lo offline
+    if config_source == ConfigSource::StoredSnapshots {
+      write_to_log_file_thread(&format!("Medidor Laager {} ignorado no reprocessamento com snapshots, dia {}", device.laager_code, day), 0, "INFO");
+    } else {
+      process_laager_devices_per_hour(unit_id, day, &device, globs).await;
+    }
   }
   
   if let Some(device) = dma_device {
@@ -506,6 +513,12 @@ pub fn forecast_without_consumption_by_day(installation_date: Option<String>, da
 }
 
 fn verify_last_three_weeks_consumption(forecast_date: NaiveDate, installation_date: &str, unit_id: i32, actual_consumption: Decimal, globs: &Arc<GlobalVars>) {
+    // Feriados e dias sem expediente não representam o dia da semana: não atualizam a previsão nem entram na média
+    let non_working_days = unit_non_working_days(unit_id, forecast_date - Duration::weeks(3), forecast_date, globs);
+    if non_working_days.contains(&forecast_date) {
+        return;
+    }
+
     let days = get_last_three_days_weeks(forecast_date);
     let installation_date_aux = NaiveDate::parse_from_str(&installation_date, "%Y/%m/%d")
     .map_err(|e| {
@@ -516,7 +529,7 @@ fn verify_last_three_weeks_consumption(forecast_date: NaiveDate, installation_da
 
     if let Some(date) = installation_date_aux {
         let days_after_installation_date = days.iter()
-            .filter(|&&day| day >= date);
+            .filter(|&&day| day >= date && !non_working_days.contains(&day));
     
         let qtd_days_after_installation_date = days_after_installation_date.clone().count() as i64;
     
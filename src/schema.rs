@@ -1,5 +1,19 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_server_snapshots (snapshot_type, reference_id, record_date) {
+        snapshot_type -> Text,
+        reference_id -> Int4,
+        record_date -> Date,
+        parent_reference_id -> Nullable<Int4>,
+        payload -> Text,
+        changed -> Bool,
+        fetched_at -> Timestamp,
+        deleted -> Bool,
+        other_timezone -> Nullable<Bool>,
+    }
+}
+
 diesel::table! {
     assets (id) {
         id -> Int4,
@@ -11,6 +25,23 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    calendar_holidays (id) {
+        id -> Int4,
+        name -> Text,
+        scope -> Text,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+        recurring -> Bool,
+        #[max_length = 100]
+        state_name -> Nullable<Varchar>,
+        #[max_length = 100]
+        city_name -> Nullable<Varchar>,
+        client_id -> Nullable<Int4>,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     chiller_hx_parameters_minutes_hist (device_code, record_date) {
         unit_id -> Int4,
@@ -167,6 +198,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    client_energy_forecast_models (client_id) {
+        client_id -> Int4,
+        forecast_model -> Text,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     clients (id) {
         id -> Int4,
@@ -176,6 +215,35 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    contracted_demands (id) {
+        id -> Int4,
+        unit_id -> Int4,
+        electric_circuit_id -> Nullable<Int4>,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+        peak_demand -> Nullable<Numeric>,
+        off_peak_demand -> Numeric,
+        tolerance_percentage -> Numeric,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    demand_overrun_events (id) {
+        id -> Int4,
+        contracted_demand_id -> Int4,
+        event_date -> Date,
+        tariff_period -> Text,
+        record_date -> Timestamp,
+        contracted_demand -> Numeric,
+        tolerance_limit -> Numeric,
+        measured_demand -> Numeric,
+        overrun_demand -> Numeric,
+        estimated_penalty -> Nullable<Numeric>,
+    }
+}
+
 diesel::table! {
     device_disponibility_hist (unit_id, device_code, record_date) {
         unit_id -> Int4,
@@ -208,6 +276,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    dynamo_consumed_capacity_hist (table_name, pipeline, client_reference_id, record_date) {
+        table_name -> Text,
+        pipeline -> Text,
+        client_reference_id -> Int4,
+        record_date -> Date,
+        consumed_rcu -> Numeric,
+        cache_hits -> Int4,
+        cache_hit_rcu -> Numeric,
+    }
+}
+
+diesel::table! {
+    dynamo_table_rules (id) {
+        id -> Int4,
+        pattern -> Text,
+        table_template -> Text,
+        partition_key -> Text,
+        sort_key -> Text,
+        updated_at -> Timestamp,
+        device_type -> Nullable<Text>,
+        priority -> Int4,
+    }
+}
+
 diesel::table! {
     electric_circuits (id) {
         id -> Int4,
@@ -215,6 +308,33 @@ diesel::table! {
         #[max_length = 50]
         name -> Varchar,
         reference_id -> Int4,
+        gap_fill_strategy -> Nullable<Text>,
+        max_hour_consumption -> Nullable<Numeric>,
+        register_rollover_value -> Nullable<Numeric>,
+        parent_id -> Nullable<Int4>,
+        virtual_formula -> Nullable<Text>,
+        nominal_voltage -> Nullable<Numeric>,
+    }
+}
+
+diesel::table! {
+    energy_baselines (id) {
+        id -> Int4,
+        unit_id -> Int4,
+        name -> Text,
+        reference_start -> Date,
+        reference_end -> Date,
+        cooling_base_temperature -> Nullable<Numeric>,
+        uses_operating_hours -> Bool,
+        intercept -> Numeric,
+        cooling_degree_days_coefficient -> Nullable<Numeric>,
+        operating_hours_coefficient -> Nullable<Numeric>,
+        observations -> Int4,
+        r_squared -> Numeric,
+        cv_rmse -> Numeric,
+        rmse -> Numeric,
+        residual_autocorrelation -> Numeric,
+        created_at -> Timestamp,
     }
 }
 
@@ -264,14 +384,52 @@ diesel::table! {
         record_date -> Timestamp,
         is_measured_consumption -> Nullable<Bool>,
         is_valid_consumption -> Nullable<Bool>,
+        fill_strategy -> Nullable<Text>,
     }
 }
 
 diesel::table! {
-    energy_monthly_consumption_target (unit_id, date_forecast) {
+    energy_monthly_consumption_target (unit_id, date_forecast, is_manual) {
         unit_id -> Int4,
         consumption_target -> Numeric,
         date_forecast -> Timestamp,
+        is_manual -> Bool,
+    }
+}
+
+diesel::table! {
+    energy_reactive_day_hist (electric_circuit_id, record_date) {
+        electric_circuit_id -> Int4,
+        record_date -> Date,
+        active_consumption -> Numeric,
+        reactive_consumption -> Numeric,
+        average_power_factor -> Nullable<Numeric>,
+        min_power_factor -> Nullable<Numeric>,
+        ufer -> Numeric,
+    }
+}
+
+diesel::table! {
+    energy_reactive_hour_hist (electric_circuit_id, record_date) {
+        electric_circuit_id -> Int4,
+        record_date -> Timestamp,
+        active_consumption -> Numeric,
+        reactive_consumption -> Numeric,
+        average_power_factor -> Nullable<Numeric>,
+        min_power_factor -> Nullable<Numeric>,
+        ufer -> Numeric,
+    }
+}
+
+diesel::table! {
+    grid_emission_factors (id) {
+        id -> Int4,
+        grid -> Text,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+        emission_factor -> Numeric,
+        source -> Nullable<Text>,
+        updated_at -> Timestamp,
     }
 }
 
@@ -282,6 +440,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    load_anomalies (id) {
+        id -> Int4,
+        unit_id -> Int4,
+        anomaly_date -> Date,
+        anomaly_type -> Text,
+        measured_value -> Numeric,
+        expected_value -> Numeric,
+        threshold -> Numeric,
+        deviation_percentage -> Nullable<Numeric>,
+    }
+}
+
 diesel::table! {
     machines (id) {
         id -> Int4,
@@ -292,6 +463,100 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    meter_discontinuity_events (electric_circuit_id, record_date) {
+        electric_circuit_id -> Int4,
+        record_date -> Timestamp,
+        event_type -> Text,
+        previous_value -> Numeric,
+        new_value -> Numeric,
+        applied_offset -> Numeric,
+        estimated_consumption -> Nullable<Numeric>,
+    }
+}
+
+diesel::table! {
+    power_quality_events (electric_circuit_id, record_date, event_type, phase) {
+        electric_circuit_id -> Int4,
+        record_date -> Timestamp,
+        event_type -> Text,
+        phase -> Text,
+        end_date -> Timestamp,
+        duration_seconds -> Int4,
+        extreme_voltage -> Numeric,
+        extreme_voltage_pu -> Numeric,
+    }
+}
+
+diesel::table! {
+    power_quality_minutes_hist (electric_circuit_id, record_date) {
+        electric_circuit_id -> Int4,
+        record_date -> Timestamp,
+        min_voltage -> Nullable<Numeric>,
+        max_voltage -> Nullable<Numeric>,
+        average_voltage -> Nullable<Numeric>,
+        average_voltage_unbalance -> Nullable<Numeric>,
+        max_voltage_unbalance -> Nullable<Numeric>,
+        average_current_unbalance -> Nullable<Numeric>,
+        max_current_unbalance -> Nullable<Numeric>,
+        min_frequency -> Nullable<Numeric>,
+        max_frequency -> Nullable<Numeric>,
+        max_frequency_deviation -> Nullable<Numeric>,
+        readings_count -> Int4,
+        readings_out_of_limits -> Int4,
+    }
+}
+
+diesel::table! {
+    unit_baseline_variables (unit_id, day) {
+        unit_id -> Int4,
+        day -> Date,
+        average_temperature -> Nullable<Numeric>,
+        operating_hours -> Nullable<Numeric>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    unit_daily_load_profiles (unit_id, day) {
+        unit_id -> Int4,
+        day -> Date,
+        base_load -> Numeric,
+        after_hours_consumption -> Numeric,
+        after_hours_average_load -> Numeric,
+        after_hours_count -> Int4,
+        operating_hours_consumption -> Numeric,
+    }
+}
+
+diesel::table! {
+    unit_operating_schedules (unit_id, weekday) {
+        unit_id -> Int4,
+        weekday -> Int4,
+        start_time -> Time,
+        end_time -> Time,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    unit_tariffs (id) {
+        id -> Int4,
+        unit_id -> Int4,
+        modality -> Text,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+        peak_start_time -> Time,
+        peak_end_time -> Time,
+        peak_weekdays_only -> Bool,
+        peak_energy_price -> Numeric,
+        off_peak_energy_price -> Numeric,
+        peak_demand_price -> Nullable<Numeric>,
+        off_peak_demand_price -> Nullable<Numeric>,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     units (id) {
         id -> Int4,
@@ -346,44 +611,79 @@ diesel::table! {
 }
 
 diesel::joinable!(assets -> units (unit_id));
+diesel::joinable!(calendar_holidays -> clients (client_id));
 diesel::joinable!(chiller_hx_parameters_minutes_hist -> units (unit_id));
 diesel::joinable!(chiller_parameters_changes_hist -> units (unit_id));
 diesel::joinable!(chiller_xa_hvar_parameters_minutes_hist -> units (unit_id));
 diesel::joinable!(chiller_xa_parameters_minutes_hist -> units (unit_id));
+diesel::joinable!(client_energy_forecast_models -> clients (client_id));
+diesel::joinable!(contracted_demands -> units (unit_id));
+diesel::joinable!(contracted_demands -> electric_circuits (electric_circuit_id));
+diesel::joinable!(demand_overrun_events -> contracted_demands (contracted_demand_id));
 diesel::joinable!(device_disponibility_hist -> units (unit_id));
 diesel::joinable!(disponibility_hist -> units (unit_id));
 diesel::joinable!(electric_circuits -> units (unit_id));
+diesel::joinable!(energy_baselines -> units (unit_id));
 diesel::joinable!(energy_consumption_forecast -> electric_circuits (electric_circuit_id));
 diesel::joinable!(energy_demand_minutes_hist -> electric_circuits (electric_circuit_id));
 diesel::joinable!(energy_efficiency_hist -> machines (machine_id));
 diesel::joinable!(energy_efficiency_hour_hist -> machines (machine_id));
 diesel::joinable!(energy_hist -> electric_circuits (electric_circuit_id));
 diesel::joinable!(energy_monthly_consumption_target -> units (unit_id));
+diesel::joinable!(energy_reactive_day_hist -> electric_circuits (electric_circuit_id));
+diesel::joinable!(energy_reactive_hour_hist -> electric_circuits (electric_circuit_id));
+diesel::joinable!(load_anomalies -> units (unit_id));
 diesel::joinable!(machines -> units (unit_id));
+diesel::joinable!(meter_discontinuity_events -> electric_circuits (electric_circuit_id));
+diesel::joinable!(power_quality_events -> electric_circuits (electric_circuit_id));
+diesel::joinable!(power_quality_minutes_hist -> electric_circuits (electric_circuit_id));
+diesel::joinable!(unit_baseline_variables -> units (unit_id));
+diesel::joinable!(unit_daily_load_profiles -> units (unit_id));
+diesel::joinable!(unit_operating_schedules -> units (unit_id));
+diesel::joinable!(unit_tariffs -> units (unit_id));
 diesel::joinable!(units -> clients (client_id));
 diesel::joinable!(water_consumption_forecast -> units (unit_id));
 diesel::joinable!(water_hist -> units (unit_id));
 diesel::joinable!(waters_hist -> units (unit_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_server_snapshots,
     assets,
+    calendar_holidays,
     chiller_hx_parameters_minutes_hist,
     chiller_parameters_changes_hist,
     chiller_xa_hvar_parameters_minutes_hist,
     chiller_xa_parameters_minutes_hist,
+    client_energy_forecast_models,
     clients,
+    contracted_demands,
+    demand_overrun_events,
     device_disponibility_hist,
     devices_l1_totalization_hist,
     disponibility_hist,
+    dynamo_consumed_capacity_hist,
+    dynamo_table_rules,
     electric_circuits,
+    energy_baselines,
     energy_consumption_forecast,
     energy_demand_minutes_hist,
     energy_efficiency_hist,
     energy_efficiency_hour_hist,
     energy_hist,
     energy_monthly_consumption_target,
+    energy_reactive_day_hist,
+    energy_reactive_hour_hist,
+    grid_emission_factors,
     last_device_telemetry_time,
+    load_anomalies,
     machines,
+    meter_discontinuity_events,
+    power_quality_events,
+    power_quality_minutes_hist,
+    unit_baseline_variables,
+    unit_daily_load_profiles,
+    unit_operating_schedules,
+    unit_tariffs,
     units,
     water_consumption_forecast,
     water_hist,
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::configs::ConfigFile;
+use crate::telemetry_payloads::formulas::FormulaSet;
+
+use super::dme::TelemetryDME;
+use super::padronized::PadronizedEnergyTelemetry;
+
+/// Converte a telemetria bruta de um fabricante de medidor de energia para o formato padronizado.
+pub trait EnergyManufacturerAdapter: Send + Sync {
+    fn to_padronized(&self, raw: Value, formulas: &FormulaSet) -> Result<PadronizedEnergyTelemetry, String>;
+}
+
+/// Retorna o adaptador do fabricante: o DME para "Diel Energia" ou um mapa de registradores do configfile.
+pub fn adapter_for_manufacturer(manufacturer: &str, configfile: &ConfigFile) -> Option<Box<dyn EnergyManufacturerAdapter>> {
+    if manufacturer == DME_MANUFACTURER {
+        return Some(Box::new(DmeAdapter));
+    }
+
+    configfile.ENERGY_REGISTER_MAPS.as_ref()?
+        .iter()
+        .find(|register_map| register_map.manufacturer == manufacturer)
+        .map(|register_map| Box::new(RegisterMapAdapter { register_map: register_map.clone() }) as Box<dyn EnergyManufacturerAdapter>)
+}
+
+const DME_MANUFACTURER: &str = "Diel Energia";
+
+pub struct DmeAdapter;
+
+impl EnergyManufacturerAdapter for DmeAdapter {
+    fn to_padronized(&self, raw: Value, formulas: &FormulaSet) -> Result<PadronizedEnergyTelemetry, String> {
+        let tel: TelemetryDME = serde_json::from_value(raw).map_err(|err| err.to_string())?;
+        PadronizedEnergyTelemetry::from_dme(tel, formulas)
+    }
+}
+
+/// Mapa de registradores de um fabricante, configurado em ENERGY_REGISTER_MAPS. Exemplo:
+/// `{ manufacturer: "Fabricante X", registers: { pot_at_tri: { field: "P_TOTAL", scale: 0.001 } } }`
+#[derive(Deserialize, Debug, Clone)]
+pub struct EnergyRegisterMap {
+    pub manufacturer: String,
+    #[serde(default = "default_timestamp_field")]
+    pub timestamp_field: String,
+    #[serde(default = "default_timestamp_format")]
+    pub timestamp_format: String,
+    /* Campo padronizado (ex.: en_at_tri) -> campo da telemetria bruta */
+    pub registers: HashMap<String, RegisterMapping>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RegisterMapping {
+    pub field: String,
+    /* Valor final = valor bruto * scale + offset */
+    pub scale: Option<f64>,
+    pub offset: Option<f64>,
+}
+
+fn default_timestamp_field() -> String {
+    "timestamp".to_owned()
+}
+
+fn default_timestamp_format() -> String {
+    "%Y-%m-%dT%H:%M:%S".to_owned()
+}
+
+pub struct RegisterMapAdapter {
+    register_map: EnergyRegisterMap,
+}
+
+impl EnergyManufacturerAdapter for RegisterMapAdapter {
+    fn to_padronized(&self, raw: Value, _formulas: &FormulaSet) -> Result<PadronizedEnergyTelemetry, String> {
+        let timestamp = raw.get(&self.register_map.timestamp_field)
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("Telemetria sem o campo {}", self.register_map.timestamp_field))?;
+        let timestamp = NaiveDateTime::parse_from_str(timestamp, &self.register_map.timestamp_format)
+            .map_err(|err| err.to_string())?;
+
+        let mut fields = Map::new();
+        for (padronized_field, mapping) in &self.register_map.registers {
+            if let Some(value) = raw.get(&mapping.field).and_then(register_value) {
+                let value = value * mapping.scale.unwrap_or(1.0) + mapping.offset.unwrap_or(0.0);
+                fields.insert(padronized_field.clone(), Value::from(value));
+            }
+        }
+
+        // Campos do mapa que não existem no formato padronizado são ignorados pelo serde
+        let mut tel: PadronizedEnergyTelemetry = serde_json::from_value(Value::Object(fields)).map_err(|err| err.to_string())?;
+        tel.timestamp = Some(timestamp);
+
+        Ok(tel)
+    }
+}
+
+// Alguns fabricantes enviam os registradores como string
+fn register_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
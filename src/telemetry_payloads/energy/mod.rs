@@ -1,2 +1,3 @@
 pub mod dme;
 pub mod padronized;
+pub mod adapters;
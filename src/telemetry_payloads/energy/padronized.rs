@@ -4,6 +4,7 @@ use std::convert::TryFrom;
 use serde_json::{json, Value};
 
 use crate::telemetry_payloads::energy::dme::TelemetryDME;
+use crate::telemetry_payloads::formulas::{compile_device_formulas, formulas_from_telemetry, FormulaError, FormulaSet};
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct PadronizedEnergyTelemetry {
@@ -80,6 +81,13 @@ pub struct PadronizedEnergyTelemetry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub erro: Option<f64>,
     pub timestamp: Option<NaiveDateTime>,
+    /* Leitura de en_at_tri presente mas inválida; a hora em que ela aparece é descartada na compilação do consumo */
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub en_at_tri_invalid: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
@@ -113,102 +121,35 @@ impl EnergyDemandTelemetry {
     }
 }
 
+// Valores que os medidores enviam no registrador de energia quando não conseguem ler o contador
+const ENERGY_COUNTER_ERROR_VALUES: [f64; 3] = [65535.0, 1845494299.0, 2147483647.0];
 
+/// Aplica as fórmulas trazidas no campo "formulas" da própria telemetria (usado pelas telemetrias de DRI).
 pub fn calculate_formulas(param: &str, value: f64, tel: &Value, is_ieee754_fp: bool) -> f64 {
-    let computed_value = {
-        if is_ieee754_fp {
-            return f32::from_bits(value as u32) as f64;
-        }
-        value
-    };
-
-    let formula = tel.get("formulas")
-        .and_then(|f| f.as_object())
-        .and_then(|f| f.get(param))
-        .and_then(|f| f.as_str());
+    apply_formula(&formulas_from_telemetry(tel), param, value, tel, is_ieee754_fp)
+}
 
-    if formula.is_none() {
-        return computed_value;
+fn apply_formula(formulas: &FormulaSet, param: &str, value: f64, tel: &Value, is_ieee754_fp: bool) -> f64 {
+    match formulas.evaluate(param, value, tel, is_ieee754_fp) {
+        Ok(result) => result,
+        Err(_) => value,
     }
+}
 
-    let formulaString = formula.unwrap();
-    let variables: Vec<String> = formulaString.match_indices("CMN").map(|(i, _)| {
-        let mut result = String::from("CMN");
-        for c in formulaString.get(i+3..).unwrap().chars() {
-            if !c.is_numeric() {
-                break;
-            }
-            result.push_str(c.to_string().as_str());
-        }
-        result
-    }).collect();
-
-    let expr: meval::Expr = format!("X{}", formulaString).parse().unwrap();
-    if variables.is_empty() {
-        if let Ok(func) = expr.clone().bind("X") {
-            return func(computed_value);
-        }
-    }
-    if variables.len() == 1 {
-        let var = "X";
-        let var0 = variables[0].as_str();
-        let var0_value = tel.get(var0).and_then(|f| f.as_f64());
-        if var0_value.is_none() {
-            if (param == "en_at_tri") {
-                return -1.0;
-            } else {
-                return computed_value;
-            }
-        }
-        let var0_value_calc = calculate_formulas(var0, var0_value.unwrap(), &tel, is_ieee754_fp);
-        if let Ok(func) = expr.clone().bind2(var, var0) {
-            return func(computed_value, var0_value_calc);
-        }
-    }
-    if variables.len() == 2 {
-        let var = "X";
-        let var0 = variables[0].as_str();
-        let var0_value = tel.get(var0).and_then(|f| f.as_f64());
-        let var1 = variables[1].as_str();
-        let var1_value = tel.get(var1).and_then(|f| f.as_f64());
-        if var0_value.is_none() || var1_value.is_none() {
-            if param == "en_at_tri" {
-                return -1.0;
-            } else {
-                return computed_value;
-            }
-        }
-        let var0_value_calc = calculate_formulas(var0, var0_value.unwrap(), &tel, is_ieee754_fp);
-        let var1_value_calc = calculate_formulas(var1, var1_value.unwrap(), &tel, is_ieee754_fp);
-        if let Ok(func) = expr.clone().bind3(var, var0, var1) {
-            return func(computed_value, var0_value_calc, var1_value_calc);
-        }
+/// Energia ativa acumulada da leitura, None se a leitura for inválida: valor de erro do medidor,
+/// fórmula sem os registradores de que depende (não há como saber a energia acumulada) ou resultado negativo.
+fn active_energy_reading(formulas: &FormulaSet, value: f64, tel: &Value, is_ieee754_fp: bool) -> Option<f64> {
+    if ENERGY_COUNTER_ERROR_VALUES.contains(&value) {
+        return None;
     }
-    if variables.len() == 3 {
-        let var = "X";
-        let var0 = variables[0].as_str();
-        let var0_value = tel.get(var0).and_then(|f| f.as_f64());
-        let var1 = variables[1].as_str();
-        let var1_value = tel.get(var1).and_then(|f| f.as_f64());
-        let var2 = variables[2].as_str();
-        let var2_value = tel.get(var2).and_then(|f| f.as_f64());
 
-        if var0_value.is_none() || var1_value.is_none() || var2_value.is_none() {
-            if param == "en_at_tri" {
-                return -1.0;
-            } else {
-                return computed_value;
-            }
-        }
-        let var0_value_calc = calculate_formulas(var0, var0_value.unwrap(), tel, is_ieee754_fp);
-        let var1_value_calc = calculate_formulas(var1, var1_value.unwrap(), tel, is_ieee754_fp);
-        let var2_value_calc = calculate_formulas(var2, var2_value.unwrap(), tel, is_ieee754_fp);
-        if let Ok(func) = expr.bind4(var, var0, var1, var2) {
-            return func(computed_value, var0_value_calc, var1_value_calc, var2_value_calc);
-        }
-    }
+    let result = match formulas.evaluate("en_at_tri", value, tel, is_ieee754_fp) {
+        Ok(result) => result,
+        Err(FormulaError::MissingInput { .. }) => return None,
+        Err(_) => value,
+    };
 
-    computed_value
+    (result >= 0.0).then_some(result)
 }
 
 fn check_parameter(value: Option<f64>, options: &Vec<String>, option: &String) -> Option<f64> {
@@ -239,6 +180,7 @@ pub fn format_padronized_energy_temeletry (tel: PadronizedEnergyTelemetry, optio
     }
     PadronizedEnergyTelemetry {
         timestamp: tel.timestamp,
+        en_at_tri_invalid: tel.en_at_tri_invalid && options.unwrap().contains(&"en_at_tri".to_string()),
         v_a: check_parameter(tel.v_a, &options.as_ref().unwrap(), &"v_a".to_string()),
         v_b: check_parameter(tel.v_b, &options.as_ref().unwrap(), &"v_b".to_string()),
         v_c: check_parameter(tel.v_c, &options.as_ref().unwrap(), &"v_c".to_string()),
@@ -281,6 +223,14 @@ pub fn format_padronized_energy_temeletry (tel: PadronizedEnergyTelemetry, optio
 impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
     type Error = String;
     fn try_from(value: TelemetryDME) -> Result<PadronizedEnergyTelemetry, String> {
+        let formulas = compile_device_formulas(value.formulas.as_ref(), value.dev_id.as_ref());
+        PadronizedEnergyTelemetry::from_dme(value, &formulas)
+    }
+}
+
+impl PadronizedEnergyTelemetry {
+    /// Converte a telemetria do DME usando as fórmulas já compiladas do dispositivo
+    pub fn from_dme(value: TelemetryDME, formulas: &FormulaSet) -> Result<PadronizedEnergyTelemetry, String> {
         let timestamp = NaiveDateTime::parse_from_str(value.timestamp.as_ref(), "%Y-%m-%dT%H:%M:%S")
             .map_err(|e| e.to_string())?;
         let tel = json!(value);
@@ -300,154 +250,141 @@ impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
             _ => false,
         };
 
+        // Externo None: telemetria sem o registrador; interno None: leitura presente mas inválida
+        let en_at_tri_reading = match value.en_at_tri {
+            None => None,
+            Some(-1.0) => None,
+            // No PM2100 só os registradores de energia não vêm em IEEE 754
+            Some(raw) => Some(active_energy_reading(formulas, raw, &tel, is_ieee754_fp && !is_schneider_pm2100)),
+        };
+
         let result = PadronizedEnergyTelemetry {
             timestamp: Some(timestamp),
             v_a: match value.v_a {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_a", value.v_a.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_a", value.v_a.unwrap(), &tel, is_ieee754_fp)),
             },
             v_b: match value.v_b {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_b", value.v_b.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_b", value.v_b.unwrap(), &tel, is_ieee754_fp)),
             },
             v_c: match value.v_c {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_c", value.v_c.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_c", value.v_c.unwrap(), &tel, is_ieee754_fp)),
             },
             v_ab: match value.v_ab {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_ab", value.v_ab.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_ab", value.v_ab.unwrap(), &tel, is_ieee754_fp)),
             },
             v_bc: match value.v_bc {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_bc", value.v_bc.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_bc", value.v_bc.unwrap(), &tel, is_ieee754_fp)),
             },
             v_ca: match value.v_ca {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_ca", value.v_ca.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_ca", value.v_ca.unwrap(), &tel, is_ieee754_fp)),
             },
             i_a: match value.i_a {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("i_a", value.i_a.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "i_a", value.i_a.unwrap(), &tel, is_ieee754_fp)),
             },
             i_b: match value.i_b {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("i_b", value.i_b.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "i_b", value.i_b.unwrap(), &tel, is_ieee754_fp)),
             },
             i_c: match value.i_c {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("i_c", value.i_c.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "i_c", value.i_c.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_at_a: match value.pot_at_a {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_at_a", value.pot_at_a.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_at_a", value.pot_at_a.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_at_b: match value.pot_at_b {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_at_b", value.pot_at_b.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_at_b", value.pot_at_b.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_at_c: match value.pot_at_c {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_at_c", value.pot_at_c.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_at_c", value.pot_at_c.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_ap_a: match value.pot_ap_a {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_ap_a", value.pot_ap_a.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_ap_a", value.pot_ap_a.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_ap_b: match value.pot_ap_b {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_ap_b", value.pot_ap_b.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_ap_b", value.pot_ap_b.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_ap_c: match value.pot_ap_c {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_ap_c", value.pot_ap_c.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_ap_c", value.pot_ap_c.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_re_a: match value.pot_re_a {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_re_a", value.pot_re_a.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_re_a", value.pot_re_a.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_re_b: match value.pot_re_b {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_re_b", value.pot_re_b.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_re_b", value.pot_re_b.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_re_c: match value.pot_re_c {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_re_c", value.pot_re_c.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_re_c", value.pot_re_c.unwrap(), &tel, is_ieee754_fp)),
             },
             v_tri_ln: match value.v_tri_ln {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_tri_ln", value.v_tri_ln.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_tri_ln", value.v_tri_ln.unwrap(), &tel, is_ieee754_fp)),
             },
             v_tri_ll: match value.v_tri_ll {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("v_tri_ll", value.v_tri_ll.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "v_tri_ll", value.v_tri_ll.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_at_tri: match value.pot_at_tri {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_at_tri", value.pot_at_tri.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_at_tri", value.pot_at_tri.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_ap_tri: match value.pot_ap_tri {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_ap_tri", value.pot_ap_tri.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "pot_ap_tri", value.pot_ap_tri.unwrap(), &tel, is_ieee754_fp)),
             },
             pot_re_tri: match value.pot_re_tri {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("pot_re_tri", value.pot_re_tri.unwrap(), &tel, is_ieee754_fp)),
-            },
-            en_at_tri: match value.en_at_tri {
-                None => None,
-                Some(-1.0) => None,
-                _ => {
-                    if value.en_at_tri == Some(65535.0) || value.en_at_tri == Some(1845494299.0) || value.en_at_tri == Some(2147483647.0) {
-                        Some(-1.0)
-                    } else {
-                        let result = if is_schneider_pm2100 {
-                            calculate_formulas("en_at_tri", value.en_at_tri.unwrap(), &tel, false)
-                        } else {
-                            calculate_formulas("en_at_tri", value.en_at_tri.unwrap(), &tel, is_ieee754_fp)
-                        };
-                        
-                        let final_result = if result < 0.0 {
-                            Some(-1.0)
-                        } else {
-                            Some(result)
-                        };
-
-                        final_result
-                    }
-                },
+                _ => Some(apply_formula(formulas, "pot_re_tri", value.pot_re_tri.unwrap(), &tel, is_ieee754_fp)),
             },
+            en_at_tri: en_at_tri_reading.flatten(),
+            en_at_tri_invalid: en_at_tri_reading == Some(None),
             en_re_tri: match value.en_re_tri {
                 None => None,
                 Some(-1.0) => None,
                 _ => {
                     if is_schneider_pm2100 {
-                        Some(calculate_formulas("en_re_tri", value.en_re_tri.unwrap(), &tel, false))
+                        Some(apply_formula(formulas, "en_re_tri", value.en_re_tri.unwrap(), &tel, false))
                     } else  {
-                        Some(calculate_formulas("en_re_tri", value.en_re_tri.unwrap(), &tel, is_ieee754_fp))
+                        Some(apply_formula(formulas, "en_re_tri", value.en_re_tri.unwrap(), &tel, is_ieee754_fp))
                     }
                 }
             },
@@ -455,14 +392,14 @@ impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
                 None => None,
                 Some(-1.0) => None,
                 _ => {
-                    Some(calculate_formulas("en_ap_tri", value.en_ap_tri.unwrap(), &tel, is_ieee754_fp))
+                    Some(apply_formula(formulas, "en_ap_tri", value.en_ap_tri.unwrap(), &tel, is_ieee754_fp))
                 }
             },
             fp_a: match value.fp_a {
                 None => None,
                 Some(-1.0) => None,
                 _ => {
-                    let value = calculate_formulas("fp_a", value.fp_a.unwrap(), &tel, is_ieee754_fp);
+                    let value = apply_formula(formulas, "fp_a", value.fp_a.unwrap(), &tel, is_ieee754_fp);
                     if is_schneider_pm2100 {
                         Some(convert_4Q_FP_PF(value))
                     } else {
@@ -474,7 +411,7 @@ impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
                 None => None,
                 Some(-1.0) => None,
                 _ => {
-                    let value = calculate_formulas("fp_b", value.fp_b.unwrap(), &tel, is_ieee754_fp);
+                    let value = apply_formula(formulas, "fp_b", value.fp_b.unwrap(), &tel, is_ieee754_fp);
                     if is_schneider_pm2100 {
                         Some(convert_4Q_FP_PF(value))
                     } else {
@@ -485,7 +422,7 @@ impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
                 None => None,
                 Some(-1.0) => None,
                 _ => {
-                    let value = calculate_formulas("fp_c", value.fp_c.unwrap(), &tel, is_ieee754_fp);
+                    let value = apply_formula(formulas, "fp_c", value.fp_c.unwrap(), &tel, is_ieee754_fp);
                     if is_schneider_pm2100 {
                         Some(convert_4Q_FP_PF(value))
                     } else {
@@ -497,7 +434,7 @@ impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
                 None => None,
                 Some(-1.0) => None,
                 _ => {
-                    let value = calculate_formulas("fp", value.fp.unwrap(), &tel, is_ieee754_fp);
+                    let value = apply_formula(formulas, "fp", value.fp.unwrap(), &tel, is_ieee754_fp);
                     if is_schneider_pm2100 {
                         Some(convert_4Q_FP_PF(value))
                     } else {
@@ -507,32 +444,32 @@ impl<'a> TryFrom<TelemetryDME<'a>> for PadronizedEnergyTelemetry {
             freq: match value.freq {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("freq", value.freq.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "freq", value.freq.unwrap(), &tel, is_ieee754_fp)),
             },
             demanda: match value.demanda {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("demanda", value.demanda.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "demanda", value.demanda.unwrap(), &tel, is_ieee754_fp)),
             },
             demanda_at: match value.demanda_at {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("demanda_at", value.demanda_at.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "demanda_at", value.demanda_at.unwrap(), &tel, is_ieee754_fp)),
             },
             demanda_ap: match value.demanda_ap {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("demanda_ap", value.demanda_ap.unwrap(), &tel, is_ieee754_fp)),
+                _ => Some(apply_formula(formulas, "demanda_ap", value.demanda_ap.unwrap(), &tel, is_ieee754_fp)),
             },
             demanda_med_at: match value.demanda_med_at {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("demanda_med_at", value.demanda_med_at.unwrap(), &tel, is_ieee754_fp))
+                _ => Some(apply_formula(formulas, "demanda_med_at", value.demanda_med_at.unwrap(), &tel, is_ieee754_fp))
             },
             erro: match value.erro {
                 None => None,
                 Some(-1.0) => None,
-                _ => Some(calculate_formulas("erro", value.erro.unwrap(), &tel, false)),
+                _ => Some(apply_formula(formulas, "erro", value.erro.unwrap(), &tel, false)),
             },
         };
         Ok(result)
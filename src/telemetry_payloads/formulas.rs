@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde_json::Value;
+
+use crate::schedules::scheduler::write_to_log_file_thread;
+
+// Variável que recebe o valor do próprio registrador: a fórmula "*CMN3/1000" vira "X*CMN3/1000"
+const SELF_VARIABLE: &str = "X";
+// Constantes do meval que não são registradores da telemetria
+const BUILTIN_CONSTANTS: [&str; 2] = ["pi", "e"];
+// Limite de fórmulas encadeadas (CMN1 usa CMN2 que usa CMN3...), protege contra referências circulares
+const MAX_FORMULA_DEPTH: usize = 8;
+// Conjuntos distintos de fórmulas mantidos em memória por formulas_from_telemetry
+const MAX_CACHED_FORMULA_SETS: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaError {
+    /// A fórmula não é uma expressão válida
+    Parse { param: String, formula: String, message: String },
+    /// A telemetria não tem o registrador usado pela fórmula
+    MissingInput { param: String, variable: String },
+    /// Fórmulas que dependem umas das outras em ciclo
+    CircularReference { param: String },
+    Evaluation { param: String, message: String },
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormulaError::Parse { param, formula, message } => write!(f, "Fórmula inválida para {} ({}): {}", param, formula, message),
+            FormulaError::MissingInput { param, variable } => write!(f, "Fórmula de {} depende de {}, ausente na telemetria", param, variable),
+            FormulaError::CircularReference { param } => write!(f, "Referência circular nas fórmulas a partir de {}", param),
+            FormulaError::Evaluation { param, message } => write!(f, "Erro ao calcular a fórmula de {}: {}", param, message),
+        }
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+#[derive(Debug, Clone)]
+pub struct CompiledFormula {
+    pub formula: String,
+    expr: meval::Expr,
+    variables: Vec<String>,
+}
+
+impl CompiledFormula {
+    pub fn compile(param: &str, formula: &str) -> Result<Self, FormulaError> {
+        let expr: meval::Expr = format!("{}{}", SELF_VARIABLE, formula).parse()
+            .map_err(|err: meval::Error| FormulaError::Parse { param: param.to_owned(), formula: formula.to_owned(), message: err.to_string() })?;
+
+        Ok(CompiledFormula {
+            formula: formula.to_owned(),
+            expr,
+            variables: extract_variables(formula),
+        })
+    }
+
+    /// Registradores da telemetria usados pela fórmula, sem contar o próprio valor (X)
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+}
+
+/// Fórmulas de um dispositivo (registrador -> fórmula), compiladas uma única vez.
+#[derive(Debug, Clone, Default)]
+pub struct FormulaSet {
+    formulas: HashMap<String, CompiledFormula>,
+}
+
+impl FormulaSet {
+    /// Compila cada fórmula separadamente, devolvendo todos os erros encontrados
+    pub fn compile_all(formulas: &HashMap<String, String>) -> (Self, Vec<FormulaError>) {
+        let mut compiled = HashMap::new();
+        let mut errors = Vec::new();
+        for (param, formula) in formulas {
+            match CompiledFormula::compile(param, formula) {
+                Ok(formula) => { compiled.insert(param.clone(), formula); },
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (FormulaSet { formulas: compiled }, errors)
+    }
+
+    pub fn get(&self, param: &str) -> Option<&CompiledFormula> {
+        self.formulas.get(param)
+    }
+
+    /// Aplica a fórmula do registrador `param` ao `value` lido. Sem fórmula, o valor é devolvido como está.
+    /// Registradores IEEE 754 são apenas decodificados, sem aplicar fórmula.
+    pub fn evaluate(&self, param: &str, value: f64, tel: &Value, is_ieee754_fp: bool) -> Result<f64, FormulaError> {
+        self.evaluate_with_depth(param, value, tel, is_ieee754_fp, 0)
+    }
+
+    fn evaluate_with_depth(&self, param: &str, value: f64, tel: &Value, is_ieee754_fp: bool, depth: usize) -> Result<f64, FormulaError> {
+        if is_ieee754_fp {
+            return Ok(f32::from_bits(value as u32) as f64);
+        }
+
+        let formula = match self.formulas.get(param) {
+            Some(formula) => formula,
+            None => return Ok(value),
+        };
+
+        if depth >= MAX_FORMULA_DEPTH {
+            return Err(FormulaError::CircularReference { param: param.to_owned() });
+        }
+
+        let mut ctx = meval::Context::new();
+        ctx.var(SELF_VARIABLE, value);
+        for variable in &formula.variables {
+            let raw = tel.get(variable).and_then(Value::as_f64)
+                .ok_or_else(|| FormulaError::MissingInput { param: param.to_owned(), variable: variable.clone() })?;
+            let variable_value = self.evaluate_with_depth(variable, raw, tel, is_ieee754_fp, depth + 1)?;
+            ctx.var(variable.clone(), variable_value);
+        }
+
+        formula.expr.eval_with_context(ctx)
+            .map_err(|err| FormulaError::Evaluation { param: param.to_owned(), message: err.to_string() })
+    }
+}
+
+/// Compila as fórmulas cadastradas do dispositivo. Fórmulas inválidas são registradas no log e ignoradas,
+/// para que os demais registradores do dispositivo continuem sendo processados.
+pub fn compile_device_formulas(formulas: Option<&HashMap<String, String>>, dev_id: &str) -> FormulaSet {
+    let formulas = match formulas {
+        Some(formulas) => formulas,
+        None => return FormulaSet::default(),
+    };
+
+    let (compiled, errors) = FormulaSet::compile_all(formulas);
+    for err in errors {
+        write_to_log_file_thread(&format!("{}, dispositivo {}", err, dev_id), 0, "ERROR");
+    }
+
+    compiled
+}
+
+/// Conjunto de fórmulas compilado a partir do campo "formulas" da telemetria, reaproveitado entre telemetrias
+/// do mesmo dispositivo. Fórmulas inválidas são registradas no log uma única vez e ignoradas.
+pub fn formulas_from_telemetry(tel: &Value) -> Arc<FormulaSet> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Arc<FormulaSet>>>> = OnceLock::new();
+
+    let formulas = match tel.get("formulas").and_then(Value::as_object) {
+        Some(formulas) if !formulas.is_empty() => formulas,
+        _ => return Arc::new(FormulaSet::default()),
+    };
+
+    let key = Value::Object(formulas.clone()).to_string();
+    let mut cache = CACHE.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    if let Some(compiled) = cache.get(&key) {
+        return compiled.clone();
+    }
+
+    let formulas: HashMap<String, String> = formulas.iter()
+        .filter_map(|(param, formula)| formula.as_str().map(|formula| (param.clone(), formula.to_owned())))
+        .collect();
+    let (compiled, errors) = FormulaSet::compile_all(&formulas);
+    for err in errors {
+        write_to_log_file_thread(&err.to_string(), 0, "ERROR");
+    }
+
+    // As fórmulas mudam quando o cadastro do dispositivo é alterado, então o cache é esvaziado ao atingir o limite
+    if cache.len() >= MAX_CACHED_FORMULA_SETS {
+        cache.clear();
+    }
+    let compiled = Arc::new(compiled);
+    cache.insert(key, compiled.clone());
+    compiled
+}
+
+/// Identificadores da expressão que não são funções, constantes nem o próprio valor
+pub fn extract_variables(formula: &str) -> Vec<String> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut variables: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit() || c == '.' {
+            // Números, incluindo notação científica (1e3, 2.5E-2)
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            let is_function = chars[i..].iter().find(|c| !c.is_whitespace()) == Some(&'(');
+            if !is_function && name != SELF_VARIABLE && !BUILTIN_CONSTANTS.contains(&name.as_str()) && !variables.contains(&name) {
+                variables.push(name);
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn formula_set(formulas: &[(&str, &str)]) -> (FormulaSet, Vec<FormulaError>) {
+        let formulas: HashMap<String, String> = formulas.iter().map(|(param, formula)| (param.to_string(), formula.to_string())).collect();
+        FormulaSet::compile_all(&formulas)
+    }
+
+    #[test]
+    fn evaluate_uses_more_than_three_registers() {
+        let (formulas, errors) = formula_set(&[("CMN1", "+C1+C2+C3+C4*CMN10")]);
+        assert!(errors.is_empty());
+        assert_eq!(formulas.get("CMN1").unwrap().variables(), ["C1", "C2", "C3", "C4", "CMN10"]);
+
+        let tel = json!({ "C1": 1.0, "C2": 2.0, "C3": 3.0, "C4": 4.0, "CMN10": 10.0 });
+        assert_eq!(formulas.evaluate("CMN1", 5.0, &tel, false), Ok(5.0 + 1.0 + 2.0 + 3.0 + 40.0));
+        // Registrador sem fórmula volta como está
+        assert_eq!(formulas.evaluate("CMN2", 7.0, &tel, false), Ok(7.0));
+    }
+
+    #[test]
+    fn evaluate_applies_chained_formulas_to_the_inputs() {
+        let (formulas, _) = formula_set(&[("CMN1", "+CMN2"), ("CMN2", "*10")]);
+        assert_eq!(formulas.evaluate("CMN1", 1.0, &json!({ "CMN2": 3.0 }), false), Ok(31.0));
+    }
+
+    #[test]
+    fn missing_input_is_reported_with_the_register() {
+        let (formulas, _) = formula_set(&[("CMN1", "*CMN3/1000")]);
+        let result = formulas.evaluate("CMN1", 5.0, &json!({ "CMN2": 1.0 }), false);
+        assert_eq!(result, Err(FormulaError::MissingInput { param: "CMN1".to_owned(), variable: "CMN3".to_owned() }));
+    }
+
+    #[test]
+    fn circular_reference_stops_at_the_depth_limit() {
+        let (formulas, errors) = formula_set(&[("CMN1", "+CMN2"), ("CMN2", "+CMN1")]);
+        assert!(errors.is_empty());
+
+        let result = formulas.evaluate("CMN1", 1.0, &json!({ "CMN1": 1.0, "CMN2": 2.0 }), false);
+        assert!(matches!(result, Err(FormulaError::CircularReference { .. })), "{:?}", result);
+    }
+
+    #[test]
+    fn compile_all_keeps_valid_formulas_and_reports_parse_errors() {
+        let (formulas, errors) = formula_set(&[("CMN1", "*2"), ("CMN2", "*(3+"), ("CMN3", "//4")]);
+
+        assert!(formulas.get("CMN1").is_some());
+        assert!(formulas.get("CMN2").is_none());
+        assert!(formulas.get("CMN3").is_none());
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|err| matches!(err, FormulaError::Parse { .. })));
+        assert_eq!(formulas.evaluate("CMN1", 4.0, &json!({}), false), Ok(8.0));
+    }
+
+    #[test]
+    fn extract_variables_reads_whole_register_names() {
+        assert_eq!(extract_variables("*C12/CMN10"), ["C12", "CMN10"]);
+        // Números em notação científica, funções, constantes e o próprio valor não são registradores
+        assert_eq!(extract_variables("*1e3+2.5E-2*sqrt(C12)+pi*e-X+C12"), ["C12"]);
+        assert!(extract_variables("/1000").is_empty());
+    }
+}
@@ -17,3 +17,4 @@ pub mod dal_payload_json;
 pub mod dal_telemetry;
 pub mod dam_payload_json;
 pub mod dam_telemetry;
+pub mod formulas;